@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS pending_edit
+(
+    id             INTEGER NOT NULL,
+    kind           TEXT    NOT NULL CHECK (kind IN ('add', 'edit', 'delete')),
+    episode_number REAL    NOT NULL,
+    event_id       TEXT,
+    timestamp      INTEGER,
+    description    TEXT,
+    reason         TEXT,
+    status         TEXT    NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'rejected')),
+    submitted_at   BIGINT  NOT NULL,
+    reviewed_at    BIGINT,
+    reviewer       TEXT,
+    review_note    TEXT,
+    PRIMARY KEY (id AUTOINCREMENT),
+    FOREIGN KEY (episode_number) REFERENCES pka_episode (number) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS pending_edit_status ON pending_edit (status);
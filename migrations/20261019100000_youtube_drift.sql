@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS youtube_drift
+(
+    video_id          TEXT   NOT NULL,
+    episode_number    REAL   NOT NULL,
+    kind              TEXT   NOT NULL CHECK (kind IN ('title', 'duration', 'description', 'unavailable')),
+    stored_value      TEXT,
+    current_value     TEXT,
+    first_detected_at BIGINT NOT NULL,
+    last_checked_at   BIGINT NOT NULL,
+    PRIMARY KEY (video_id, kind),
+    FOREIGN KEY (episode_number) REFERENCES pka_episode (number) ON DELETE CASCADE
+);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS ingestion_log
+(
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    episode_number  REAL    NOT NULL,
+    video_id        TEXT    NOT NULL,
+    attempted_at    BIGINT  NOT NULL,
+    outcome         TEXT    NOT NULL CHECK (outcome IN ('succeeded', 'failed')),
+    events_inserted INTEGER NOT NULL DEFAULT 0,
+    error           TEXT
+);
+
+CREATE INDEX IF NOT EXISTS ingestion_log_attempted_at ON ingestion_log (attempted_at);
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS youtube_quota
+(
+    quota_day  TEXT    NOT NULL PRIMARY KEY,
+    units_used INTEGER NOT NULL DEFAULT 0
+);
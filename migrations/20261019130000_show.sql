@@ -0,0 +1,144 @@
+CREATE TABLE IF NOT EXISTS show
+(
+    slug                 TEXT    NOT NULL,
+    name                 TEXT    NOT NULL,
+    -- Prefix for generated episode names, e.g. `PKA` gives `PKA 481`.
+    episode_prefix       TEXT    NOT NULL,
+    playlist_id          TEXT    NOT NULL,
+    -- Must capture the episode number in a group named `number`.
+    title_regex          TEXT    NOT NULL,
+    -- Where ingestion starts for a show without any stored episodes.
+    first_episode_number INTEGER NOT NULL DEFAULT 1,
+    enabled              BOOLEAN NOT NULL DEFAULT TRUE,
+    PRIMARY KEY (slug)
+);
+
+INSERT OR IGNORE INTO show (slug, name, episode_prefix, playlist_id, title_regex, first_episode_number)
+VALUES ('pka', 'Painkiller Already', 'PKA', 'UUIPVJoHb_A5S3kcv3TJlyEg', '(?i)PKA\s*(?P<number>\d{3,})', 1);
+
+-- Episodes are keyed by (show_slug, number) from here on, so every table referencing an
+-- episode is rebuilt with the show included in its foreign key. Existing rows belong to PKA.
+
+CREATE TABLE pka_episode_new
+(
+    show_slug    TEXT   NOT NULL DEFAULT 'pka',
+    number       REAL   NOT NULL,
+    name         TEXT   NOT NULL,
+    youtube_link TEXT   NOT NULL,
+    upload_date  BIGINT NOT NULL,
+    PRIMARY KEY (show_slug, number),
+    FOREIGN KEY (show_slug) REFERENCES show (slug)
+);
+
+INSERT INTO pka_episode_new (show_slug, number, name, youtube_link, upload_date)
+SELECT 'pka', number, name, youtube_link, upload_date
+FROM pka_episode;
+
+CREATE TABLE pka_guest_new
+(
+    name           TEXT NOT NULL,
+    show_slug      TEXT NOT NULL DEFAULT 'pka',
+    episode_number REAL NOT NULL,
+    PRIMARY KEY (name),
+    FOREIGN KEY (show_slug, episode_number) REFERENCES pka_episode_new (show_slug, number) ON DELETE CASCADE
+);
+
+INSERT INTO pka_guest_new (name, show_slug, episode_number)
+SELECT name, 'pka', episode_number
+FROM pka_guest;
+
+CREATE TABLE pka_event_new
+(
+    event_id       TEXT    NOT NULL,
+    show_slug      TEXT    NOT NULL DEFAULT 'pka',
+    episode_number REAL    NOT NULL,
+    timestamp      INTEGER NOT NULL,
+    description    TEXT    NOT NULL,
+    length_seconds INTEGER NOT NULL,
+    upload_date    BIGINT  NOT NULL,
+    PRIMARY KEY (event_id),
+    FOREIGN KEY (show_slug, episode_number) REFERENCES pka_episode_new (show_slug, number) ON DELETE CASCADE
+);
+
+INSERT INTO pka_event_new (event_id, show_slug, episode_number, timestamp, description, length_seconds, upload_date)
+SELECT event_id, 'pka', episode_number, timestamp, description, length_seconds, upload_date
+FROM pka_event;
+
+CREATE TABLE pka_youtube_details_new
+(
+    video_id       TEXT    NOT NULL,
+    show_slug      TEXT    NOT NULL DEFAULT 'pka',
+    episode_number REAL    NOT NULL,
+    title          TEXT    NOT NULL,
+    length_seconds INTEGER NOT NULL,
+    PRIMARY KEY (video_id),
+    FOREIGN KEY (show_slug, episode_number) REFERENCES pka_episode_new (show_slug, number) ON DELETE CASCADE
+);
+
+INSERT INTO pka_youtube_details_new (video_id, show_slug, episode_number, title, length_seconds)
+SELECT video_id, 'pka', episode_number, title, length_seconds
+FROM pka_youtube_details;
+
+CREATE TABLE pending_edit_new
+(
+    id             INTEGER NOT NULL,
+    kind           TEXT    NOT NULL CHECK (kind IN ('add', 'edit', 'delete')),
+    show_slug      TEXT    NOT NULL DEFAULT 'pka',
+    episode_number REAL    NOT NULL,
+    event_id       TEXT,
+    timestamp      INTEGER,
+    description    TEXT,
+    reason         TEXT,
+    status         TEXT    NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'rejected')),
+    submitted_at   BIGINT  NOT NULL,
+    reviewed_at    BIGINT,
+    reviewer       TEXT,
+    review_note    TEXT,
+    PRIMARY KEY (id AUTOINCREMENT),
+    FOREIGN KEY (show_slug, episode_number) REFERENCES pka_episode_new (show_slug, number) ON DELETE CASCADE
+);
+
+INSERT INTO pending_edit_new (id, kind, show_slug, episode_number, event_id, timestamp, description, reason, status,
+                              submitted_at, reviewed_at, reviewer, review_note)
+SELECT id, kind, 'pka', episode_number, event_id, timestamp, description, reason, status,
+       submitted_at, reviewed_at, reviewer, review_note
+FROM pending_edit;
+
+CREATE TABLE youtube_drift_new
+(
+    video_id          TEXT   NOT NULL,
+    show_slug         TEXT   NOT NULL DEFAULT 'pka',
+    episode_number    REAL   NOT NULL,
+    kind              TEXT   NOT NULL CHECK (kind IN ('title', 'duration', 'description', 'unavailable')),
+    stored_value      TEXT,
+    current_value     TEXT,
+    first_detected_at BIGINT NOT NULL,
+    last_checked_at   BIGINT NOT NULL,
+    PRIMARY KEY (video_id, kind),
+    FOREIGN KEY (show_slug, episode_number) REFERENCES pka_episode_new (show_slug, number) ON DELETE CASCADE
+);
+
+INSERT INTO youtube_drift_new (video_id, show_slug, episode_number, kind, stored_value, current_value,
+                               first_detected_at, last_checked_at)
+SELECT video_id, 'pka', episode_number, kind, stored_value, current_value, first_detected_at, last_checked_at
+FROM youtube_drift;
+
+DROP TABLE pka_guest;
+DROP TABLE pka_event;
+DROP TABLE pka_youtube_details;
+DROP TABLE pending_edit;
+DROP TABLE youtube_drift;
+DROP TABLE pka_episode;
+
+-- Renaming the parent also rewrites the foreign keys in the new child tables.
+ALTER TABLE pka_episode_new RENAME TO pka_episode;
+ALTER TABLE pka_guest_new RENAME TO pka_guest;
+ALTER TABLE pka_event_new RENAME TO pka_event;
+ALTER TABLE pka_youtube_details_new RENAME TO pka_youtube_details;
+ALTER TABLE pending_edit_new RENAME TO pending_edit;
+ALTER TABLE youtube_drift_new RENAME TO youtube_drift;
+
+CREATE INDEX IF NOT EXISTS pka_event_episode ON pka_event (show_slug, episode_number);
+CREATE INDEX IF NOT EXISTS pending_edit_status ON pending_edit (status);
+
+ALTER TABLE ingestion_log ADD COLUMN show_slug TEXT NOT NULL DEFAULT 'pka';
@@ -0,0 +1,205 @@
+-- Episode numbers were stored as REAL, which made half-episodes such as 481.5 depend on float
+-- equality. They are now an INTEGER number plus a single-digit part, where 0 means the whole
+-- episode, so `481.5` is stored as (481, 5) and `481` as (481, 0).
+
+CREATE TABLE pka_episode_new
+(
+    show_slug    TEXT    NOT NULL DEFAULT 'pka',
+    number       INTEGER NOT NULL,
+    part         INTEGER NOT NULL DEFAULT 0 CHECK (part BETWEEN 0 AND 9),
+    name         TEXT    NOT NULL,
+    youtube_link TEXT    NOT NULL,
+    upload_date  BIGINT  NOT NULL,
+    PRIMARY KEY (show_slug, number, part),
+    FOREIGN KEY (show_slug) REFERENCES show (slug)
+);
+
+INSERT INTO pka_episode_new (show_slug, number, part, name, youtube_link, upload_date)
+SELECT show_slug,
+       CAST(number AS INTEGER),
+       CAST(ROUND((number - CAST(number AS INTEGER)) * 10) AS INTEGER),
+       name,
+       youtube_link,
+       upload_date
+FROM pka_episode;
+
+CREATE TABLE pka_guest_new
+(
+    name           TEXT    NOT NULL,
+    show_slug      TEXT    NOT NULL DEFAULT 'pka',
+    episode_number INTEGER NOT NULL,
+    episode_part   INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (name),
+    FOREIGN KEY (show_slug, episode_number, episode_part)
+        REFERENCES pka_episode_new (show_slug, number, part) ON DELETE CASCADE
+);
+
+INSERT INTO pka_guest_new (name, show_slug, episode_number, episode_part)
+SELECT name,
+       show_slug,
+       CAST(episode_number AS INTEGER),
+       CAST(ROUND((episode_number - CAST(episode_number AS INTEGER)) * 10) AS INTEGER)
+FROM pka_guest;
+
+CREATE TABLE pka_event_new
+(
+    event_id       TEXT    NOT NULL,
+    show_slug      TEXT    NOT NULL DEFAULT 'pka',
+    episode_number INTEGER NOT NULL,
+    episode_part   INTEGER NOT NULL DEFAULT 0,
+    timestamp      INTEGER NOT NULL,
+    description    TEXT    NOT NULL,
+    length_seconds INTEGER NOT NULL,
+    upload_date    BIGINT  NOT NULL,
+    PRIMARY KEY (event_id),
+    FOREIGN KEY (show_slug, episode_number, episode_part)
+        REFERENCES pka_episode_new (show_slug, number, part) ON DELETE CASCADE
+);
+
+INSERT INTO pka_event_new (event_id, show_slug, episode_number, episode_part, timestamp, description,
+                           length_seconds, upload_date)
+SELECT event_id,
+       show_slug,
+       CAST(episode_number AS INTEGER),
+       CAST(ROUND((episode_number - CAST(episode_number AS INTEGER)) * 10) AS INTEGER),
+       timestamp,
+       description,
+       length_seconds,
+       upload_date
+FROM pka_event;
+
+CREATE TABLE pka_youtube_details_new
+(
+    video_id       TEXT    NOT NULL,
+    show_slug      TEXT    NOT NULL DEFAULT 'pka',
+    episode_number INTEGER NOT NULL,
+    episode_part   INTEGER NOT NULL DEFAULT 0,
+    title          TEXT    NOT NULL,
+    length_seconds INTEGER NOT NULL,
+    PRIMARY KEY (video_id),
+    FOREIGN KEY (show_slug, episode_number, episode_part)
+        REFERENCES pka_episode_new (show_slug, number, part) ON DELETE CASCADE
+);
+
+INSERT INTO pka_youtube_details_new (video_id, show_slug, episode_number, episode_part, title, length_seconds)
+SELECT video_id,
+       show_slug,
+       CAST(episode_number AS INTEGER),
+       CAST(ROUND((episode_number - CAST(episode_number AS INTEGER)) * 10) AS INTEGER),
+       title,
+       length_seconds
+FROM pka_youtube_details;
+
+CREATE TABLE pending_edit_new
+(
+    id             INTEGER NOT NULL,
+    kind           TEXT    NOT NULL CHECK (kind IN ('add', 'edit', 'delete')),
+    show_slug      TEXT    NOT NULL DEFAULT 'pka',
+    episode_number INTEGER NOT NULL,
+    episode_part   INTEGER NOT NULL DEFAULT 0,
+    event_id       TEXT,
+    timestamp      INTEGER,
+    description    TEXT,
+    reason         TEXT,
+    status         TEXT    NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'rejected')),
+    submitted_at   BIGINT  NOT NULL,
+    reviewed_at    BIGINT,
+    reviewer       TEXT,
+    review_note    TEXT,
+    PRIMARY KEY (id AUTOINCREMENT),
+    FOREIGN KEY (show_slug, episode_number, episode_part)
+        REFERENCES pka_episode_new (show_slug, number, part) ON DELETE CASCADE
+);
+
+INSERT INTO pending_edit_new (id, kind, show_slug, episode_number, episode_part, event_id, timestamp, description,
+                              reason, status, submitted_at, reviewed_at, reviewer, review_note)
+SELECT id,
+       kind,
+       show_slug,
+       CAST(episode_number AS INTEGER),
+       CAST(ROUND((episode_number - CAST(episode_number AS INTEGER)) * 10) AS INTEGER),
+       event_id,
+       timestamp,
+       description,
+       reason,
+       status,
+       submitted_at,
+       reviewed_at,
+       reviewer,
+       review_note
+FROM pending_edit;
+
+CREATE TABLE youtube_drift_new
+(
+    video_id          TEXT    NOT NULL,
+    show_slug         TEXT    NOT NULL DEFAULT 'pka',
+    episode_number    INTEGER NOT NULL,
+    episode_part      INTEGER NOT NULL DEFAULT 0,
+    kind              TEXT    NOT NULL CHECK (kind IN ('title', 'duration', 'description', 'unavailable')),
+    stored_value      TEXT,
+    current_value     TEXT,
+    first_detected_at BIGINT  NOT NULL,
+    last_checked_at   BIGINT  NOT NULL,
+    PRIMARY KEY (video_id, kind),
+    FOREIGN KEY (show_slug, episode_number, episode_part)
+        REFERENCES pka_episode_new (show_slug, number, part) ON DELETE CASCADE
+);
+
+INSERT INTO youtube_drift_new (video_id, show_slug, episode_number, episode_part, kind, stored_value, current_value,
+                               first_detected_at, last_checked_at)
+SELECT video_id,
+       show_slug,
+       CAST(episode_number AS INTEGER),
+       CAST(ROUND((episode_number - CAST(episode_number AS INTEGER)) * 10) AS INTEGER),
+       kind,
+       stored_value,
+       current_value,
+       first_detected_at,
+       last_checked_at
+FROM youtube_drift;
+
+CREATE TABLE ingestion_log_new
+(
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    show_slug       TEXT    NOT NULL DEFAULT 'pka',
+    episode_number  INTEGER NOT NULL,
+    episode_part    INTEGER NOT NULL DEFAULT 0,
+    video_id        TEXT    NOT NULL,
+    attempted_at    BIGINT  NOT NULL,
+    outcome         TEXT    NOT NULL CHECK (outcome IN ('succeeded', 'failed')),
+    events_inserted INTEGER NOT NULL DEFAULT 0,
+    error           TEXT
+);
+
+INSERT INTO ingestion_log_new (id, show_slug, episode_number, episode_part, video_id, attempted_at, outcome,
+                               events_inserted, error)
+SELECT id,
+       show_slug,
+       CAST(episode_number AS INTEGER),
+       CAST(ROUND((episode_number - CAST(episode_number AS INTEGER)) * 10) AS INTEGER),
+       video_id,
+       attempted_at,
+       outcome,
+       events_inserted,
+       error
+FROM ingestion_log;
+
+DROP TABLE pka_guest;
+DROP TABLE pka_event;
+DROP TABLE pka_youtube_details;
+DROP TABLE pending_edit;
+DROP TABLE youtube_drift;
+DROP TABLE ingestion_log;
+DROP TABLE pka_episode;
+
+ALTER TABLE pka_episode_new RENAME TO pka_episode;
+ALTER TABLE pka_guest_new RENAME TO pka_guest;
+ALTER TABLE pka_event_new RENAME TO pka_event;
+ALTER TABLE pka_youtube_details_new RENAME TO pka_youtube_details;
+ALTER TABLE pending_edit_new RENAME TO pending_edit;
+ALTER TABLE youtube_drift_new RENAME TO youtube_drift;
+ALTER TABLE ingestion_log_new RENAME TO ingestion_log;
+
+CREATE INDEX IF NOT EXISTS pka_event_episode ON pka_event (show_slug, episode_number, episode_part);
+CREATE INDEX IF NOT EXISTS pending_edit_status ON pending_edit (status);
+CREATE INDEX IF NOT EXISTS ingestion_log_attempted_at ON ingestion_log (attempted_at);
@@ -0,0 +1,2 @@
+-- JSON array of problems found in the extracted timeline.
+ALTER TABLE ingestion_log ADD COLUMN warnings TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,19 @@
+-- Episode numbers in their text form, e.g. `481` or `481.5`, so queries can select them
+-- without repeating the expression.
+ALTER TABLE pka_episode ADD COLUMN label TEXT
+    GENERATED ALWAYS AS (number || CASE part WHEN 0 THEN '' ELSE '.' || part END) VIRTUAL;
+
+ALTER TABLE pka_event ADD COLUMN episode_label TEXT
+    GENERATED ALWAYS AS (episode_number || CASE episode_part WHEN 0 THEN '' ELSE '.' || episode_part END) VIRTUAL;
+
+ALTER TABLE pka_youtube_details ADD COLUMN episode_label TEXT
+    GENERATED ALWAYS AS (episode_number || CASE episode_part WHEN 0 THEN '' ELSE '.' || episode_part END) VIRTUAL;
+
+ALTER TABLE pending_edit ADD COLUMN episode_label TEXT
+    GENERATED ALWAYS AS (episode_number || CASE episode_part WHEN 0 THEN '' ELSE '.' || episode_part END) VIRTUAL;
+
+ALTER TABLE youtube_drift ADD COLUMN episode_label TEXT
+    GENERATED ALWAYS AS (episode_number || CASE episode_part WHEN 0 THEN '' ELSE '.' || episode_part END) VIRTUAL;
+
+ALTER TABLE ingestion_log ADD COLUMN episode_label TEXT
+    GENERATED ALWAYS AS (episode_number || CASE episode_part WHEN 0 THEN '' ELSE '.' || episode_part END) VIRTUAL;
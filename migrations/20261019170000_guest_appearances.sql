@@ -0,0 +1,21 @@
+-- Guests were keyed by name alone, so a guest could only ever be linked to one episode. Each row
+-- is now one appearance.
+
+CREATE TABLE pka_guest_new
+(
+    name           TEXT    NOT NULL,
+    show_slug      TEXT    NOT NULL DEFAULT 'pka',
+    episode_number INTEGER NOT NULL,
+    episode_part   INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (show_slug, name, episode_number, episode_part),
+    FOREIGN KEY (show_slug, episode_number, episode_part)
+        REFERENCES pka_episode (show_slug, number, part) ON DELETE CASCADE
+);
+
+INSERT INTO pka_guest_new (name, show_slug, episode_number, episode_part)
+SELECT name, show_slug, episode_number, episode_part
+FROM pka_guest;
+
+DROP TABLE pka_guest;
+
+ALTER TABLE pka_guest_new RENAME TO pka_guest;
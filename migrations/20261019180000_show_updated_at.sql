@@ -0,0 +1,59 @@
+-- When anything a show's feeds are built from last changed, for `Last-Modified` headers. Episodes,
+-- their timelines and their YouTube details keep it current through the triggers below.
+ALTER TABLE show ADD COLUMN updated_at BIGINT NOT NULL DEFAULT 0;
+
+UPDATE show SET updated_at = CAST(strftime('%s', 'now') AS INTEGER);
+
+CREATE TRIGGER pka_episode_insert_touches_show
+    AFTER INSERT ON pka_episode
+BEGIN
+    UPDATE show SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE slug = NEW.show_slug;
+END;
+
+CREATE TRIGGER pka_episode_update_touches_show
+    AFTER UPDATE ON pka_episode
+BEGIN
+    UPDATE show SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE slug = NEW.show_slug;
+END;
+
+CREATE TRIGGER pka_episode_delete_touches_show
+    AFTER DELETE ON pka_episode
+BEGIN
+    UPDATE show SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE slug = OLD.show_slug;
+END;
+
+CREATE TRIGGER pka_event_insert_touches_show
+    AFTER INSERT ON pka_event
+BEGIN
+    UPDATE show SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE slug = NEW.show_slug;
+END;
+
+CREATE TRIGGER pka_event_update_touches_show
+    AFTER UPDATE ON pka_event
+BEGIN
+    UPDATE show SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE slug = NEW.show_slug;
+END;
+
+CREATE TRIGGER pka_event_delete_touches_show
+    AFTER DELETE ON pka_event
+BEGIN
+    UPDATE show SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE slug = OLD.show_slug;
+END;
+
+CREATE TRIGGER pka_youtube_details_insert_touches_show
+    AFTER INSERT ON pka_youtube_details
+BEGIN
+    UPDATE show SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE slug = NEW.show_slug;
+END;
+
+CREATE TRIGGER pka_youtube_details_update_touches_show
+    AFTER UPDATE ON pka_youtube_details
+BEGIN
+    UPDATE show SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE slug = NEW.show_slug;
+END;
+
+CREATE TRIGGER pka_youtube_details_delete_touches_show
+    AFTER DELETE ON pka_youtube_details
+BEGIN
+    UPDATE show SET updated_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE slug = OLD.show_slug;
+END;
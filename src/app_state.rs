@@ -1,15 +1,37 @@
+use std::net::IpAddr;
 use std::sync::Arc;
 
+use crate::auth::AuthTokens;
+use crate::site::Site;
+use crate::updater::source::YoutubeSource;
 use crate::{redis_db::RedisDb, Repo};
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: Arc<Repo>,
     pub redis: Arc<RedisDb>,
+    pub auth: Arc<AuthTokens>,
+    pub youtube: Arc<YoutubeSource>,
+    pub site: Arc<Site>,
+    pub trusted_proxies: Arc<[IpAddr]>,
 }
 
 impl AppState {
-    pub fn new(db: Arc<Repo>, redis: Arc<RedisDb>) -> Self {
-        Self { db, redis }
+    pub fn new(
+        db: Arc<Repo>,
+        redis: Arc<RedisDb>,
+        auth: Arc<AuthTokens>,
+        youtube: Arc<YoutubeSource>,
+        site: Arc<Site>,
+        trusted_proxies: Arc<[IpAddr]>,
+    ) -> Self {
+        Self {
+            db,
+            redis,
+            auth,
+            youtube,
+            site,
+            trusted_proxies,
+        }
     }
 }
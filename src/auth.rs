@@ -0,0 +1,125 @@
+use compact_str::{CompactString, ToCompactString};
+
+use crate::config::Config;
+
+/// Roles are ordered so that a higher role can do everything a lower one can.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    Moderator,
+    Admin,
+}
+
+#[derive(Debug)]
+pub struct ApiToken {
+    name: CompactString,
+    secret: String,
+    role: Role,
+}
+
+impl ApiToken {
+    /// Parses a `name:secret` entry, falling back to `default_name` when no name is given.
+    fn parse(entry: &str, role: Role, default_name: &str) -> Option<Self> {
+        let entry = entry.trim();
+
+        let (name, secret) = match entry.split_once(':') {
+            Some((name, secret)) => (name.trim(), secret.trim()),
+            None => (default_name, entry),
+        };
+
+        if secret.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            name: name.to_compact_string(),
+            secret: secret.to_owned(),
+            role,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn has_role(&self, role: Role) -> bool {
+        self.role >= role
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AuthTokens {
+    tokens: Vec<ApiToken>,
+}
+
+impl AuthTokens {
+    pub fn from_config(config: &Config) -> Self {
+        let moderators = config
+            .moderator_tokens
+            .iter()
+            .filter_map(|entry| ApiToken::parse(entry, Role::Moderator, "moderator"));
+
+        let admins = config
+            .admin_tokens
+            .iter()
+            .filter_map(|entry| ApiToken::parse(entry, Role::Admin, "admin"));
+
+        let tokens = moderators.chain(admins).collect();
+
+        Self { tokens }
+    }
+
+    pub fn find(&self, secret: &str) -> Option<&ApiToken> {
+        self.tokens
+            .iter()
+            .find(|token| constant_time_eq(token.secret.as_bytes(), secret.as_bytes()))
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_token_entries() {
+        let named = ApiToken::parse(" alice:s3cret ", Role::Moderator, "moderator").unwrap();
+        assert_eq!(named.name(), "alice");
+        assert_eq!(named.secret, "s3cret");
+
+        let unnamed = ApiToken::parse("s3cret", Role::Admin, "admin").unwrap();
+        assert_eq!(unnamed.name(), "admin");
+        assert_eq!(unnamed.secret, "s3cret");
+
+        assert!(ApiToken::parse("", Role::Moderator, "moderator").is_none());
+        assert!(ApiToken::parse("alice:", Role::Moderator, "moderator").is_none());
+    }
+
+    #[test]
+    fn find_token() {
+        let tokens = AuthTokens {
+            tokens: vec![
+                ApiToken::parse("alice:s3cret", Role::Moderator, "moderator").unwrap(),
+                ApiToken::parse("bob:hunter2", Role::Admin, "admin").unwrap(),
+            ],
+        };
+
+        assert_eq!(tokens.find("s3cret").map(ApiToken::name), Some("alice"));
+        assert!(tokens.find("s3cre").is_none());
+
+        let alice = tokens.find("s3cret").unwrap();
+        assert!(alice.has_role(Role::Moderator));
+        assert!(!alice.has_role(Role::Admin));
+
+        let bob = tokens.find("hunter2").unwrap();
+        assert!(bob.has_role(Role::Moderator));
+        assert!(bob.has_role(Role::Admin));
+        assert!(tokens.find("").is_none());
+    }
+}
@@ -0,0 +1,354 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::{Args, Parser, Subcommand};
+
+use crate::conduit::redis::{event_cache, stats_cache};
+use crate::conduit::sqlite::stats;
+use crate::config::Config;
+use crate::db;
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::show::DEFAULT_SHOW;
+use crate::redis_db::RedisDb;
+use crate::reports::timeline_coverage::{
+    self, CoverageOptions, DEFAULT_MAX_GAP_SECONDS, DEFAULT_MIN_EVENTS,
+};
+use crate::search::pka_search::EVENTS_REDIS_TAG;
+use crate::updater::pka::load_new_episodes;
+use crate::updater::reextract::reextract_episode;
+use crate::updater::source::YoutubeSource;
+use crate::updater::{export, import};
+use crate::Repo;
+
+#[derive(Debug, Parser)]
+#[command(version, about = "PKA Index backend")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Default, Subcommand)]
+pub enum Command {
+    /// Run the HTTP server (default)
+    #[default]
+    Serve,
+    /// Print a report to stdout
+    #[command(subcommand)]
+    Report(ReportCommand),
+    /// Re-extract an episode's timeline from its current YouTube description
+    ReExtract(ReExtractArgs),
+    /// Import episode lists and timelines from files
+    #[command(subcommand)]
+    Import(ImportCommand),
+    /// Export episode lists and timelines in the formats `import` reads
+    #[command(subcommand)]
+    Export(ExportCommand),
+    /// Apply pending database migrations
+    Migrate,
+    /// Check YouTube for new episodes once
+    CheckNew,
+    /// Flush the search and stats caches so they're rebuilt from the database. The server
+    /// reloads its events index by itself every minute.
+    #[command(alias = "reindex")]
+    FlushCache,
+    /// Print show statistics
+    Stats(StatsArgs),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ReportCommand {
+    /// Episodes with missing or sparse timelines, overrunning events and gaps
+    TimelineCoverage(TimelineCoverageArgs),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ImportCommand {
+    /// Add or update episodes from a JSON episode list such as `pka_episode_list.json`
+    Episodes(ImportEpisodesArgs),
+    /// Replace timelines from description text files or painkilleralready.info JSON dumps,
+    /// named after their episode, e.g. `PKA 481.txt` or `481.json`
+    Timelines(ImportTimelinesArgs),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ExportCommand {
+    /// Write the episode list as JSON, to stdout unless `--output` is given
+    Episodes(ExportEpisodesArgs),
+    /// Write one timeline text file per episode into a directory
+    Timelines(ExportTimelinesArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct TimelineCoverageArgs {
+    /// Report episodes with fewer events than this
+    #[arg(long, default_value_t = DEFAULT_MIN_EVENTS)]
+    pub min_events: usize,
+    /// Report stretches without an event longer than this
+    #[arg(long, default_value_t = DEFAULT_MAX_GAP_SECONDS)]
+    pub max_gap_seconds: i32,
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ReExtractArgs {
+    /// Episode number, e.g. 481 or 481.5
+    pub number: EpisodeNumber,
+    /// Slug of the show the episode belongs to
+    #[arg(long, default_value = DEFAULT_SHOW)]
+    pub show: String,
+    /// Replace the stored timeline instead of only printing the difference
+    #[arg(long)]
+    pub apply: bool,
+    /// Print the difference as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ImportEpisodesArgs {
+    /// Episode list to import
+    pub file: PathBuf,
+    /// Slug of the show the episodes belong to
+    #[arg(long, default_value = DEFAULT_SHOW)]
+    pub show: String,
+    /// Only print what would change
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ImportTimelinesArgs {
+    /// Timeline files, or directories of them
+    #[arg(required = true)]
+    pub paths: Vec<PathBuf>,
+    /// Slug of the show the episodes belong to
+    #[arg(long, default_value = DEFAULT_SHOW)]
+    pub show: String,
+    /// Only print what would change
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportEpisodesArgs {
+    /// Slug of the show to export
+    #[arg(long, default_value = DEFAULT_SHOW)]
+    pub show: String,
+    /// File to write instead of stdout
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportTimelinesArgs {
+    /// Directory to write the files into, created if missing
+    pub dir: PathBuf,
+    /// Slug of the show to export
+    #[arg(long, default_value = DEFAULT_SHOW)]
+    pub show: String,
+}
+
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    /// Slug of the show
+    #[arg(long, default_value = DEFAULT_SHOW)]
+    pub show: String,
+    /// Print the stats as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Opens the database for commands that read or write it, refusing to run against a schema
+/// this build doesn't match.
+async fn open_db(config: &Config) -> anyhow::Result<Repo> {
+    let db_pool = connect(config).await?;
+
+    db::ensure_up_to_date(&db_pool).await?;
+
+    Ok(db_pool)
+}
+
+async fn connect(config: &Config) -> anyhow::Result<Repo> {
+    db::create_pool(&config.database_url)
+        .await
+        .context("Failed to open database")
+}
+
+pub async fn report(config: &Config, command: ReportCommand) -> anyhow::Result<()> {
+    let db_pool = open_db(config).await?;
+
+    match command {
+        ReportCommand::TimelineCoverage(args) => {
+            let options = CoverageOptions {
+                min_events: args.min_events,
+                max_gap_seconds: args.max_gap_seconds,
+            };
+
+            let report = timeline_coverage::generate(&db_pool, options).await?;
+
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print!("{report}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn reextract(config: &Config, args: ReExtractArgs) -> anyhow::Result<()> {
+    let db_pool = open_db(config).await?;
+
+    let youtube = YoutubeSource::from_config(config, &db_pool)?;
+
+    let diff = reextract_episode(
+        &db_pool,
+        &youtube,
+        &args.show,
+        args.number,
+        args.apply,
+        None,
+    )
+    .await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        print!("{diff}");
+    }
+
+    Ok(())
+}
+
+pub async fn import(config: &Config, command: ImportCommand) -> anyhow::Result<()> {
+    let db_pool = open_db(config).await?;
+
+    match command {
+        ImportCommand::Episodes(args) => {
+            let json = tokio::fs::read_to_string(&args.file)
+                .await
+                .with_context(|| format!("Failed to read '{}'", args.file.display()))?;
+
+            let episodes = import::parse_episode_list(&args.show, &json)?;
+            let report = import::import_episodes(&db_pool, episodes, args.dry_run).await?;
+
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print!("{report}");
+            }
+        }
+        ImportCommand::Timelines(args) => {
+            let report =
+                import::import_timelines(&db_pool, &args.show, &args.paths, args.dry_run).await?;
+
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print!("{report}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn export(config: &Config, command: ExportCommand) -> anyhow::Result<()> {
+    let db_pool = open_db(config).await?;
+
+    match command {
+        ExportCommand::Episodes(args) => {
+            let list = export::episode_list(&db_pool, &args.show).await?;
+            let json = serde_json::to_string_pretty(&list)?;
+
+            match args.output {
+                Some(path) => {
+                    tokio::fs::write(&path, json)
+                        .await
+                        .with_context(|| format!("Failed to write '{}'", path.display()))?;
+
+                    println!(
+                        "Exported {} episodes to {}",
+                        list.episodes.len(),
+                        path.display()
+                    );
+                }
+                None => println!("{json}"),
+            }
+        }
+        ExportCommand::Timelines(args) => {
+            let written = export::timeline_files(&db_pool, &args.show, &args.dir).await?;
+
+            println!("Exported {written} timelines to {}", args.dir.display());
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn migrate(config: &Config) -> anyhow::Result<()> {
+    let db_pool = connect(config).await?;
+
+    let applied = db::migrate(&db_pool).await?;
+
+    println!(
+        "Applied {applied} migrations, database is at {}",
+        db::schema_version()
+    );
+
+    Ok(())
+}
+
+pub async fn check_new(config: &Config) -> anyhow::Result<()> {
+    let db_pool = open_db(config).await?;
+
+    let youtube = YoutubeSource::from_config(config, &db_pool)?;
+
+    let added = load_new_episodes(&db_pool, &youtube).await?;
+
+    if added > 0 {
+        let redis = RedisDb::new(&config.redis_url).await?;
+
+        stats_cache::invalidate(&redis).await?;
+    }
+
+    println!("Added {added} new episodes");
+
+    Ok(())
+}
+
+/// The running server reloads its in-memory index from the database every minute, so only
+/// the Redis caches built from it need clearing here.
+pub async fn flush_cache(config: &Config) -> anyhow::Result<()> {
+    let redis = RedisDb::new(&config.redis_url).await?;
+
+    let keys_deleted = event_cache::flush(&redis, EVENTS_REDIS_TAG).await?;
+    stats_cache::invalidate(&redis).await?;
+
+    println!("Flushed {keys_deleted} cached searches and the cached stats");
+
+    Ok(())
+}
+
+pub async fn stats(config: &Config, args: StatsArgs) -> anyhow::Result<()> {
+    let db_pool = open_db(config).await?;
+
+    let stats = stats::all(&db_pool, &args.show).await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+    } else {
+        print!("{stats}");
+    }
+
+    Ok(())
+}
@@ -17,3 +17,9 @@ pub async fn get(redis: &RedisDb, redis_tag: &str, key: String) -> Result<Vec<u8
 
     Ok(res)
 }
+
+pub async fn flush(redis: &RedisDb, redis_tag: &str) -> Result<usize, ApiError> {
+    let deleted = redis.delete_tag(redis_tag).await?;
+
+    Ok(deleted)
+}
@@ -1 +1,3 @@
 pub mod event_cache;
+pub mod rate_limit;
+pub mod stats_cache;
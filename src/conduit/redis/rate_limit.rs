@@ -0,0 +1,23 @@
+use crate::models::errors::ApiError;
+use crate::redis_db::RedisDb;
+
+const REDIS_TAG: &str = "RATE";
+
+/// Counts a request by `client` against `bucket` and returns how many it has made in the
+/// current window.
+pub async fn hit(
+    redis: &RedisDb,
+    bucket: &str,
+    client: &str,
+    window_seconds: i64,
+) -> Result<u64, ApiError> {
+    let count = redis
+        .incr_with_expiry(
+            REDIS_TAG.to_string(),
+            format!("{bucket}-{client}"),
+            window_seconds,
+        )
+        .await?;
+
+    Ok(count)
+}
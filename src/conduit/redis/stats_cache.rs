@@ -0,0 +1,32 @@
+use crate::models::errors::ApiError;
+use crate::redis_db::RedisDb;
+
+const REDIS_TAG: &str = "STATS";
+
+// Stats only change when new episodes are ingested, which invalidates every show's entry.
+const EXPIRY_SECONDS: u64 = 60 * 60 * 24;
+
+pub async fn set(redis: &RedisDb, show: &str, value: &[u8]) -> Result<(), ApiError> {
+    redis
+        .set_with_expiry(
+            REDIS_TAG.to_string(),
+            show.to_string(),
+            value,
+            EXPIRY_SECONDS,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get(redis: &RedisDb, show: &str) -> Result<Vec<u8>, ApiError> {
+    let res: Vec<u8> = redis.get(REDIS_TAG.to_string(), show.to_string()).await?;
+
+    Ok(res)
+}
+
+pub async fn invalidate(redis: &RedisDb) -> Result<(), ApiError> {
+    redis.delete_tag(REDIS_TAG).await?;
+
+    Ok(())
+}
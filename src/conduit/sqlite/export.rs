@@ -0,0 +1,84 @@
+use compact_str::CompactString;
+use futures_util::stream::BoxStream;
+use sqlx::SqliteConnection;
+
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::export::{ExportEpisode, ExportEvent};
+use crate::Repo;
+
+pub fn episodes(repo: &Repo) -> BoxStream<'_, Result<ExportEpisode, sqlx::Error>> {
+    sqlx::query_as!(
+        ExportEpisode,
+        r#"SELECT
+            e.show_slug      AS "show_slug: CompactString",
+            e.label          AS "number!: EpisodeNumber",
+            e.name           AS "name: CompactString",
+            e.youtube_link   AS "youtube_link: CompactString",
+            e.upload_date    AS "upload_date: i64",
+            y.title          AS "title?: CompactString",
+            y.length_seconds AS "length_seconds?: i32"
+          FROM pka_episode e
+          LEFT JOIN pka_youtube_details y
+            ON y.show_slug = e.show_slug AND y.episode_number = e.number AND y.episode_part = e.part
+          ORDER BY e.show_slug, e.number, e.part"#
+    )
+    .fetch(repo)
+}
+
+pub fn events(repo: &Repo) -> BoxStream<'_, Result<ExportEvent, sqlx::Error>> {
+    sqlx::query_as!(
+        ExportEvent,
+        r#"SELECT
+            event_id       AS "event_id: CompactString",
+            show_slug      AS "show_slug: CompactString",
+            episode_label  AS "episode_number!: EpisodeNumber",
+            timestamp      AS "timestamp: i32",
+            description    AS "description: CompactString",
+            length_seconds AS "length_seconds: i32",
+            upload_date    AS "upload_date: i64"
+          FROM pka_event
+          ORDER BY show_slug, episode_number, episode_part, timestamp"#
+    )
+    .fetch(repo)
+}
+
+/// Writes a consistent copy of the whole database to `path`, which must not exist yet.
+pub async fn vacuum_into(repo: &Repo, path: &str) -> Result<(), sqlx::Error> {
+    sqlx::query!("VACUUM INTO ?", path).execute(repo).await?;
+
+    Ok(())
+}
+
+/// Tables that make up the public dataset. Snapshots drop every other table, so new
+/// tables stay private unless they're added here.
+pub const PUBLIC_TABLES: &[&str] = &[
+    "pka_episode",
+    "pka_event",
+    "pka_guest",
+    "pka_youtube_details",
+    "show",
+    "_sqlx_migrations",
+];
+
+/// Drops tables that aren't part of the public dataset from a snapshot and reclaims their space.
+pub async fn drop_private_tables(conn: &mut SqliteConnection) -> Result<(), sqlx::Error> {
+    let tables = sqlx::query_scalar!(
+        r#"SELECT name AS "name!: String"
+          FROM sqlite_master
+          WHERE type = 'table' AND name NOT LIKE 'sqlite\_%' ESCAPE '\'"#
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for table in tables {
+        if !PUBLIC_TABLES.contains(&table.as_str()) {
+            let drop = format!("DROP TABLE \"{}\"", table.replace('"', "\"\""));
+
+            sqlx::query(&drop).execute(&mut *conn).await?;
+        }
+    }
+
+    sqlx::query!("VACUUM").execute(&mut *conn).await?;
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use compact_str::CompactString;
+use sqlx::SqliteExecutor;
+
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::ingestion_log::{IngestionLogEntry, IngestionOutcome, NewIngestionLogEntry};
+use crate::models::timeline_warning::TimelineWarnings;
+
+pub async fn insert(
+    executor: impl SqliteExecutor<'_>,
+    entry: NewIngestionLogEntry,
+) -> Result<i64, sqlx::Error> {
+    let NewIngestionLogEntry {
+        show_slug,
+        episode_number,
+        video_id,
+        attempted_at,
+        outcome,
+        events_inserted,
+        error,
+        warnings,
+    } = entry;
+
+    let (number, part) = (episode_number.number(), episode_number.part());
+
+    let res = sqlx::query!(
+        r#"INSERT INTO ingestion_log (show_slug, episode_number, episode_part, video_id, attempted_at, outcome, events_inserted, error, warnings)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+        show_slug,
+        number,
+        part,
+        video_id,
+        attempted_at,
+        outcome,
+        events_inserted,
+        error,
+        warnings
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(res.last_insert_rowid())
+}
+
+pub async fn recent(
+    executor: impl SqliteExecutor<'_>,
+    limit: u32,
+) -> Result<Vec<IngestionLogEntry>, sqlx::Error> {
+    sqlx::query_as!(
+        IngestionLogEntry,
+        r#"SELECT
+            id              AS "id!: i64",
+            show_slug       AS "show_slug: CompactString",
+            episode_label   AS "episode_number!: EpisodeNumber",
+            video_id        AS "video_id: CompactString",
+            attempted_at    AS "attempted_at: i64",
+            outcome         AS "outcome: IngestionOutcome",
+            events_inserted AS "events_inserted: i64",
+            error           AS "error: CompactString",
+            warnings        AS "warnings!: TimelineWarnings"
+          FROM ingestion_log
+          ORDER BY attempted_at DESC, id DESC
+          LIMIT ?"#,
+        limit
+    )
+    .fetch_all(executor)
+    .await
+}
@@ -1,4 +1,11 @@
+pub mod export;
+pub mod ingestion_log;
+pub mod pending_edit;
 pub mod pka_episode;
 pub mod pka_event;
 pub mod pka_guest;
 pub mod pka_youtube_details;
+pub mod show;
+pub mod stats;
+pub mod youtube_drift;
+pub mod youtube_quota;
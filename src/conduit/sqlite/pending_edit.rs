@@ -0,0 +1,115 @@
+use compact_str::CompactString;
+use sqlx::SqliteExecutor;
+
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::pending_edit::{EditKind, EditStatus, NewPendingEdit, PendingEdit};
+
+pub async fn insert(
+    executor: impl SqliteExecutor<'_>,
+    edit: NewPendingEdit,
+) -> Result<i64, sqlx::Error> {
+    let NewPendingEdit {
+        kind,
+        episode_number,
+        event_id,
+        timestamp,
+        description,
+        reason,
+        submitted_at,
+    } = edit;
+
+    let (number, part) = (episode_number.number(), episode_number.part());
+
+    let res = sqlx::query!(
+        r#"INSERT INTO pending_edit (kind, episode_number, episode_part, event_id, timestamp, description, reason, submitted_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
+        kind,
+        number,
+        part,
+        event_id,
+        timestamp,
+        description,
+        reason,
+        submitted_at
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(res.last_insert_rowid())
+}
+
+pub async fn find(executor: impl SqliteExecutor<'_>, id: i64) -> Result<PendingEdit, sqlx::Error> {
+    sqlx::query_as!(
+        PendingEdit,
+        r#"SELECT
+            id             AS "id!: i64",
+            kind           AS "kind: EditKind",
+            episode_label  AS "episode_number!: EpisodeNumber",
+            event_id       AS "event_id: CompactString",
+            timestamp      AS "timestamp: i32",
+            description    AS "description: CompactString",
+            reason         AS "reason: CompactString",
+            status         AS "status: EditStatus",
+            submitted_at   AS "submitted_at: i64",
+            reviewed_at    AS "reviewed_at: i64",
+            reviewer       AS "reviewer: CompactString",
+            review_note    AS "review_note: CompactString"
+          FROM pending_edit
+          WHERE id = ?"#,
+        id
+    )
+    .fetch_one(executor)
+    .await
+}
+
+pub async fn all_with_status(
+    executor: impl SqliteExecutor<'_>,
+    status: EditStatus,
+) -> Result<Vec<PendingEdit>, sqlx::Error> {
+    sqlx::query_as!(
+        PendingEdit,
+        r#"SELECT
+            id             AS "id!: i64",
+            kind           AS "kind: EditKind",
+            episode_label  AS "episode_number!: EpisodeNumber",
+            event_id       AS "event_id: CompactString",
+            timestamp      AS "timestamp: i32",
+            description    AS "description: CompactString",
+            reason         AS "reason: CompactString",
+            status         AS "status: EditStatus",
+            submitted_at   AS "submitted_at: i64",
+            reviewed_at    AS "reviewed_at: i64",
+            reviewer       AS "reviewer: CompactString",
+            review_note    AS "review_note: CompactString"
+          FROM pending_edit
+          WHERE status = ?
+          ORDER BY submitted_at ASC"#,
+        status
+    )
+    .fetch_all(executor)
+    .await
+}
+
+pub async fn mark_reviewed(
+    executor: impl SqliteExecutor<'_>,
+    id: i64,
+    status: EditStatus,
+    reviewed_at: i64,
+    reviewer: &str,
+    review_note: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"UPDATE pending_edit
+           SET status = ?, reviewed_at = ?, reviewer = ?, review_note = ?
+           WHERE id = ?"#,
+        status,
+        reviewed_at,
+        reviewer,
+        review_note,
+        id
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
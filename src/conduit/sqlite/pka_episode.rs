@@ -1,95 +1,278 @@
+use std::collections::HashMap;
+
 use compact_str::CompactString;
+use sqlx::SqliteExecutor;
 
+use crate::models::episode_number::EpisodeNumber;
 use crate::models::pka_episode::PkaEpisode;
 use crate::models::pka_episode_with_all::PkaEpisodeWithAll;
 use crate::models::pka_event::PkaEvent;
 use crate::models::pka_youtube_details::PkaYoutubeDetails;
+use crate::models::podcast_feed::FeedEpisode;
 use crate::models::search::PkaEpisodeSearchResult;
+use crate::models::timeline_coverage::EpisodeLength;
 use crate::Repo;
 
+/// Every episode of every show.
 pub async fn all(repo: &Repo) -> Result<Vec<PkaEpisode>, sqlx::Error> {
     sqlx::query_as!(
         PkaEpisode,
         r#"SELECT
-            number        AS "number: f32",
-            name          AS "name: CompactString",
-            youtube_link  AS "youtube_link: CompactString",
-            upload_date   AS "upload_date: i64"
+            show_slug    AS "show_slug: CompactString",
+            label        AS "number!: EpisodeNumber",
+            name         AS "name: CompactString",
+            youtube_link AS "youtube_link: CompactString",
+            upload_date  AS "upload_date: i64"
           FROM pka_episode"#
     )
     .fetch_all(repo)
     .await
 }
 
-pub async fn find_youtube_link(repo: &Repo, id: f32) -> Result<CompactString, sqlx::Error> {
+/// The show's episodes, oldest first.
+pub async fn for_show(repo: &Repo, show: &str) -> Result<Vec<PkaEpisode>, sqlx::Error> {
+    sqlx::query_as!(
+        PkaEpisode,
+        r#"SELECT
+            show_slug    AS "show_slug: CompactString",
+            label        AS "number!: EpisodeNumber",
+            name         AS "name: CompactString",
+            youtube_link AS "youtube_link: CompactString",
+            upload_date  AS "upload_date: i64"
+          FROM pka_episode
+          WHERE show_slug = ?
+          ORDER BY number, part"#,
+        show
+    )
+    .fetch_all(repo)
+    .await
+}
+
+pub async fn find(
+    executor: impl SqliteExecutor<'_>,
+    show: &str,
+    id: EpisodeNumber,
+) -> Result<PkaEpisode, sqlx::Error> {
+    let (number, part) = (id.number(), id.part());
+
+    sqlx::query_as!(
+        PkaEpisode,
+        r#"SELECT
+            show_slug    AS "show_slug: CompactString",
+            label        AS "number!: EpisodeNumber",
+            name         AS "name: CompactString",
+            youtube_link AS "youtube_link: CompactString",
+            upload_date  AS "upload_date: i64"
+          FROM pka_episode
+          WHERE show_slug = ? AND number = ? AND part = ?"#,
+        show,
+        number,
+        part
+    )
+    .fetch_one(executor)
+    .await
+}
+
+pub async fn find_youtube_link(
+    repo: &Repo,
+    show: &str,
+    id: EpisodeNumber,
+) -> Result<CompactString, sqlx::Error> {
+    let (number, part) = (id.number(), id.part());
+
+    sqlx::query_scalar!(
+        r#"SELECT youtube_link AS "youtube_link: CompactString"
+          FROM pka_episode
+          WHERE show_slug = ? AND number = ? AND part = ?"#,
+        show,
+        number,
+        part
+    )
+    .fetch_one(repo)
+    .await
+}
+
+pub async fn find_number_by_video_id(
+    repo: &Repo,
+    show: &str,
+    video_id: &str,
+) -> Result<EpisodeNumber, sqlx::Error> {
     sqlx::query_scalar!(
-        r#"SELECT youtube_link AS "youtube_link: CompactString" FROM pka_episode WHERE number = ?"#,
-        id
+        r#"SELECT e.label AS "number!: EpisodeNumber"
+          FROM pka_episode e
+          LEFT JOIN pka_youtube_details y
+            ON y.show_slug = e.show_slug AND y.episode_number = e.number AND y.episode_part = e.part
+          WHERE e.show_slug = ? AND (y.video_id = ? OR e.youtube_link = ?)
+          LIMIT 1"#,
+        show,
+        video_id,
+        video_id
     )
     .fetch_one(repo)
     .await
 }
 
-pub async fn latest(repo: &Repo) -> Result<f32, sqlx::Error> {
+/// Video ids of every show, so one upload is never ingested twice.
+pub async fn all_video_ids(repo: &Repo) -> Result<Vec<CompactString>, sqlx::Error> {
+    sqlx::query_scalar!(r#"SELECT youtube_link AS "youtube_link: CompactString" FROM pka_episode"#)
+        .fetch_all(repo)
+        .await
+}
+
+pub async fn latest(repo: &Repo, show: &str) -> Result<EpisodeNumber, sqlx::Error> {
     sqlx::query_scalar!(
-        r#"SELECT number AS "number: f32" FROM pka_episode ORDER BY number DESC LIMIT 1"#
+        r#"SELECT label AS "number!: EpisodeNumber"
+          FROM pka_episode
+          WHERE show_slug = ?
+          ORDER BY number DESC, part DESC
+          LIMIT 1"#,
+        show
     )
     .fetch_one(repo)
     .await
 }
 
-pub async fn random(repo: &Repo) -> Result<f32, sqlx::Error> {
+pub async fn random(repo: &Repo, show: &str) -> Result<EpisodeNumber, sqlx::Error> {
     sqlx::query_scalar!(
-        r#"SELECT number AS "number: f32" FROM pka_episode ORDER BY RANDOM() LIMIT 1"#
+        r#"SELECT label AS "number!: EpisodeNumber"
+          FROM pka_episode
+          WHERE show_slug = ?
+          ORDER BY RANDOM()
+          LIMIT 1"#,
+        show
     )
     .fetch_one(repo)
     .await
 }
 
-pub async fn all_with_yt_details(repo: &Repo) -> Result<Vec<PkaEpisodeSearchResult>, sqlx::Error> {
+pub async fn all_lengths(repo: &Repo, show: &str) -> Result<Vec<EpisodeLength>, sqlx::Error> {
     sqlx::query_as!(
-        PkaEpisodeSearchResult,
+        EpisodeLength,
         r#"SELECT
-            e.number         AS "episode_number: f32",
-            e.upload_date    AS "upload_date: i64",
-            y.title          AS "title: String",
-            y.length_seconds AS "length_seconds: i32"
+            e.label          AS "episode_number!: EpisodeNumber",
+            y.length_seconds AS "length_seconds?: i32"
           FROM pka_episode e
-          INNER JOIN pka_youtube_details y ON y.episode_number = e.number
-          ORDER BY e.number DESC"#
+          LEFT JOIN pka_youtube_details y
+            ON y.show_slug = e.show_slug AND y.episode_number = e.number AND y.episode_part = e.part
+          WHERE e.show_slug = ?"#,
+        show
     )
     .fetch_all(repo)
     .await
 }
 
-pub async fn find_with_all(repo: &Repo, id: f32) -> Result<PkaEpisodeWithAll, sqlx::Error> {
-    let episode = sqlx::query_as!(
+pub async fn on_this_day(
+    repo: &Repo,
+    show: &str,
+    month_day: &str,
+    before_year: i32,
+) -> Result<Vec<PkaEpisode>, sqlx::Error> {
+    let before_year = before_year.to_string();
+
+    sqlx::query_as!(
         PkaEpisode,
         r#"SELECT
-            number       AS "number: f32",
+            show_slug    AS "show_slug: CompactString",
+            label        AS "number!: EpisodeNumber",
             name         AS "name: CompactString",
             youtube_link AS "youtube_link: CompactString",
             upload_date  AS "upload_date: i64"
           FROM pka_episode
-          WHERE number = ?"#,
-        id
+          WHERE show_slug = ?
+            AND strftime('%m-%d', upload_date, 'unixepoch') = ?
+            AND strftime('%Y', upload_date, 'unixepoch') < ?
+          ORDER BY upload_date DESC"#,
+        show,
+        month_day,
+        before_year
     )
-    .fetch_one(repo)
+    .fetch_all(repo)
+    .await
+}
+
+pub async fn all_with_yt_details(
+    repo: &Repo,
+    show: &str,
+) -> Result<Vec<PkaEpisodeSearchResult>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT
+            e.label          AS "episode_number!: EpisodeNumber",
+            e.upload_date    AS "upload_date: i64",
+            y.title          AS "title: String",
+            y.length_seconds AS "length_seconds: i32"
+          FROM pka_episode e
+          INNER JOIN pka_youtube_details y
+            ON y.show_slug = e.show_slug AND y.episode_number = e.number AND y.episode_part = e.part
+          WHERE e.show_slug = ?
+          ORDER BY e.number DESC, e.part DESC"#,
+        show
+    )
+    .fetch_all(repo)
     .await?;
 
+    let res = rows
+        .into_iter()
+        .map(|row| PkaEpisodeSearchResult {
+            episode_number: row.episode_number.as_f32(),
+            upload_date: row.upload_date,
+            title: row.title,
+            length_seconds: row.length_seconds,
+        })
+        .collect();
+
+    Ok(res)
+}
+
+/// Episodes newest first with their YouTube details, if fetched, and number of events.
+pub async fn feed(repo: &Repo, show: &str) -> Result<Vec<FeedEpisode>, sqlx::Error> {
+    sqlx::query_as!(
+        FeedEpisode,
+        r#"SELECT
+            e.label          AS "number!: EpisodeNumber",
+            e.name           AS "name: CompactString",
+            e.youtube_link   AS "youtube_link: CompactString",
+            e.upload_date    AS "upload_date: i64",
+            y.title          AS "title?: CompactString",
+            y.length_seconds AS "length_seconds?: i32",
+            (SELECT COUNT(*) FROM pka_event v
+              WHERE v.show_slug = e.show_slug AND v.episode_number = e.number AND v.episode_part = e.part
+            ) AS "event_count!: i64"
+          FROM pka_episode e
+          LEFT JOIN pka_youtube_details y
+            ON y.show_slug = e.show_slug AND y.episode_number = e.number AND y.episode_part = e.part
+          WHERE e.show_slug = ?
+          ORDER BY e.upload_date DESC, e.number DESC, e.part DESC"#,
+        show
+    )
+    .fetch_all(repo)
+    .await
+}
+
+pub async fn find_with_all(
+    repo: &Repo,
+    show: &str,
+    id: EpisodeNumber,
+) -> Result<PkaEpisodeWithAll, sqlx::Error> {
+    let episode = find(repo, show, id).await?;
+
+    let (number, part) = (id.number(), id.part());
+
     let events = sqlx::query_as!(
         PkaEvent,
         r#"SELECT
             event_id       AS "event_id: CompactString",
-            episode_number AS "episode_number: f32",
+            show_slug      AS "show_slug: CompactString",
+            episode_label  AS "episode_number!: EpisodeNumber",
             timestamp      AS "timestamp: i32",
             description    AS "description: CompactString",
             length_seconds AS "length_seconds: i32",
             upload_date    AS "upload_date: i64"
           FROM pka_event
-          WHERE episode_number = ?
+          WHERE show_slug = ? AND episode_number = ? AND episode_part = ?
           ORDER BY timestamp ASC"#,
-        id
+        show,
+        number,
+        part
     )
     .fetch_all(repo)
     .await?;
@@ -98,13 +281,16 @@ pub async fn find_with_all(repo: &Repo, id: f32) -> Result<PkaEpisodeWithAll, sq
         PkaYoutubeDetails,
         r#"SELECT
             video_id       AS "video_id: CompactString",
-            episode_number AS "episode_number: f32",
+            show_slug      AS "show_slug: CompactString",
+            episode_label  AS "episode_number!: EpisodeNumber",
             title          AS "title: CompactString",
             length_seconds AS "length_seconds: i32"
           FROM pka_youtube_details
-          WHERE episode_number = ?
+          WHERE show_slug = ? AND episode_number = ? AND episode_part = ?
           LIMIT 1"#,
-        id
+        show,
+        number,
+        part
     )
     .fetch_one(repo)
     .await?;
@@ -112,23 +298,160 @@ pub async fn find_with_all(repo: &Repo, id: f32) -> Result<PkaEpisodeWithAll, sq
     Ok(PkaEpisodeWithAll::new(episode, youtube_details, events))
 }
 
-pub async fn insert(repo: &Repo, episode: PkaEpisode) -> Result<(), sqlx::Error> {
+pub async fn find_many_with_all(
+    repo: &Repo,
+    show: &str,
+    ids: &[EpisodeNumber],
+) -> Result<Vec<PkaEpisodeWithAll>, sqlx::Error> {
+    // Sent as `[[number, part], ...]` and matched as row values.
+    let ids = serde_json::to_string(
+        &ids.iter()
+            .map(|id| (id.number(), id.part()))
+            .collect::<Vec<_>>(),
+    )
+    .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+
+    let episodes = sqlx::query_as!(
+        PkaEpisode,
+        r#"SELECT
+            show_slug    AS "show_slug: CompactString",
+            label        AS "number!: EpisodeNumber",
+            name         AS "name: CompactString",
+            youtube_link AS "youtube_link: CompactString",
+            upload_date  AS "upload_date: i64"
+          FROM pka_episode
+          WHERE show_slug = ?
+            AND (number, part) IN (SELECT value ->> 0, value ->> 1 FROM json_each(?))"#,
+        show,
+        ids
+    )
+    .fetch_all(repo)
+    .await?;
+
+    let events = sqlx::query_as!(
+        PkaEvent,
+        r#"SELECT
+            event_id       AS "event_id: CompactString",
+            show_slug      AS "show_slug: CompactString",
+            episode_label  AS "episode_number!: EpisodeNumber",
+            timestamp      AS "timestamp: i32",
+            description    AS "description: CompactString",
+            length_seconds AS "length_seconds: i32",
+            upload_date    AS "upload_date: i64"
+          FROM pka_event
+          WHERE show_slug = ?
+            AND (episode_number, episode_part) IN (SELECT value ->> 0, value ->> 1 FROM json_each(?))
+          ORDER BY episode_number ASC, episode_part ASC, timestamp ASC"#,
+        show,
+        ids
+    )
+    .fetch_all(repo)
+    .await?;
+
+    let youtube_details = sqlx::query_as!(
+        PkaYoutubeDetails,
+        r#"SELECT
+            video_id       AS "video_id: CompactString",
+            show_slug      AS "show_slug: CompactString",
+            episode_label  AS "episode_number!: EpisodeNumber",
+            title          AS "title: CompactString",
+            length_seconds AS "length_seconds: i32"
+          FROM pka_youtube_details
+          WHERE show_slug = ?
+            AND (episode_number, episode_part) IN (SELECT value ->> 0, value ->> 1 FROM json_each(?))"#,
+        show,
+        ids
+    )
+    .fetch_all(repo)
+    .await?;
+
+    let mut events_by_episode: HashMap<EpisodeNumber, Vec<PkaEvent>> = HashMap::new();
+    for event in events {
+        events_by_episode
+            .entry(event.episode_number())
+            .or_default()
+            .push(event);
+    }
+
+    let mut details_by_episode = youtube_details
+        .into_iter()
+        .map(|details| (details.episode_number, details))
+        .collect::<HashMap<_, _>>();
+
+    let res = episodes
+        .into_iter()
+        .filter_map(|episode| {
+            let key = episode.number();
+            let details = details_by_episode.remove(&key)?;
+            let events = events_by_episode.remove(&key).unwrap_or_default();
+
+            Some(PkaEpisodeWithAll::new(episode, details, events))
+        })
+        .collect();
+
+    Ok(res)
+}
+
+pub async fn insert(
+    executor: impl SqliteExecutor<'_>,
+    episode: PkaEpisode,
+) -> Result<(), sqlx::Error> {
     let PkaEpisode {
+        show_slug,
+        number: id,
+        name,
+        youtube_link,
+        upload_date,
+    } = episode;
+
+    let (number, part) = (id.number(), id.part());
+
+    sqlx::query!(
+        r#"INSERT INTO pka_episode (show_slug, number, part, name, youtube_link, upload_date)
+           VALUES (?, ?, ?, ?, ?, ?)"#,
+        show_slug,
         number,
+        part,
+        name,
+        youtube_link,
+        upload_date
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Inserts the episode or overwrites the stored one with the same number.
+pub async fn upsert(
+    executor: impl SqliteExecutor<'_>,
+    episode: PkaEpisode,
+) -> Result<(), sqlx::Error> {
+    let PkaEpisode {
+        show_slug,
+        number: id,
         name,
         youtube_link,
         upload_date,
     } = episode;
 
+    let (number, part) = (id.number(), id.part());
+
     sqlx::query!(
-        r#"INSERT INTO pka_episode (number, name, youtube_link, upload_date)
-           VALUES (?, ?, ?, ?)"#,
+        r#"INSERT INTO pka_episode (show_slug, number, part, name, youtube_link, upload_date)
+           VALUES (?, ?, ?, ?, ?, ?)
+           ON CONFLICT (show_slug, number, part) DO UPDATE
+             SET name = excluded.name,
+                 youtube_link = excluded.youtube_link,
+                 upload_date = excluded.upload_date"#,
+        show_slug,
         number,
+        part,
         name,
         youtube_link,
         upload_date
     )
-    .execute(repo)
+    .execute(executor)
     .await?;
 
     Ok(())
@@ -1,17 +1,21 @@
 use compact_str::CompactString;
 use rand::prelude::IndexedRandom;
 use rand::rng;
+use sqlx::SqliteExecutor;
 
+use crate::models::episode_number::EpisodeNumber;
 use crate::models::pka_event::PkaEvent;
 use crate::models::search::PkaEventSearchResult;
 use crate::Repo;
 
+/// Every event of every show.
 pub async fn all(repo: &Repo) -> Result<Vec<PkaEvent>, sqlx::Error> {
     sqlx::query_as!(
         PkaEvent,
         r#"SELECT
             event_id       AS "event_id: CompactString",
-            episode_number AS "episode_number: f32",
+            show_slug      AS "show_slug: CompactString",
+            episode_label  AS "episode_number!: EpisodeNumber",
             timestamp      AS "timestamp: i32",
             description    AS "description: CompactString",
             length_seconds AS "length_seconds: i32",
@@ -22,9 +26,148 @@ pub async fn all(repo: &Repo) -> Result<Vec<PkaEvent>, sqlx::Error> {
     .await
 }
 
-pub async fn insert(repo: &Repo, event: PkaEvent) -> Result<(), sqlx::Error> {
+pub async fn for_show(repo: &Repo, show: &str) -> Result<Vec<PkaEvent>, sqlx::Error> {
+    sqlx::query_as!(
+        PkaEvent,
+        r#"SELECT
+            event_id       AS "event_id: CompactString",
+            show_slug      AS "show_slug: CompactString",
+            episode_label  AS "episode_number!: EpisodeNumber",
+            timestamp      AS "timestamp: i32",
+            description    AS "description: CompactString",
+            length_seconds AS "length_seconds: i32",
+            upload_date    AS "upload_date: i64"
+          FROM pka_event
+          WHERE show_slug = ?"#,
+        show
+    )
+    .fetch_all(repo)
+    .await
+}
+
+/// Top events of every episode uploaded on `month_day` before `before_year`,
+/// at most `limit` per episode, ordered by episode then rank.
+pub async fn top_on_this_day(
+    repo: &Repo,
+    show: &str,
+    month_day: &str,
+    before_year: i32,
+    limit: i64,
+) -> Result<Vec<PkaEvent>, sqlx::Error> {
+    let before_year = before_year.to_string();
+
+    sqlx::query_as!(
+        PkaEvent,
+        r#"SELECT
+            event_id       AS "event_id: CompactString",
+            show_slug      AS "show_slug: CompactString",
+            episode_label  AS "episode_number!: EpisodeNumber",
+            timestamp      AS "timestamp: i32",
+            description    AS "description: CompactString",
+            length_seconds AS "length_seconds: i32",
+            upload_date    AS "upload_date: i64"
+          FROM (
+            SELECT ev.*,
+                   ROW_NUMBER() OVER (
+                     PARTITION BY ev.episode_number, ev.episode_part
+                     ORDER BY ev.length_seconds DESC, ev.timestamp ASC
+                   ) AS rank
+              FROM pka_event ev
+              JOIN pka_episode ep
+                ON ep.show_slug = ev.show_slug
+               AND ep.number = ev.episode_number
+               AND ep.part = ev.episode_part
+             WHERE ev.show_slug = ?
+               AND strftime('%m-%d', ep.upload_date, 'unixepoch') = ?
+               AND strftime('%Y', ep.upload_date, 'unixepoch') < ?
+               AND lower(ev.description) NOT LIKE '%intro%'
+               AND lower(ev.description) NOT LIKE '%outro%'
+               AND lower(ev.description) NOT LIKE '%ad read%'
+          )
+          WHERE rank <= ?
+          ORDER BY episode_number, episode_part, rank"#,
+        show,
+        month_day,
+        before_year,
+        limit
+    )
+    .fetch_all(repo)
+    .await
+}
+
+pub async fn find(
+    executor: impl SqliteExecutor<'_>,
+    event_id: &str,
+) -> Result<PkaEvent, sqlx::Error> {
+    sqlx::query_as!(
+        PkaEvent,
+        r#"SELECT
+            event_id       AS "event_id: CompactString",
+            show_slug      AS "show_slug: CompactString",
+            episode_label  AS "episode_number!: EpisodeNumber",
+            timestamp      AS "timestamp: i32",
+            description    AS "description: CompactString",
+            length_seconds AS "length_seconds: i32",
+            upload_date    AS "upload_date: i64"
+          FROM pka_event
+          WHERE event_id = ?"#,
+        event_id
+    )
+    .fetch_one(executor)
+    .await
+}
+
+pub async fn for_episode(
+    executor: impl SqliteExecutor<'_>,
+    show: &str,
+    episode_number: EpisodeNumber,
+) -> Result<Vec<PkaEvent>, sqlx::Error> {
+    let (number, part) = (episode_number.number(), episode_number.part());
+
+    sqlx::query_as!(
+        PkaEvent,
+        r#"SELECT
+            event_id       AS "event_id: CompactString",
+            show_slug      AS "show_slug: CompactString",
+            episode_label  AS "episode_number!: EpisodeNumber",
+            timestamp      AS "timestamp: i32",
+            description    AS "description: CompactString",
+            length_seconds AS "length_seconds: i32",
+            upload_date    AS "upload_date: i64"
+          FROM pka_event
+          WHERE show_slug = ? AND episode_number = ? AND episode_part = ?
+          ORDER BY timestamp ASC"#,
+        show,
+        number,
+        part
+    )
+    .fetch_all(executor)
+    .await
+}
+
+pub async fn delete_for_episode(
+    executor: impl SqliteExecutor<'_>,
+    show: &str,
+    episode_number: EpisodeNumber,
+) -> Result<(), sqlx::Error> {
+    let (number, part) = (episode_number.number(), episode_number.part());
+
+    sqlx::query!(
+        "DELETE FROM pka_event WHERE show_slug = ? AND episode_number = ? AND episode_part = ?",
+        show,
+        number,
+        part
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn insert(executor: impl SqliteExecutor<'_>, event: PkaEvent) -> Result<(), sqlx::Error> {
     let PkaEvent {
         event_id,
+        show_slug,
         episode_number,
         timestamp,
         description,
@@ -32,24 +175,31 @@ pub async fn insert(repo: &Repo, event: PkaEvent) -> Result<(), sqlx::Error> {
         upload_date,
     } = event;
 
+    let (number, part) = (episode_number.number(), episode_number.part());
+
     sqlx::query!(
-        r#"INSERT INTO pka_event (event_id, episode_number, timestamp, description, length_seconds, upload_date)
-           VALUES (?, ?, ?, ?, ?, ?)"#,
+        r#"INSERT INTO pka_event (event_id, show_slug, episode_number, episode_part, timestamp, description, length_seconds, upload_date)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
         event_id,
-        episode_number,
+        show_slug,
+        number,
+        part,
         timestamp,
         description,
         length_seconds,
         upload_date
     )
-    .execute(repo)
+    .execute(executor)
     .await?;
 
     Ok(())
 }
 
-pub async fn random_amount(repo: &Repo) -> Result<Option<PkaEventSearchResult>, sqlx::Error> {
-    let mut all_events = all(repo).await?;
+pub async fn random_amount(
+    repo: &Repo,
+    show: &str,
+) -> Result<Option<PkaEventSearchResult>, sqlx::Error> {
+    let mut all_events = for_show(repo, show).await?;
 
     all_events.retain(|e| {
         let des = e.description().to_lowercase();
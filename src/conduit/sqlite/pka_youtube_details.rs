@@ -1,23 +1,70 @@
+use compact_str::CompactString;
+use sqlx::SqliteExecutor;
+
+use crate::models::episode_number::EpisodeNumber;
 use crate::models::pka_youtube_details::PkaYoutubeDetails;
 use crate::Repo;
 
-pub async fn insert(repo: &Repo, details: PkaYoutubeDetails) -> Result<(), sqlx::Error> {
+pub async fn all(repo: &Repo) -> Result<Vec<PkaYoutubeDetails>, sqlx::Error> {
+    sqlx::query_as!(
+        PkaYoutubeDetails,
+        r#"SELECT
+            video_id       AS "video_id: CompactString",
+            show_slug      AS "show_slug: CompactString",
+            episode_label  AS "episode_number!: EpisodeNumber",
+            title          AS "title: CompactString",
+            length_seconds AS "length_seconds: i32"
+          FROM pka_youtube_details"#
+    )
+    .fetch_all(repo)
+    .await
+}
+
+pub async fn find_length(
+    executor: impl SqliteExecutor<'_>,
+    show: &str,
+    episode_number: EpisodeNumber,
+) -> Result<Option<i32>, sqlx::Error> {
+    let (number, part) = (episode_number.number(), episode_number.part());
+
+    sqlx::query_scalar!(
+        r#"SELECT length_seconds AS "length_seconds: i32"
+          FROM pka_youtube_details
+          WHERE show_slug = ? AND episode_number = ? AND episode_part = ?
+          LIMIT 1"#,
+        show,
+        number,
+        part
+    )
+    .fetch_optional(executor)
+    .await
+}
+
+pub async fn insert(
+    executor: impl SqliteExecutor<'_>,
+    details: PkaYoutubeDetails,
+) -> Result<(), sqlx::Error> {
     let PkaYoutubeDetails {
         video_id,
+        show_slug,
         episode_number,
         title,
         length_seconds,
     } = details;
 
+    let (number, part) = (episode_number.number(), episode_number.part());
+
     sqlx::query!(
-        r#"INSERT INTO pka_youtube_details (video_id, episode_number, title, length_seconds)
-           VALUES (?, ?, ?, ?)"#,
+        r#"INSERT INTO pka_youtube_details (video_id, show_slug, episode_number, episode_part, title, length_seconds)
+           VALUES (?, ?, ?, ?, ?, ?)"#,
         video_id,
-        episode_number,
+        show_slug,
+        number,
+        part,
         title,
         length_seconds
     )
-    .execute(repo)
+    .execute(executor)
     .await?;
 
     Ok(())
@@ -0,0 +1,50 @@
+use compact_str::CompactString;
+use sqlx::SqliteExecutor;
+
+use crate::models::show::Show;
+
+pub async fn all_enabled(executor: impl SqliteExecutor<'_>) -> Result<Vec<Show>, sqlx::Error> {
+    sqlx::query_as!(
+        Show,
+        r#"SELECT
+            slug                 AS "slug: CompactString",
+            name                 AS "name: CompactString",
+            episode_prefix       AS "episode_prefix: CompactString",
+            playlist_id          AS "playlist_id: CompactString",
+            title_regex          AS "title_regex: CompactString",
+            first_episode_number AS "first_episode_number: i64"
+          FROM show
+          WHERE enabled
+          ORDER BY slug ASC"#
+    )
+    .fetch_all(executor)
+    .await
+}
+
+pub async fn find(executor: impl SqliteExecutor<'_>, slug: &str) -> Result<Show, sqlx::Error> {
+    sqlx::query_as!(
+        Show,
+        r#"SELECT
+            slug                 AS "slug: CompactString",
+            name                 AS "name: CompactString",
+            episode_prefix       AS "episode_prefix: CompactString",
+            playlist_id          AS "playlist_id: CompactString",
+            title_regex          AS "title_regex: CompactString",
+            first_episode_number AS "first_episode_number: i64"
+          FROM show
+          WHERE slug = ? AND enabled"#,
+        slug
+    )
+    .fetch_one(executor)
+    .await
+}
+
+/// Unix time of the latest change to the show's episodes, timelines or YouTube details.
+pub async fn updated_at(executor: impl SqliteExecutor<'_>, slug: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT updated_at AS "updated_at: i64" FROM show WHERE slug = ?"#,
+        slug
+    )
+    .fetch_one(executor)
+    .await
+}
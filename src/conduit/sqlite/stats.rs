@@ -0,0 +1,125 @@
+use compact_str::CompactString;
+
+use crate::models::stats::{GuestCount, PkaStats, PkaStatsTotals, YearCount};
+use crate::Repo;
+
+const TOP_GUESTS_LIMIT: i64 = 10;
+
+pub async fn all(repo: &Repo, show: &str) -> Result<PkaStats, sqlx::Error> {
+    let totals = totals(repo, show).await?;
+    let uploads_per_year = uploads_per_year(repo, show).await?;
+    let top_guests = top_guests(repo, show, TOP_GUESTS_LIMIT).await?;
+
+    Ok(PkaStats {
+        totals,
+        uploads_per_year,
+        top_guests,
+    })
+}
+
+pub async fn totals(repo: &Repo, show: &str) -> Result<PkaStatsTotals, sqlx::Error> {
+    sqlx::query_as!(
+        PkaStatsTotals,
+        r#"SELECT
+            (SELECT COUNT(*) FROM pka_episode WHERE show_slug = ?1) AS "episode_count!: i64",
+            (SELECT COALESCE(SUM(length_seconds), 0) FROM pka_youtube_details WHERE show_slug = ?1) AS "total_runtime_seconds!: i64",
+            (SELECT COUNT(*) FROM pka_event WHERE show_slug = ?1) AS "event_count!: i64",
+            (SELECT COALESCE(CAST(COUNT(*) AS REAL) / NULLIF((SELECT COUNT(*) FROM pka_episode WHERE show_slug = ?1), 0), 0.0)
+               FROM pka_event WHERE show_slug = ?1) AS "average_events_per_episode!: f64",
+            (SELECT COALESCE(MAX(c), 0)
+               FROM (SELECT COUNT(*) AS c FROM pka_event WHERE show_slug = ?1 GROUP BY episode_number, episode_part)) AS "max_events_per_episode!: i64",
+            (SELECT COUNT(*)
+               FROM pka_episode e
+               WHERE e.show_slug = ?1
+                 AND NOT EXISTS (SELECT 1 FROM pka_event v
+                                 WHERE v.show_slug = e.show_slug AND v.episode_number = e.number AND v.episode_part = e.part)) AS "episodes_without_timeline!: i64",
+            (SELECT COALESCE(AVG(length_seconds), 0.0) FROM pka_event WHERE show_slug = ?1) AS "average_event_length_seconds!: f64""#,
+        show
+    )
+    .fetch_one(repo)
+    .await
+}
+
+pub async fn uploads_per_year(repo: &Repo, show: &str) -> Result<Vec<YearCount>, sqlx::Error> {
+    sqlx::query_as!(
+        YearCount,
+        r#"SELECT
+            strftime('%Y', upload_date, 'unixepoch') AS "year!: CompactString",
+            COUNT(*)                                 AS "episode_count!: i64"
+          FROM pka_episode
+          WHERE show_slug = ?
+          GROUP BY 1
+          ORDER BY 1 ASC"#,
+        show
+    )
+    .fetch_all(repo)
+    .await
+}
+
+pub async fn top_guests(
+    repo: &Repo,
+    show: &str,
+    limit: i64,
+) -> Result<Vec<GuestCount>, sqlx::Error> {
+    sqlx::query_as!(
+        GuestCount,
+        r#"SELECT
+            name     AS "name!: CompactString",
+            COUNT(*) AS "episode_count!: i64"
+          FROM pka_guest
+          WHERE show_slug = ?
+          GROUP BY name
+          ORDER BY 2 DESC, name ASC
+          LIMIT ?"#,
+        show,
+        limit
+    )
+    .fetch_all(repo)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conduit::sqlite::pka_episode;
+    use crate::db::test_pool;
+    use crate::models::episode_number::EpisodeNumber;
+    use crate::models::pka_episode::PkaEpisode;
+    use crate::models::show::DEFAULT_SHOW;
+
+    #[tokio::test]
+    async fn guests_count_every_appearance() {
+        let pool = test_pool("stats_guests").await;
+
+        for number in [480, 481] {
+            let episode = PkaEpisode::new(
+                DEFAULT_SHOW.into(),
+                EpisodeNumber::new(number),
+                format!("PKA {number}").into(),
+                "HuxCJNLOtoU".into(),
+                1583539200,
+            );
+            pka_episode::insert(&pool, episode).await.unwrap();
+        }
+
+        for (name, number) in [("Kyle", 480), ("Kyle", 481), ("Alex", 481)] {
+            sqlx::query("INSERT INTO pka_guest (name, show_slug, episode_number) VALUES (?, ?, ?)")
+                .bind(name)
+                .bind(DEFAULT_SHOW)
+                .bind(number)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let guests = top_guests(&pool, DEFAULT_SHOW, 10).await.unwrap();
+
+        assert_eq!(
+            guests
+                .iter()
+                .map(|g| (g.name.as_str(), g.episode_count))
+                .collect::<Vec<_>>(),
+            [("Kyle", 2), ("Alex", 1)]
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use compact_str::CompactString;
+use sqlx::SqliteExecutor;
+
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::youtube_drift::{DriftKind, YoutubeDrift};
+
+pub async fn all(executor: impl SqliteExecutor<'_>) -> Result<Vec<YoutubeDrift>, sqlx::Error> {
+    sqlx::query_as!(
+        YoutubeDrift,
+        r#"SELECT
+            video_id          AS "video_id: CompactString",
+            show_slug         AS "show_slug: CompactString",
+            episode_label     AS "episode_number!: EpisodeNumber",
+            kind              AS "kind: DriftKind",
+            stored_value      AS "stored_value: CompactString",
+            current_value     AS "current_value: CompactString",
+            first_detected_at AS "first_detected_at: i64",
+            last_checked_at   AS "last_checked_at: i64"
+          FROM youtube_drift
+          ORDER BY show_slug ASC, episode_number DESC, episode_part DESC, kind ASC"#
+    )
+    .fetch_all(executor)
+    .await
+}
+
+/// Inserts or refreshes a drift, keeping the time it was first detected.
+pub async fn upsert(
+    executor: impl SqliteExecutor<'_>,
+    drift: YoutubeDrift,
+) -> Result<(), sqlx::Error> {
+    let YoutubeDrift {
+        video_id,
+        show_slug,
+        episode_number,
+        kind,
+        stored_value,
+        current_value,
+        first_detected_at,
+        last_checked_at,
+    } = drift;
+
+    let (number, part) = (episode_number.number(), episode_number.part());
+
+    sqlx::query!(
+        r#"INSERT INTO youtube_drift (video_id, show_slug, episode_number, episode_part, kind, stored_value, current_value, first_detected_at, last_checked_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+           ON CONFLICT (video_id, kind) DO UPDATE SET
+               stored_value    = excluded.stored_value,
+               current_value   = excluded.current_value,
+               last_checked_at = excluded.last_checked_at"#,
+        video_id,
+        show_slug,
+        number,
+        part,
+        kind,
+        stored_value,
+        current_value,
+        first_detected_at,
+        last_checked_at
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Removes drifts that were not seen again in the check that ran at `checked_at`.
+pub async fn delete_resolved(
+    executor: impl SqliteExecutor<'_>,
+    checked_at: i64,
+) -> Result<u64, sqlx::Error> {
+    let res = sqlx::query!(
+        "DELETE FROM youtube_drift WHERE last_checked_at < ?",
+        checked_at
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(res.rows_affected())
+}
@@ -0,0 +1,43 @@
+use sqlx::SqliteExecutor;
+
+/// Adds `units` to the day's usage unless that would go over `daily_limit`.
+/// Returns whether the units were spent.
+pub async fn try_spend(
+    executor: impl SqliteExecutor<'_>,
+    quota_day: &str,
+    units: i64,
+    daily_limit: i64,
+) -> Result<bool, sqlx::Error> {
+    let res = sqlx::query!(
+        r#"INSERT INTO youtube_quota (quota_day, units_used)
+           SELECT ?1, ?2 WHERE ?2 <= ?3
+           ON CONFLICT(quota_day) DO UPDATE SET units_used = units_used + excluded.units_used
+           WHERE units_used + excluded.units_used <= ?3"#,
+        quota_day,
+        units,
+        daily_limit
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(res.rows_affected() > 0)
+}
+
+/// Marks the day's quota as used up, e.g. after YouTube reports `quotaExceeded`.
+pub async fn exhaust(
+    executor: impl SqliteExecutor<'_>,
+    quota_day: &str,
+    daily_limit: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"INSERT INTO youtube_quota (quota_day, units_used)
+           VALUES (?1, ?2)
+           ON CONFLICT(quota_day) DO UPDATE SET units_used = MAX(units_used, excluded.units_used)"#,
+        quota_day,
+        daily_limit
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
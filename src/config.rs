@@ -1,3 +1,5 @@
+use std::net::IpAddr;
+
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -6,10 +8,39 @@ pub struct Config {
     #[serde(default = "default_redis_url")]
     pub redis_url: String,
     pub yt_api_key: String,
+    /// Base URL of the YouTube Data API, overridable to point at a mock server.
+    #[serde(default = "default_youtube_api_url")]
+    pub youtube_api_url: String,
+    /// YouTube Data API units the updater may spend per day. Defaults to YouTube's 10,000.
+    #[serde(default = "default_youtube_daily_quota")]
+    pub youtube_daily_quota: i64,
+    /// Base URL for the public feed and watch pages used when the API can't be.
+    #[serde(default = "default_youtube_feed_url")]
+    pub youtube_feed_url: String,
+    /// Apply pending migrations on startup. When off, the server refuses to start with any pending.
+    #[serde(default = "default_run_migrations")]
+    pub run_migrations: bool,
+    /// Public URL of the site, used for links in the sitemap and feeds.
+    #[serde(default = "default_site_url")]
+    pub site_url: String,
+    /// Comma-separated `path[:changefreq[:priority]]` pages listed in the sitemap besides the
+    /// episode pages.
+    #[serde(default = "default_sitemap_pages")]
+    pub sitemap_pages: Vec<String>,
     #[serde(default = "default_bind_address")]
     pub bind_address: String,
+    /// Comma-separated addresses of reverse proxies whose `X-Forwarded-For` and `X-Real-IP`
+    /// headers are believed when telling clients apart.
+    #[serde(default = "default_trusted_proxies")]
+    pub trusted_proxies: Vec<IpAddr>,
     #[serde(default)]
     pub expose_openapi: bool,
+    /// Comma-separated `name:token` entries allowed to review timeline corrections.
+    #[serde(default)]
+    pub moderator_tokens: Vec<String>,
+    /// Comma-separated `name:token` entries allowed to use the admin API.
+    #[serde(default)]
+    pub admin_tokens: Vec<String>,
 }
 
 impl Config {
@@ -22,6 +53,46 @@ fn default_redis_url() -> String {
     "redis://redis:6379".to_string()
 }
 
+fn default_youtube_api_url() -> String {
+    "https://www.googleapis.com/youtube/v3".to_string()
+}
+
+fn default_youtube_feed_url() -> String {
+    "https://www.youtube.com".to_string()
+}
+
+fn default_youtube_daily_quota() -> i64 {
+    10_000
+}
+
+fn default_run_migrations() -> bool {
+    true
+}
+
+fn default_site_url() -> String {
+    "https://www.pkaindex.com".to_string()
+}
+
+fn default_sitemap_pages() -> Vec<String> {
+    [
+        "/:weekly:1.0",
+        "/watch:weekly:1.0",
+        "/watch/latest:weekly:1.0",
+        "/episodes:weekly:0.9",
+        "/events:weekly:0.9",
+        "/watch/random:weekly:0.8",
+    ]
+    .map(str::to_string)
+    .to_vec()
+}
+
+fn default_trusted_proxies() -> Vec<IpAddr> {
+    vec![
+        IpAddr::from([127, 0, 0, 1]),
+        IpAddr::from([0, 0, 0, 0, 0, 0, 0, 1]),
+    ]
+}
+
 fn default_bind_address() -> String {
     "0.0.0.0:1234".to_string()
 }
@@ -0,0 +1,505 @@
+use axum::http::StatusCode;
+use chrono::Utc;
+use compact_str::CompactString;
+use sqlx::SqliteConnection;
+use tracing::error;
+
+use crate::conduit::redis::stats_cache;
+use crate::conduit::sqlite::{pending_edit, pka_episode, pka_event, pka_youtube_details};
+use crate::models::errors::ApiError;
+use crate::models::pending_edit::{
+    EditKind, EditStatus, EditSubmission, NewPendingEdit, PendingEdit,
+};
+use crate::models::pka_event::PkaEvent;
+use crate::models::show::DEFAULT_SHOW;
+use crate::redis_db::RedisDb;
+use crate::updater::pka::compute_event_lengths;
+use crate::updater::timeline::{normalize_timestamp, Timeline, TimelineEntry};
+use crate::workers::events::refresh_events_index;
+use crate::Repo;
+
+const MAX_DESCRIPTION_LENGTH: usize = 500;
+const MAX_REASON_LENGTH: usize = 1000;
+
+pub async fn submit(repo: &Repo, submission: EditSubmission) -> Result<PendingEdit, ApiError> {
+    let edit = validate(submission, Utc::now().timestamp())
+        .map_err(|message| ApiError::new(message, StatusCode::BAD_REQUEST))?;
+
+    pka_episode::find(repo, DEFAULT_SHOW, edit.episode_number).await?;
+
+    if let Some(event_id) = &edit.event_id {
+        let events = pka_event::for_episode(repo, DEFAULT_SHOW, edit.episode_number).await?;
+
+        if !events.iter().any(|e| e.event_id == *event_id) {
+            return Err(ApiError::new(
+                "Couldn't find event in episode",
+                StatusCode::NOT_FOUND,
+            ));
+        }
+    }
+
+    let id = pending_edit::insert(repo, edit).await?;
+    let res = pending_edit::find(repo, id).await?;
+
+    Ok(res)
+}
+
+pub async fn approve(
+    repo: &Repo,
+    redis: &RedisDb,
+    id: i64,
+    reviewer: &str,
+    note: Option<&str>,
+) -> Result<PendingEdit, ApiError> {
+    let mut tx = repo.begin().await?;
+
+    let res = approve_in(&mut tx, id, reviewer, note).await?;
+
+    tx.commit().await?;
+
+    if let Err(e) = refresh_events_index(repo).await {
+        error!("Failed to refresh events index after approving edit {id}: {e}");
+    }
+
+    if let Err(e) = stats_cache::invalidate(redis).await {
+        error!("Failed to invalidate stats cache after approving edit {id}: {e}");
+    }
+
+    Ok(res)
+}
+
+/// Records an edit made by a trusted reviewer and applies it straight away, so it still
+/// shows up in the edit history.
+pub async fn apply_directly(
+    repo: &Repo,
+    redis: &RedisDb,
+    submission: EditSubmission,
+    reviewer: &str,
+) -> Result<PendingEdit, ApiError> {
+    let edit = validate(submission, Utc::now().timestamp())
+        .map_err(|message| ApiError::new(message, StatusCode::BAD_REQUEST))?;
+
+    let mut tx = repo.begin().await?;
+
+    let id = pending_edit::insert(&mut *tx, edit).await?;
+    let res = approve_in(&mut tx, id, reviewer, None).await?;
+
+    tx.commit().await?;
+
+    if let Err(e) = refresh_events_index(repo).await {
+        error!("Failed to refresh events index after applying edit {id}: {e}");
+    }
+
+    if let Err(e) = stats_cache::invalidate(redis).await {
+        error!("Failed to invalidate stats cache after applying edit {id}: {e}");
+    }
+
+    Ok(res)
+}
+
+async fn approve_in(
+    conn: &mut SqliteConnection,
+    id: i64,
+    reviewer: &str,
+    note: Option<&str>,
+) -> Result<PendingEdit, ApiError> {
+    let edit = find_pending(&mut *conn, id).await?;
+
+    apply(&mut *conn, &edit).await?;
+
+    pending_edit::mark_reviewed(
+        &mut *conn,
+        id,
+        EditStatus::Approved,
+        Utc::now().timestamp(),
+        reviewer,
+        note,
+    )
+    .await?;
+
+    let res = pending_edit::find(&mut *conn, id).await?;
+
+    Ok(res)
+}
+
+pub async fn reject(
+    repo: &Repo,
+    id: i64,
+    reviewer: &str,
+    note: Option<&str>,
+) -> Result<PendingEdit, ApiError> {
+    let mut tx = repo.begin().await?;
+
+    find_pending(&mut tx, id).await?;
+
+    pending_edit::mark_reviewed(
+        &mut *tx,
+        id,
+        EditStatus::Rejected,
+        Utc::now().timestamp(),
+        reviewer,
+        note,
+    )
+    .await?;
+
+    let res = pending_edit::find(&mut *tx, id).await?;
+
+    tx.commit().await?;
+
+    Ok(res)
+}
+
+async fn find_pending(conn: &mut SqliteConnection, id: i64) -> Result<PendingEdit, ApiError> {
+    let edit = pending_edit::find(&mut *conn, id).await?;
+
+    if edit.status != EditStatus::Pending {
+        return Err(ApiError::new(
+            "Edit has already been reviewed",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    Ok(edit)
+}
+
+/// Applies an edit to the episode's events and rewrites them with recomputed lengths.
+/// Corrections are only taken for PKA episodes.
+async fn apply(conn: &mut SqliteConnection, edit: &PendingEdit) -> Result<(), ApiError> {
+    let show = DEFAULT_SHOW;
+    let number = edit.episode_number;
+
+    let episode = pka_episode::find(&mut *conn, show, number).await?;
+    let mut events = pka_event::for_episode(&mut *conn, show, number).await?;
+
+    let video_length_seconds = pka_youtube_details::find_length(&mut *conn, show, number).await?;
+
+    let ep_length_seconds = match video_length_seconds {
+        Some(length) => length,
+        None => events
+            .iter()
+            .map(|e| e.timestamp() + e.length_seconds())
+            .max()
+            .unwrap_or_default(),
+    };
+
+    let conflict = |message: &str| ApiError::new(message, StatusCode::CONFLICT);
+
+    match edit.kind {
+        EditKind::Add => {
+            let (Some(timestamp), Some(description)) = (edit.timestamp, &edit.description) else {
+                return Err(conflict("Edit is missing a timestamp or description"));
+            };
+
+            events.push(PkaEvent::new(
+                PkaEvent::id_for(show, number, timestamp),
+                episode.show_slug.clone(),
+                number,
+                timestamp,
+                description.clone(),
+                0,
+                episode.upload_date,
+            ));
+        }
+        EditKind::Edit => {
+            let event = events
+                .iter_mut()
+                .find(|e| Some(&e.event_id) == edit.event_id.as_ref())
+                .ok_or_else(|| conflict("Event no longer exists"))?;
+
+            if let Some(timestamp) = edit.timestamp {
+                event.timestamp = timestamp;
+                event.event_id = PkaEvent::id_for(show, number, timestamp);
+            }
+
+            if let Some(description) = &edit.description {
+                event.description = description.clone();
+            }
+        }
+        EditKind::Delete => {
+            let before = events.len();
+
+            events.retain(|e| Some(&e.event_id) != edit.event_id.as_ref());
+
+            if events.len() == before {
+                return Err(conflict("Event no longer exists"));
+            }
+        }
+    }
+
+    compute_event_lengths(&mut events, ep_length_seconds);
+
+    let timeline = Timeline {
+        entries: events
+            .iter()
+            .map(|e| TimelineEntry {
+                start: e.timestamp(),
+                end: None,
+                description: e.description.clone(),
+                confidence: 100,
+            })
+            .collect(),
+        ..Timeline::default()
+    };
+
+    let (_, warnings) = timeline.validate(video_length_seconds.unwrap_or_default());
+
+    // Problems already in the stored timeline shouldn't block unrelated corrections.
+    if let Some(warning) = warnings
+        .iter()
+        .find(|w| edit.timestamp.is_some_and(|t| w.timestamp() == Some(t)))
+    {
+        return Err(ApiError::new(
+            format!("Edit would leave an invalid timeline: {warning}"),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ));
+    }
+
+    pka_event::delete_for_episode(&mut *conn, show, number).await?;
+
+    for event in events {
+        pka_event::insert(&mut *conn, event).await?;
+    }
+
+    Ok(())
+}
+
+fn validate(submission: EditSubmission, submitted_at: i64) -> Result<NewPendingEdit, String> {
+    let EditSubmission {
+        kind,
+        episode_number,
+        event_id,
+        timestamp,
+        description,
+        reason,
+    } = submission;
+
+    let timestamp = timestamp
+        .map(|t| normalize_timestamp(t.trim()).map_err(|e| e.to_string()))
+        .transpose()?;
+
+    if timestamp.is_some_and(|t| t < 0) {
+        return Err("Timestamp cannot be negative".to_owned());
+    }
+
+    let description = non_empty(description);
+    let reason = non_empty(reason);
+
+    if description
+        .as_ref()
+        .is_some_and(|d| d.chars().count() > MAX_DESCRIPTION_LENGTH)
+    {
+        return Err(format!(
+            "Description cannot be longer than {MAX_DESCRIPTION_LENGTH} characters"
+        ));
+    }
+
+    if reason
+        .as_ref()
+        .is_some_and(|r| r.chars().count() > MAX_REASON_LENGTH)
+    {
+        return Err(format!(
+            "Reason cannot be longer than {MAX_REASON_LENGTH} characters"
+        ));
+    }
+
+    let event_id = non_empty(event_id);
+
+    let (event_id, timestamp, description) = match kind {
+        EditKind::Add => {
+            if timestamp.is_none() || description.is_none() {
+                return Err("New events need a timestamp and a description".to_owned());
+            }
+
+            (None, timestamp, description)
+        }
+        EditKind::Edit => {
+            if event_id.is_none() {
+                return Err("Edits need an event id".to_owned());
+            }
+
+            if timestamp.is_none() && description.is_none() {
+                return Err("Edits need a new timestamp or description".to_owned());
+            }
+
+            (event_id, timestamp, description)
+        }
+        EditKind::Delete => {
+            if event_id.is_none() {
+                return Err("Deletions need an event id".to_owned());
+            }
+
+            (event_id, None, None)
+        }
+    };
+
+    Ok(NewPendingEdit {
+        kind,
+        episode_number,
+        event_id,
+        timestamp,
+        description,
+        reason,
+        submitted_at,
+    })
+}
+
+fn non_empty(value: Option<CompactString>) -> Option<CompactString> {
+    value
+        .map(|v| CompactString::from(v.trim()))
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::response::IntoResponse;
+
+    use super::*;
+    use crate::db::test_pool;
+    use crate::models::episode_number::EpisodeNumber;
+    use crate::models::pka_episode::PkaEpisode;
+    use crate::models::pka_youtube_details::PkaYoutubeDetails;
+
+    fn submission(kind: EditKind) -> EditSubmission {
+        EditSubmission {
+            kind,
+            episode_number: EpisodeNumber::new(481),
+            event_id: None,
+            timestamp: None,
+            description: None,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn validate_add() {
+        let res = validate(
+            EditSubmission {
+                event_id: Some("481-10".into()),
+                timestamp: Some(" 1:02:03 ".into()),
+                description: Some("  Woody buys a boat ".into()),
+                ..submission(EditKind::Add)
+            },
+            100,
+        )
+        .unwrap();
+
+        assert_eq!(
+            res,
+            NewPendingEdit {
+                kind: EditKind::Add,
+                episode_number: EpisodeNumber::new(481),
+                event_id: None,
+                timestamp: Some(3723),
+                description: Some("Woody buys a boat".into()),
+                reason: None,
+                submitted_at: 100,
+            }
+        );
+
+        assert!(validate(submission(EditKind::Add), 0).is_err());
+        assert!(validate(
+            EditSubmission {
+                timestamp: Some("abc".into()),
+                description: Some("Boat".into()),
+                ..submission(EditKind::Add)
+            },
+            0
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn validate_edit_and_delete() {
+        assert!(validate(
+            EditSubmission {
+                description: Some("Boat".into()),
+                ..submission(EditKind::Edit)
+            },
+            0
+        )
+        .is_err());
+
+        assert!(validate(
+            EditSubmission {
+                event_id: Some("481-10".into()),
+                ..submission(EditKind::Edit)
+            },
+            0
+        )
+        .is_err());
+
+        let delete = validate(
+            EditSubmission {
+                event_id: Some("481-10".into()),
+                description: Some("ignored".into()),
+                ..submission(EditKind::Delete)
+            },
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(delete.event_id.as_deref(), Some("481-10"));
+        assert_eq!(delete.description, None);
+
+        assert!(validate(submission(EditKind::Delete), 0).is_err());
+    }
+
+    #[tokio::test]
+    async fn reject_edits_past_the_end_of_the_video() {
+        let pool = test_pool("corrections_past_end").await;
+        let number = EpisodeNumber::new(481);
+
+        let episode = PkaEpisode::new(
+            DEFAULT_SHOW.into(),
+            number,
+            "PKA 481".into(),
+            "HuxCJNLOtoU".into(),
+            1583539200,
+        );
+        pka_episode::insert(&pool, episode).await.unwrap();
+
+        let details = PkaYoutubeDetails::new(
+            "HuxCJNLOtoU".into(),
+            DEFAULT_SHOW.into(),
+            number,
+            "PKA 481".into(),
+            3600,
+        );
+        pka_youtube_details::insert(&pool, details).await.unwrap();
+
+        let add = |timestamp: &str| {
+            validate(
+                EditSubmission {
+                    timestamp: Some(timestamp.into()),
+                    description: Some("Woody buys a boat".into()),
+                    ..submission(EditKind::Add)
+                },
+                100,
+            )
+            .unwrap()
+        };
+
+        let mut conn = pool.acquire().await.unwrap();
+
+        let id = pending_edit::insert(&mut *conn, add("1:00:00"))
+            .await
+            .unwrap();
+        let res = approve_in(&mut conn, id, "moderator", None).await;
+
+        assert_eq!(
+            res.unwrap_err().into_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert!(pka_event::for_episode(&mut *conn, DEFAULT_SHOW, number)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let id = pending_edit::insert(&mut *conn, add("59:59"))
+            .await
+            .unwrap();
+        let res = approve_in(&mut conn, id, "moderator", None).await.unwrap();
+
+        assert_eq!(res.status, EditStatus::Approved);
+    }
+}
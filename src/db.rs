@@ -1,9 +1,25 @@
 use std::str::FromStr;
 use std::time::Duration;
 
+use sqlx::migrate::{Migrate, MigrateError, Migrator};
 use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
 use sqlx::SqlitePool;
 
+/// Migrations in `migrations/`, embedded at compile time.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+#[derive(Debug, thiserror::Error)]
+pub enum SchemaError {
+    #[error(
+        "Database has migration {0}, which this build doesn't know about. Refusing to run against a newer schema"
+    )]
+    NewerSchema(i64),
+    #[error("Database has pending migrations {0:?}; run the `migrate` command to apply them")]
+    Pending(Vec<i64>),
+    #[error("Failed to migrate database: {0}")]
+    Migrate(#[from] MigrateError),
+}
+
 pub async fn create_pool(database_url: &str) -> sqlx::Result<SqlitePool> {
     let connect_options = SqliteConnectOptions::from_str(database_url)?
         .create_if_missing(true)
@@ -17,3 +33,130 @@ pub async fn create_pool(database_url: &str) -> sqlx::Result<SqlitePool> {
         .connect_with(connect_options)
         .await
 }
+
+/// Versions of the embedded migrations the database hasn't applied yet. Fails if the database
+/// has a migration this build doesn't know, e.g. after rolling back to an older release.
+pub async fn pending_migrations(pool: &SqlitePool) -> Result<Vec<i64>, SchemaError> {
+    let mut conn = pool.acquire().await.map_err(MigrateError::from)?;
+
+    conn.ensure_migrations_table().await?;
+
+    let applied = conn.list_applied_migrations().await?;
+
+    let unknown = applied
+        .iter()
+        .map(|a| a.version)
+        .filter(|&version| MIGRATOR.iter().all(|m| m.version != version))
+        .max();
+
+    if let Some(version) = unknown {
+        return Err(SchemaError::NewerSchema(version));
+    }
+
+    Ok(MIGRATOR
+        .iter()
+        .map(|m| m.version)
+        .filter(|&version| applied.iter().all(|a| a.version != version))
+        .collect())
+}
+
+/// Fails unless every migration this build knows about has been applied.
+pub async fn ensure_up_to_date(pool: &SqlitePool) -> Result<(), SchemaError> {
+    let pending = pending_migrations(pool).await?;
+
+    if !pending.is_empty() {
+        return Err(SchemaError::Pending(pending));
+    }
+
+    Ok(())
+}
+
+/// Applies pending migrations and returns how many there were.
+pub async fn migrate(pool: &SqlitePool) -> Result<usize, SchemaError> {
+    let pending = pending_migrations(pool).await?;
+
+    if !pending.is_empty() {
+        MIGRATOR.run(pool).await?;
+    }
+
+    Ok(pending.len())
+}
+
+/// Latest migration embedded in this build.
+pub fn schema_version() -> i64 {
+    MIGRATOR.iter().map(|m| m.version).max().unwrap_or_default()
+}
+
+/// Fresh, fully migrated database in the temp directory for tests that need real queries.
+#[cfg(test)]
+pub async fn test_pool(name: &str) -> SqlitePool {
+    let path = std::env::temp_dir().join(format!("pka_test_{name}_{}.sqlite3", std::process::id()));
+
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+    }
+
+    let pool = create_pool(&format!("sqlite://{}", path.display()))
+        .await
+        .expect("Failed to create test database");
+
+    MIGRATOR.run(&pool).await.expect("Failed to run migrations");
+
+    pool
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use sqlx::migrate::{Migration, MigrationType};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn refuses_newer_schema() {
+        let pool = test_pool("newer_schema").await;
+
+        assert!(pending_migrations(&pool).await.unwrap().is_empty());
+        assert_eq!(migrate(&pool).await.unwrap(), 0);
+
+        let future = Migration::new(
+            99991231000000,
+            Cow::Borrowed("future"),
+            MigrationType::Simple,
+            Cow::Borrowed("SELECT 1"),
+            false,
+        );
+
+        let mut conn = pool.acquire().await.unwrap();
+        conn.apply(&future).await.unwrap();
+        drop(conn);
+
+        assert!(matches!(
+            migrate(&pool).await,
+            Err(SchemaError::NewerSchema(99991231000000))
+        ));
+    }
+
+    #[tokio::test]
+    async fn refuses_pending_migrations() {
+        let path = std::env::temp_dir().join(format!(
+            "pka_test_unmigrated_{}.sqlite3",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let pool = create_pool(&format!("sqlite://{}", path.display()))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            ensure_up_to_date(&pool).await,
+            Err(SchemaError::Pending(pending)) if pending.len() == MIGRATOR.iter().count()
+        ));
+
+        migrate(&pool).await.unwrap();
+
+        assert!(ensure_up_to_date(&pool).await.is_ok());
+    }
+}
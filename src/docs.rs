@@ -1,48 +1,166 @@
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
 use utoipa::openapi::OpenApi;
-use utoipa::OpenApi as OpenApiTrait;
+use utoipa::{Modify, OpenApi as OpenApiTrait};
 
-use crate::handlers::{episode, event, search, static_files};
+use crate::handlers::{
+    admin, corrections, episode, event, export, feed, on_this_day, reports, search, show,
+    static_files, stats,
+};
+use crate::models::admin::{
+    AdminEventUpdate, CheckNewEpisodesResult, FlushCacheResult, ReExtractRequest,
+};
+use crate::models::episode_batch::{EpisodeBatchItem, EpisodeBatchRequest};
 use crate::models::errors::ErrorResponseBody;
+use crate::models::export::{ExportEpisode, ExportEvent};
+use crate::models::import::{CuratedRowError, CuratedTimelineReport, CuratedTimelineRow};
+use crate::models::ingestion_log::{IngestionLogEntry, IngestionOutcome};
+use crate::models::on_this_day::OnThisDayEpisode;
+use crate::models::pending_edit::{
+    EditKind, EditStatus, EditSubmission, PendingEdit, ReviewRequest,
+};
 use crate::models::pka_episode::PkaEpisode;
 use crate::models::pka_episode_with_all::PkaEpisodeWithAll;
 use crate::models::pka_event::PkaEvent;
 use crate::models::pka_youtube_details::PkaYoutubeDetails;
 use crate::models::search::{PkaEventSearchResult, SearchQuery};
+use crate::models::show::Show;
+use crate::models::stats::{GuestCount, PkaStats, PkaStatsTotals, YearCount};
 use crate::models::success_response::SuccessResponse;
+use crate::models::timeline_coverage::{
+    EpisodeEventCount, EventOverrun, TimelineCoverageReport, TimelineGap,
+};
+use crate::models::timeline_diff::{EventChange, TimelineDiff};
+use crate::models::timeline_warning::TimelineWarning;
+use crate::models::youtube_drift::{DriftKind, YoutubeDrift};
 
 #[derive(OpenApiTrait)]
 #[openapi(
     info(title = "PKA Index API", version = "1.0"),
     paths(
+        admin::check_new_episodes,
+        admin::preview_reextract_pka_episode,
+        admin::reextract_pka_episode,
+        admin::edit_pka_event,
+        admin::delete_pka_event,
+        admin::preview_timeline_import,
+        admin::import_timelines,
+        admin::flush_caches,
+        admin::drift_report,
+        admin::ingestion_log,
+        corrections::submit_correction,
+        corrections::pending_corrections,
+        corrections::approve_correction,
+        corrections::reject_correction,
         episode::watch_pka_episode,
         episode::find_pka_episode_youtube_link,
         episode::latest_pka_episode,
         episode::random_pka_episode,
+        episode::find_pka_episode_by_video_id,
+        episode::find_pka_episode_by_title,
+        episode::batch_pka_episodes,
         event::random_pka_event,
+        export::export_dataset,
+        export::export_snapshot,
+        feed::episodes_rss,
+        feed::episodes_atom,
+        feed::episode_chapters,
+        on_this_day::on_this_day,
+        reports::timeline_coverage,
         search::search_pka_episode,
         search::search_pka_event,
+        show::list_shows,
+        show::find_show,
         static_files::robots_txt,
-        static_files::sitemap_xml
+        static_files::sitemap_xml,
+        stats::archive_stats
     ),
     components(schemas(
         SuccessResponse<PkaEpisodeWithAll>,
         SuccessResponse<String>,
         SuccessResponse<PkaEventSearchResult>,
+        SuccessResponse<Vec<OnThisDayEpisode>>,
+        SuccessResponse<PkaStats>,
+        SuccessResponse<Vec<EpisodeBatchItem>>,
+        EpisodeBatchRequest,
+        EpisodeBatchItem,
+        SuccessResponse<TimelineCoverageReport>,
+        TimelineCoverageReport,
+        EpisodeEventCount,
+        EventOverrun,
+        TimelineGap,
+        SuccessResponse<PendingEdit>,
+        SuccessResponse<Vec<PendingEdit>>,
+        PendingEdit,
+        EditKind,
+        EditStatus,
+        EditSubmission,
+        ReviewRequest,
+        SuccessResponse<CheckNewEpisodesResult>,
+        SuccessResponse<FlushCacheResult>,
+        SuccessResponse<TimelineDiff>,
+        TimelineDiff,
+        EventChange,
+        AdminEventUpdate,
+        ReExtractRequest,
+        SuccessResponse<CuratedTimelineReport>,
+        CuratedTimelineReport,
+        CuratedTimelineRow,
+        CuratedRowError,
+        CheckNewEpisodesResult,
+        FlushCacheResult,
+        SuccessResponse<Vec<YoutubeDrift>>,
+        YoutubeDrift,
+        DriftKind,
+        SuccessResponse<Vec<Show>>,
+        SuccessResponse<Show>,
+        Show,
+        SuccessResponse<Vec<IngestionLogEntry>>,
+        IngestionLogEntry,
+        IngestionOutcome,
+        TimelineWarning,
+        ExportEpisode,
+        ExportEvent,
         ErrorResponseBody,
+        OnThisDayEpisode,
         PkaEpisode,
         PkaEvent,
         PkaYoutubeDetails,
-        SearchQuery
+        SearchQuery,
+        PkaStats,
+        PkaStatsTotals,
+        YearCount,
+        GuestCount
     )),
+    modifiers(&BearerSecurity),
     tags(
+        (name = "Admin", description = "Operates on PKA episodes only."),
+        (name = "Corrections", description = "Corrections can only be made to PKA episodes."),
         (name = "Episodes"),
         (name = "Events"),
-        (name = "Search"),
-        (name = "Static")
+        (name = "Export", description = "Exports cover every show; each row carries its show's slug."),
+        (name = "Feeds", description = "The feeds list PKA episodes only."),
+        (name = "Reports"),
+        (name = "Search", description = "Only PKA episodes and events are searchable."),
+        (name = "Shows"),
+        (name = "Static"),
+        (name = "Stats")
     )
 )]
 pub struct ApiDoc;
 
+struct BearerSecurity;
+
+impl Modify for BearerSecurity {
+    fn modify(&self, openapi: &mut OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+
+        components.add_security_scheme(
+            "bearer_token",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
 pub fn openapi() -> OpenApi {
     ApiDoc::openapi()
 }
@@ -0,0 +1,283 @@
+//! Dataset downloads for researchers and mirror sites: every episode and event streamed
+//! straight from SQLite as JSON, CSV or NDJSON, and a periodically refreshed SQLite snapshot.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Context;
+use axum::body::{Body, Bytes};
+use futures_util::stream::{self, BoxStream};
+use futures_util::StreamExt;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, SqliteConnection};
+use tokio::sync::{mpsc, Mutex};
+use tracing::{error, info};
+
+use crate::conduit::sqlite::export;
+use crate::models::export::{ExportFormat, ExportRow};
+use crate::Repo;
+
+/// Snapshots older than this are rebuilt on the next download.
+pub const SNAPSHOT_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 6);
+
+const SNAPSHOT_FILE_NAME: &str = "pka_index_snapshot.sqlite3";
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Only one snapshot is built at a time; later callers wait and reuse it.
+static SNAPSHOT_LOCK: Mutex<()> = Mutex::const_new(());
+
+pub type RowStream<T> = for<'a> fn(&'a Repo) -> BoxStream<'a, Result<T, sqlx::Error>>;
+
+/// Streams rows into a response body as they're read, so the export is never held in memory.
+/// A database error part way through aborts the response.
+pub fn stream_rows<T>(repo: Repo, format: ExportFormat, rows: RowStream<T>) -> Body
+where
+    T: ExportRow + Send + 'static,
+{
+    let (tx, mut rx) = mpsc::channel::<Result<Bytes, sqlx::Error>>(4);
+
+    tokio::spawn(async move {
+        let mut rows = rows(&repo);
+        let mut buf = Vec::with_capacity(CHUNK_SIZE);
+        let mut first = true;
+
+        match format {
+            ExportFormat::Json => buf.push(b'['),
+            ExportFormat::Csv => write_csv_record(&mut buf, T::CSV_HEADER),
+            ExportFormat::Ndjson => {}
+        }
+
+        while let Some(row) = rows.next().await {
+            let row = match row {
+                Ok(row) => row,
+                Err(e) => {
+                    error!("Failed to stream export: {e}");
+                    let _ = tx.send(Err(e)).await;
+                    return;
+                }
+            };
+
+            write_row(&mut buf, format, &row, first);
+            first = false;
+
+            if buf.len() >= CHUNK_SIZE {
+                let chunk = std::mem::replace(&mut buf, Vec::with_capacity(CHUNK_SIZE));
+
+                // The client went away.
+                if tx.send(Ok(chunk.into())).await.is_err() {
+                    return;
+                }
+            }
+        }
+
+        if format == ExportFormat::Json {
+            buf.extend_from_slice(b"]\n");
+        }
+
+        let _ = tx.send(Ok(buf.into())).await;
+    });
+
+    Body::from_stream(stream::poll_fn(move |cx| rx.poll_recv(cx)))
+}
+
+fn write_row<T: ExportRow>(buf: &mut Vec<u8>, format: ExportFormat, row: &T, first: bool) {
+    match format {
+        ExportFormat::Json => {
+            if !first {
+                buf.push(b',');
+            }
+            buf.push(b'\n');
+            write_json(buf, row);
+        }
+        ExportFormat::Ndjson => {
+            write_json(buf, row);
+            buf.push(b'\n');
+        }
+        ExportFormat::Csv => write_csv_record(buf, &row.csv_record()),
+    }
+}
+
+fn write_json<T: ExportRow>(buf: &mut Vec<u8>, row: &T) {
+    // Rows are plain structs of strings and numbers, which always serialize.
+    serde_json::to_writer(&mut *buf, row).expect("Export rows serialize to JSON");
+}
+
+/// Writes one RFC 4180 record, quoting fields that contain a delimiter, quote or line break.
+fn write_csv_record(buf: &mut Vec<u8>, fields: &[impl AsRef<str>]) {
+    for (i, field) in fields.iter().enumerate() {
+        let field = field.as_ref();
+
+        if i > 0 {
+            buf.push(b',');
+        }
+
+        if field.contains([',', '"', '\n', '\r']) {
+            buf.push(b'"');
+            buf.extend_from_slice(field.replace('"', "\"\"").as_bytes());
+            buf.push(b'"');
+        } else {
+            buf.extend_from_slice(field.as_bytes());
+        }
+    }
+
+    buf.extend_from_slice(b"\r\n");
+}
+
+/// Path of a snapshot no older than [`SNAPSHOT_MAX_AGE`], building a new one if needed.
+pub async fn snapshot(repo: &Repo) -> anyhow::Result<(PathBuf, SystemTime)> {
+    let _guard = SNAPSHOT_LOCK.lock().await;
+
+    let path = std::env::temp_dir().join(SNAPSHOT_FILE_NAME);
+
+    if let Ok(modified) = tokio::fs::metadata(&path).await.and_then(|m| m.modified()) {
+        if modified.elapsed().is_ok_and(|age| age < SNAPSHOT_MAX_AGE) {
+            return Ok((path, modified));
+        }
+    }
+
+    let tmp = path.with_extension("sqlite3.tmp");
+    let _ = tokio::fs::remove_file(&tmp).await;
+
+    let tmp_str = tmp.to_str().context("Snapshot path isn't valid UTF-8")?;
+
+    export::vacuum_into(repo, tmp_str)
+        .await
+        .context("Failed to copy database")?;
+
+    strip_private_tables(tmp_str)
+        .await
+        .context("Failed to strip private tables from snapshot")?;
+
+    // Downloads still reading the old snapshot keep their file handle.
+    tokio::fs::rename(&tmp, &path).await?;
+
+    info!("Built database snapshot at {}", path.display());
+
+    Ok((path, SystemTime::now()))
+}
+
+/// Only the tables in [`export::PUBLIC_TABLES`] are kept, so e.g. edit submissions, ingestion
+/// history and quota bookkeeping never end up in a download.
+async fn strip_private_tables(path: &str) -> anyhow::Result<()> {
+    let options = SqliteConnectOptions::from_str(path)?;
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+
+    export::drop_private_tables(&mut conn).await?;
+
+    conn.close().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conduit::sqlite::{pka_episode, pka_event};
+    use crate::db::test_pool;
+    use crate::models::episode_number::EpisodeNumber;
+    use crate::models::export::ExportEvent;
+    use crate::models::pka_episode::PkaEpisode;
+    use crate::models::pka_event::PkaEvent;
+    use crate::models::show::DEFAULT_SHOW;
+
+    #[tokio::test]
+    async fn stream_events() {
+        let pool = test_pool("export_stream").await;
+        let number = EpisodeNumber::new(481);
+
+        let episode = PkaEpisode::new(
+            DEFAULT_SHOW.into(),
+            number,
+            "PKA 481".into(),
+            "HuxCJNLOtoU".into(),
+            1583539200,
+        );
+        pka_episode::insert(&pool, episode).await.unwrap();
+
+        for (timestamp, description) in [(0, "Intro"), (750, "Woody, boat")] {
+            let event = PkaEvent::new(
+                PkaEvent::id_for(DEFAULT_SHOW, number, timestamp),
+                DEFAULT_SHOW.into(),
+                number,
+                timestamp,
+                description.into(),
+                750,
+                1583539200,
+            );
+            pka_event::insert(&pool, event).await.unwrap();
+        }
+
+        let read = |format| {
+            let body = stream_rows::<ExportEvent>(pool.clone(), format, export::events);
+
+            async move {
+                let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+
+                String::from_utf8(bytes.to_vec()).unwrap()
+            }
+        };
+
+        let json = read(ExportFormat::Json).await;
+        let rows = serde_json::from_str::<Vec<serde_json::Value>>(&json).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1]["description"], "Woody, boat");
+        assert_eq!(rows[1]["episodeNumber"], 481);
+
+        assert_eq!(read(ExportFormat::Ndjson).await.lines().count(), 2);
+
+        let csv = read(ExportFormat::Csv).await;
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], ExportEvent::CSV_HEADER.join(","));
+
+        assert_eq!(
+            lines[2],
+            "481-750,pka,481,750,\"Woody, boat\",750,1583539200"
+        );
+    }
+
+    #[tokio::test]
+    async fn snapshot_keeps_only_public_tables() {
+        let pool = test_pool("export_snapshot").await;
+
+        let path =
+            std::env::temp_dir().join(format!("pka_test_snapshot_{}.sqlite3", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let path = path.to_str().unwrap();
+
+        export::vacuum_into(&pool, path).await.unwrap();
+        strip_private_tables(path).await.unwrap();
+
+        let options = SqliteConnectOptions::from_str(path).unwrap();
+        let mut conn = SqliteConnection::connect_with(&options).await.unwrap();
+
+        let mut tables = sqlx::query_scalar::<_, String>(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+        )
+        .fetch_all(&mut conn)
+        .await
+        .unwrap();
+        tables.sort();
+
+        let mut expected = export::PUBLIC_TABLES.to_vec();
+        expected.sort();
+
+        assert_eq!(tables, expected);
+    }
+
+    #[test]
+    fn csv_quoting() {
+        let mut buf = Vec::new();
+
+        write_csv_record(
+            &mut buf,
+            &["481", "Woody, Kyle", "He said \"boat\"", "a\nb"],
+        );
+
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "481,\"Woody, Kyle\",\"He said \"\"boat\"\"\",\"a\nb\"\r\n"
+        );
+    }
+}
@@ -1,9 +1,20 @@
-use axum::extract::{FromRequest, FromRequestParts};
+use std::net::{IpAddr, SocketAddr};
+
+use axum::extract::{ConnectInfo, FromRequest, FromRequestParts, RawPathParams};
+use axum::http::request::Parts;
+use axum::http::{header, StatusCode};
 use axum::response::IntoResponse;
 use axum::Json;
+use compact_str::{CompactString, ToCompactString};
 use serde::Serialize;
+use tracing::error;
 
+use crate::app_state::AppState;
+use crate::auth::{ApiToken, Role};
+use crate::conduit::redis::rate_limit;
+use crate::conduit::sqlite::show;
 use crate::models::errors::ApiError;
+use crate::models::show::{Show, DEFAULT_SHOW};
 
 #[derive(FromRequest)]
 #[from_request(via(axum::Json), rejection(ApiError))]
@@ -18,3 +29,278 @@ impl<T: Serialize> IntoResponse for AppJson<T> {
 #[derive(FromRequestParts)]
 #[from_request(via(axum::extract::Path), rejection(ApiError))]
 pub struct AppPath<T>(pub T);
+
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(ApiError))]
+pub struct AppQuery<T>(pub T);
+
+/// Show named by the `{slug}` path parameter, or PKA on routes without one.
+pub struct CurrentShow(pub Show);
+
+impl FromRequestParts<AppState> for CurrentShow {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, ApiError> {
+        let slug = RawPathParams::from_request_parts(parts, state)
+            .await
+            .ok()
+            .and_then(|params| {
+                params
+                    .iter()
+                    .find(|(key, _)| *key == "slug")
+                    .map(|(_, value)| value.to_compact_string())
+            })
+            .unwrap_or_else(|| DEFAULT_SHOW.to_compact_string());
+
+        let show = show::find(state.db.as_ref(), &slug)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => {
+                    ApiError::new("Couldn't find show", StatusCode::NOT_FOUND)
+                }
+                e => e.into(),
+            })?;
+
+        Ok(CurrentShow(show))
+    }
+}
+
+/// Dataset exports allowed per client in each [`EXPORT_RATE_LIMIT_WINDOW_SECONDS`].
+pub const EXPORT_RATE_LIMIT: u64 = 20;
+pub const EXPORT_RATE_LIMIT_WINDOW_SECONDS: i64 = 60 * 60;
+
+/// Correction submissions allowed per client in each [`CORRECTION_RATE_LIMIT_WINDOW_SECONDS`].
+pub const CORRECTION_RATE_LIMIT: u64 = 10;
+pub const CORRECTION_RATE_LIMIT_WINDOW_SECONDS: i64 = 60 * 60;
+
+/// Limits how often a client can download dataset exports. Clients are told apart by their
+/// address (see [`client_address`]), and requests are let through if Redis can't be reached.
+pub struct ExportRateLimit;
+
+impl FromRequestParts<AppState> for ExportRateLimit {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, ApiError> {
+        if !within_rate_limit(
+            parts,
+            state,
+            "export",
+            EXPORT_RATE_LIMIT,
+            EXPORT_RATE_LIMIT_WINDOW_SECONDS,
+        )
+        .await
+        {
+            return Err(ApiError::new(
+                "Too many export requests, please try again later",
+                StatusCode::TOO_MANY_REQUESTS,
+            ));
+        }
+
+        Ok(ExportRateLimit)
+    }
+}
+
+/// Limits how often a client can submit corrections, the same way as [`ExportRateLimit`].
+pub struct CorrectionRateLimit;
+
+impl FromRequestParts<AppState> for CorrectionRateLimit {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, ApiError> {
+        if !within_rate_limit(
+            parts,
+            state,
+            "correction",
+            CORRECTION_RATE_LIMIT,
+            CORRECTION_RATE_LIMIT_WINDOW_SECONDS,
+        )
+        .await
+        {
+            return Err(ApiError::new(
+                "Too many corrections submitted, please try again later",
+                StatusCode::TOO_MANY_REQUESTS,
+            ));
+        }
+
+        Ok(CorrectionRateLimit)
+    }
+}
+
+/// Counts the request against `bucket` and checks the client hasn't gone over `limit` in
+/// the window. Fails open when Redis errors.
+async fn within_rate_limit(
+    parts: &Parts,
+    state: &AppState,
+    bucket: &str,
+    limit: u64,
+    window_seconds: i64,
+) -> bool {
+    let client = client_address(parts, &state.trusted_proxies);
+
+    match rate_limit::hit(state.redis.as_ref(), bucket, &client, window_seconds).await {
+        Ok(count) => count <= limit,
+        Err(e) => {
+            error!("Failed to check {bucket} rate limit: {e}");
+            true
+        }
+    }
+}
+
+/// The peer address, unless the peer is one of the trusted proxies. Then it's the right-most
+/// `X-Forwarded-For` hop that isn't a trusted proxy, since anything left of that could have
+/// been sent by the client, falling back to `X-Real-IP`.
+fn client_address(parts: &Parts, trusted_proxies: &[IpAddr]) -> CompactString {
+    let Some(ConnectInfo(peer)) = parts.extensions.get::<ConnectInfo<SocketAddr>>() else {
+        return "unknown".to_compact_string();
+    };
+
+    let peer = peer.ip();
+
+    if !trusted_proxies.contains(&peer) {
+        return peer.to_compact_string();
+    }
+
+    let forwarded_for = parts
+        .headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect::<Vec<_>>();
+
+    if let Some(hop) = forwarded_for.iter().rev().find(|hop| {
+        hop.parse()
+            .map_or(true, |ip| !trusted_proxies.contains(&ip))
+    }) {
+        return hop.to_compact_string();
+    }
+
+    parts
+        .headers
+        .get("x-real-ip")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_compact_string())
+        .unwrap_or_else(|| peer.to_compact_string())
+}
+
+/// Caller authenticated with a moderator (or admin) bearer token.
+pub struct Moderator {
+    pub name: CompactString,
+}
+
+impl FromRequestParts<AppState> for Moderator {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, ApiError> {
+        let token = authenticate(parts, state, Role::Moderator)?;
+
+        Ok(Moderator {
+            name: token.name().to_compact_string(),
+        })
+    }
+}
+
+/// Caller authenticated with an admin bearer token.
+pub struct Admin {
+    pub name: CompactString,
+}
+
+impl FromRequestParts<AppState> for Admin {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, ApiError> {
+        let token = authenticate(parts, state, Role::Admin)?;
+
+        Ok(Admin {
+            name: token.name().to_compact_string(),
+        })
+    }
+}
+
+fn authenticate<'a>(
+    parts: &Parts,
+    state: &'a AppState,
+    role: Role,
+) -> Result<&'a ApiToken, ApiError> {
+    let secret = parts
+        .headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| ApiError::new("Missing bearer token", StatusCode::UNAUTHORIZED))?;
+
+    let token = state
+        .auth
+        .find(secret.trim())
+        .ok_or_else(|| ApiError::new("Invalid bearer token", StatusCode::UNAUTHORIZED))?;
+
+    if !token.has_role(role) {
+        return Err(ApiError::new(
+            "Token is not allowed to perform this action",
+            StatusCode::FORBIDDEN,
+        ));
+    }
+
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Request;
+
+    use super::*;
+
+    fn parts(peer: &str, headers: &[(&str, &str)]) -> Parts {
+        let mut request = Request::builder();
+
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+
+        let (parts, _) = request
+            .extension(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 50000)))
+            .body(())
+            .unwrap()
+            .into_parts();
+
+        parts
+    }
+
+    #[test]
+    fn client_address_ignores_untrusted_headers() {
+        let proxies = ["127.0.0.1".parse().unwrap()];
+
+        let spoofed = parts(
+            "203.0.113.7",
+            &[
+                ("x-forwarded-for", "198.51.100.1"),
+                ("x-real-ip", "198.51.100.2"),
+            ],
+        );
+        assert_eq!(client_address(&spoofed, &proxies), "203.0.113.7");
+        assert_eq!(
+            client_address(&parts("203.0.113.7", &[]), &proxies),
+            "203.0.113.7"
+        );
+    }
+
+    #[test]
+    fn client_address_takes_the_last_untrusted_hop() {
+        let proxies = ["127.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()];
+
+        let proxied = parts(
+            "127.0.0.1",
+            &[("x-forwarded-for", "198.51.100.1, 203.0.113.7, 10.0.0.2")],
+        );
+        assert_eq!(client_address(&proxied, &proxies), "203.0.113.7");
+
+        let real_ip = parts("127.0.0.1", &[("x-real-ip", "203.0.113.7")]);
+        assert_eq!(client_address(&real_ip, &proxies), "203.0.113.7");
+
+        assert_eq!(
+            client_address(&parts("127.0.0.1", &[]), &proxies),
+            "127.0.0.1"
+        );
+    }
+}
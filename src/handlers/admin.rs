@@ -0,0 +1,446 @@
+use anyhow::Context;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use compact_str::CompactString;
+use tracing::error;
+
+use crate::app_state::AppState;
+use crate::conduit::redis::{event_cache, stats_cache};
+use crate::conduit::sqlite::{ingestion_log, pka_event, youtube_drift};
+use crate::corrections;
+use crate::extractors::{Admin, AppJson, AppPath, AppQuery};
+use crate::models::admin::{
+    AdminEventUpdate, CheckNewEpisodesResult, FlushCacheResult, ReExtractRequest,
+};
+use crate::models::errors::{ApiError, ErrorResponseBody};
+use crate::models::import::{CuratedTimelineReport, CuratedTimelineRow};
+use crate::models::ingestion_log::{IngestionLogEntry, IngestionLogQuery};
+use crate::models::pending_edit::{EditKind, EditSubmission, PendingEdit};
+use crate::models::pka_episode::EpisodePath;
+use crate::models::pka_event::PkaEvent;
+use crate::models::show::DEFAULT_SHOW;
+use crate::models::success_response::SuccessResponse;
+use crate::models::timeline_diff::TimelineDiff;
+use crate::models::youtube_drift::YoutubeDrift;
+use crate::redis_db::RedisDb;
+use crate::search::pka_search::EVENTS_REDIS_TAG;
+use crate::updater::import::{import_curated_timelines, parse_curated_csv};
+use crate::updater::pka::load_new_episodes;
+use crate::updater::reextract::reextract_episode;
+use crate::workers::events::refresh_events_index;
+
+const DEFAULT_INGESTION_LOG_LIMIT: u32 = 50;
+const MAX_REPORTED_IMPORT_ERRORS: usize = 10;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/episodes/check-new",
+    responses(
+        (
+            status = 200,
+            description = "Number of episodes added",
+            body = SuccessResponse<CheckNewEpisodesResult>
+        ),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponseBody),
+        (status = 403, description = "Token is not an admin token", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    security(("bearer_token" = [])),
+    tag = "Admin"
+)]
+pub async fn check_new_episodes(
+    _admin: Admin,
+    State(state): State<AppState>,
+) -> Result<SuccessResponse<CheckNewEpisodesResult>, ApiError> {
+    let episodes_added = load_new_episodes(state.db.as_ref(), state.youtube.as_ref()).await?;
+
+    if episodes_added > 0 {
+        invalidate_stats(state.redis.as_ref()).await;
+    }
+
+    Ok(SuccessResponse::new(CheckNewEpisodesResult {
+        episodes_added,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/episodes/{number}/re-extract",
+    params(("number" = String, Path, description = "Episode number, e.g. `481` or `481.5`")),
+    responses(
+        (
+            status = 200,
+            description = "Difference between the stored timeline and the current YouTube description",
+            body = SuccessResponse<TimelineDiff>
+        ),
+        (status = 400, description = "Invalid episode number", body = ErrorResponseBody),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponseBody),
+        (status = 403, description = "Token is not an admin token", body = ErrorResponseBody),
+        (status = 404, description = "Episode not found", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    security(("bearer_token" = [])),
+    tag = "Admin"
+)]
+pub async fn preview_reextract_pka_episode(
+    _admin: Admin,
+    AppPath(EpisodePath { number }): AppPath<EpisodePath>,
+    State(state): State<AppState>,
+) -> Result<SuccessResponse<TimelineDiff>, ApiError> {
+    let res = reextract_episode(
+        state.db.as_ref(),
+        state.youtube.as_ref(),
+        DEFAULT_SHOW,
+        number,
+        false,
+        None,
+    )
+    .await?;
+
+    Ok(SuccessResponse::new(res))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/episodes/{number}/re-extract",
+    params(("number" = String, Path, description = "Episode number, e.g. `481` or `481.5`")),
+    request_body = ReExtractRequest,
+    responses(
+        (
+            status = 200,
+            description = "Applied difference between the stored timeline and the current YouTube description",
+            body = SuccessResponse<TimelineDiff>
+        ),
+        (status = 400, description = "Invalid episode number", body = ErrorResponseBody),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponseBody),
+        (status = 403, description = "Token is not an admin token", body = ErrorResponseBody),
+        (status = 404, description = "Episode not found", body = ErrorResponseBody),
+        (status = 409, description = "Description changed since the preview", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    security(("bearer_token" = [])),
+    tag = "Admin"
+)]
+pub async fn reextract_pka_episode(
+    _admin: Admin,
+    AppPath(EpisodePath { number }): AppPath<EpisodePath>,
+    State(state): State<AppState>,
+    AppJson(payload): AppJson<ReExtractRequest>,
+) -> Result<SuccessResponse<TimelineDiff>, ApiError> {
+    let res = reextract_episode(
+        state.db.as_ref(),
+        state.youtube.as_ref(),
+        DEFAULT_SHOW,
+        number,
+        true,
+        Some(&payload.revision),
+    )
+    .await?;
+
+    if res.applied {
+        refresh_events_index(state.db.as_ref())
+            .await
+            .context("Failed to refresh events index")?;
+        invalidate_stats(state.redis.as_ref()).await;
+    }
+
+    Ok(SuccessResponse::new(res))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/events/{event_id}",
+    params(("event_id" = String, Path, description = "Event id")),
+    request_body = AdminEventUpdate,
+    responses(
+        (
+            status = 200,
+            description = "Applied edit",
+            body = SuccessResponse<PendingEdit>
+        ),
+        (status = 400, description = "Invalid edit", body = ErrorResponseBody),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponseBody),
+        (status = 403, description = "Token is not an admin token", body = ErrorResponseBody),
+        (status = 404, description = "Event not found", body = ErrorResponseBody),
+        (status = 409, description = "Edit conflicts with existing events", body = ErrorResponseBody),
+        (status = 422, description = "Timestamp is past the end of the video or already taken", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    security(("bearer_token" = [])),
+    tag = "Admin"
+)]
+pub async fn edit_pka_event(
+    admin: Admin,
+    AppPath(event_id): AppPath<CompactString>,
+    State(state): State<AppState>,
+    AppJson(payload): AppJson<AdminEventUpdate>,
+) -> Result<SuccessResponse<PendingEdit>, ApiError> {
+    let submission = EditSubmission {
+        kind: EditKind::Edit,
+        episode_number: find_event(&state, &event_id).await?.episode_number(),
+        event_id: Some(event_id),
+        timestamp: payload.timestamp,
+        description: payload.description,
+        reason: None,
+    };
+
+    let res = corrections::apply_directly(
+        state.db.as_ref(),
+        state.redis.as_ref(),
+        submission,
+        &admin.name,
+    )
+    .await?;
+
+    Ok(SuccessResponse::new(res))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/events/{event_id}",
+    params(("event_id" = String, Path, description = "Event id")),
+    responses(
+        (
+            status = 200,
+            description = "Applied deletion",
+            body = SuccessResponse<PendingEdit>
+        ),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponseBody),
+        (status = 403, description = "Token is not an admin token", body = ErrorResponseBody),
+        (status = 404, description = "Event not found", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    security(("bearer_token" = [])),
+    tag = "Admin"
+)]
+pub async fn delete_pka_event(
+    admin: Admin,
+    AppPath(event_id): AppPath<CompactString>,
+    State(state): State<AppState>,
+) -> Result<SuccessResponse<PendingEdit>, ApiError> {
+    let submission = EditSubmission {
+        kind: EditKind::Delete,
+        episode_number: find_event(&state, &event_id).await?.episode_number(),
+        event_id: Some(event_id),
+        timestamp: None,
+        description: None,
+        reason: None,
+    };
+
+    let res = corrections::apply_directly(
+        state.db.as_ref(),
+        state.redis.as_ref(),
+        submission,
+        &admin.name,
+    )
+    .await?;
+
+    Ok(SuccessResponse::new(res))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/cache/flush",
+    responses(
+        (
+            status = 200,
+            description = "Number of cached entries removed",
+            body = SuccessResponse<FlushCacheResult>
+        ),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponseBody),
+        (status = 403, description = "Token is not an admin token", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    security(("bearer_token" = [])),
+    tag = "Admin"
+)]
+pub async fn flush_caches(
+    _admin: Admin,
+    State(state): State<AppState>,
+) -> Result<SuccessResponse<FlushCacheResult>, ApiError> {
+    let keys_deleted = event_cache::flush(state.redis.as_ref(), EVENTS_REDIS_TAG).await?;
+
+    stats_cache::invalidate(state.redis.as_ref()).await?;
+
+    Ok(SuccessResponse::new(FlushCacheResult { keys_deleted }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/drift",
+    responses(
+        (
+            status = 200,
+            description = "Stored videos that no longer match YouTube",
+            body = SuccessResponse<Vec<YoutubeDrift>>
+        ),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponseBody),
+        (status = 403, description = "Token is not an admin token", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    security(("bearer_token" = [])),
+    tag = "Admin"
+)]
+pub async fn drift_report(
+    _admin: Admin,
+    State(state): State<AppState>,
+) -> Result<SuccessResponse<Vec<YoutubeDrift>>, ApiError> {
+    let res = youtube_drift::all(state.db.as_ref()).await?;
+
+    Ok(SuccessResponse::new(res))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/ingestion-log",
+    params(IngestionLogQuery),
+    responses(
+        (
+            status = 200,
+            description = "Recent episode ingestion attempts, newest first",
+            body = SuccessResponse<Vec<IngestionLogEntry>>
+        ),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponseBody),
+        (status = 403, description = "Token is not an admin token", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    security(("bearer_token" = [])),
+    tag = "Admin"
+)]
+pub async fn ingestion_log(
+    _admin: Admin,
+    State(state): State<AppState>,
+    AppQuery(query): AppQuery<IngestionLogQuery>,
+) -> Result<SuccessResponse<Vec<IngestionLogEntry>>, ApiError> {
+    let limit = query.limit.unwrap_or(DEFAULT_INGESTION_LOG_LIMIT);
+
+    let res = ingestion_log::recent(state.db.as_ref(), limit).await?;
+
+    Ok(SuccessResponse::new(res))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/timelines/import/preview",
+    request_body(
+        description = "Rows as a JSON array, or CSV with `episode`, `timestamp` and `description` columns",
+        content(
+            (Vec<CuratedTimelineRow> = "application/json"),
+            (String = "text/csv")
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Changes the import would make, and any rows with errors",
+            body = SuccessResponse<CuratedTimelineReport>
+        ),
+        (status = 400, description = "Malformed CSV or JSON", body = ErrorResponseBody),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponseBody),
+        (status = 403, description = "Token is not an admin token", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    security(("bearer_token" = [])),
+    tag = "Admin"
+)]
+pub async fn preview_timeline_import(
+    _admin: Admin,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<SuccessResponse<CuratedTimelineReport>, ApiError> {
+    let rows = curated_rows(&headers, &body)?;
+
+    let res = import_curated_timelines(state.db.as_ref(), DEFAULT_SHOW, rows, true).await?;
+
+    Ok(SuccessResponse::new(res))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/timelines/import",
+    request_body(
+        description = "Rows as a JSON array, or CSV with `episode`, `timestamp` and `description` columns",
+        content(
+            (Vec<CuratedTimelineRow> = "application/json"),
+            (String = "text/csv")
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Applied changes. Every timeline is replaced in one transaction",
+            body = SuccessResponse<CuratedTimelineReport>
+        ),
+        (status = 400, description = "Malformed CSV or JSON", body = ErrorResponseBody),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponseBody),
+        (status = 403, description = "Token is not an admin token", body = ErrorResponseBody),
+        (status = 422, description = "Some rows have errors, nothing was applied", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    security(("bearer_token" = [])),
+    tag = "Admin"
+)]
+pub async fn import_timelines(
+    _admin: Admin,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<SuccessResponse<CuratedTimelineReport>, ApiError> {
+    let rows = curated_rows(&headers, &body)?;
+
+    let res = import_curated_timelines(state.db.as_ref(), DEFAULT_SHOW, rows, false).await?;
+
+    if !res.errors.is_empty() {
+        let errors = res
+            .errors
+            .iter()
+            .take(MAX_REPORTED_IMPORT_ERRORS)
+            .map(|e| format!("row {}: {}", e.row, e.error))
+            .collect::<Vec<_>>();
+
+        return Err(ApiError::new(
+            format!(
+                "{} rows have errors, nothing was applied: {}",
+                res.errors.len(),
+                errors.join("; ")
+            ),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ));
+    }
+
+    if res.applied {
+        refresh_events_index(state.db.as_ref())
+            .await
+            .context("Failed to refresh events index")?;
+        invalidate_stats(state.redis.as_ref()).await;
+    }
+
+    Ok(SuccessResponse::new(res))
+}
+
+/// Rows are read as CSV when the request says so and as JSON otherwise.
+fn curated_rows(headers: &HeaderMap, body: &str) -> Result<Vec<CuratedTimelineRow>, ApiError> {
+    let is_csv = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/csv"));
+
+    let res = if is_csv {
+        parse_curated_csv(body)
+    } else {
+        serde_json::from_str(body).context("Invalid JSON rows")
+    };
+
+    res.map_err(|e| ApiError::new(format!("{e:#}"), StatusCode::BAD_REQUEST))
+}
+
+async fn find_event(state: &AppState, event_id: &str) -> Result<PkaEvent, ApiError> {
+    Ok(pka_event::find(state.db.as_ref(), event_id).await?)
+}
+
+async fn invalidate_stats(redis: &RedisDb) {
+    if let Err(e) = stats_cache::invalidate(redis).await {
+        error!("Failed to invalidate stats cache: {}", e);
+    }
+}
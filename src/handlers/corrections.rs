@@ -0,0 +1,134 @@
+use axum::extract::State;
+
+use crate::app_state::AppState;
+use crate::conduit::sqlite::pending_edit;
+use crate::corrections;
+use crate::extractors::{AppJson, AppPath, CorrectionRateLimit, Moderator};
+use crate::models::errors::{ApiError, ErrorResponseBody};
+use crate::models::pending_edit::{EditStatus, EditSubmission, PendingEdit, ReviewRequest};
+use crate::models::success_response::SuccessResponse;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/corrections",
+    request_body = EditSubmission,
+    responses(
+        (
+            status = 200,
+            description = "Queued correction",
+            body = SuccessResponse<PendingEdit>
+        ),
+        (status = 400, description = "Invalid correction", body = ErrorResponseBody),
+        (status = 404, description = "Episode or event not found", body = ErrorResponseBody),
+        (status = 429, description = "Too many corrections from this client", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    tag = "Corrections"
+)]
+pub async fn submit_correction(
+    _rate_limit: CorrectionRateLimit,
+    State(state): State<AppState>,
+    AppJson(payload): AppJson<EditSubmission>,
+) -> Result<SuccessResponse<PendingEdit>, ApiError> {
+    let res = corrections::submit(state.db.as_ref(), payload).await?;
+
+    Ok(SuccessResponse::new(res))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/corrections/pending",
+    responses(
+        (
+            status = 200,
+            description = "Corrections awaiting review, oldest first",
+            body = SuccessResponse<Vec<PendingEdit>>
+        ),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    security(("bearer_token" = [])),
+    tag = "Corrections"
+)]
+pub async fn pending_corrections(
+    _moderator: Moderator,
+    State(state): State<AppState>,
+) -> Result<SuccessResponse<Vec<PendingEdit>>, ApiError> {
+    let res = pending_edit::all_with_status(state.db.as_ref(), EditStatus::Pending).await?;
+
+    Ok(SuccessResponse::new(res))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/corrections/{id}/approve",
+    params(("id" = i64, Path, description = "Correction id")),
+    request_body = ReviewRequest,
+    responses(
+        (
+            status = 200,
+            description = "Approved and applied correction",
+            body = SuccessResponse<PendingEdit>
+        ),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponseBody),
+        (status = 404, description = "Correction not found", body = ErrorResponseBody),
+        (status = 409, description = "Correction already reviewed or no longer applies", body = ErrorResponseBody),
+        (status = 422, description = "Timestamp is past the end of the video or already taken", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    security(("bearer_token" = [])),
+    tag = "Corrections"
+)]
+pub async fn approve_correction(
+    moderator: Moderator,
+    AppPath(id): AppPath<i64>,
+    State(state): State<AppState>,
+    AppJson(payload): AppJson<ReviewRequest>,
+) -> Result<SuccessResponse<PendingEdit>, ApiError> {
+    let res = corrections::approve(
+        state.db.as_ref(),
+        state.redis.as_ref(),
+        id,
+        &moderator.name,
+        payload.note.as_deref(),
+    )
+    .await?;
+
+    Ok(SuccessResponse::new(res))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/corrections/{id}/reject",
+    params(("id" = i64, Path, description = "Correction id")),
+    request_body = ReviewRequest,
+    responses(
+        (
+            status = 200,
+            description = "Rejected correction",
+            body = SuccessResponse<PendingEdit>
+        ),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponseBody),
+        (status = 404, description = "Correction not found", body = ErrorResponseBody),
+        (status = 409, description = "Correction already reviewed", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    security(("bearer_token" = [])),
+    tag = "Corrections"
+)]
+pub async fn reject_correction(
+    moderator: Moderator,
+    AppPath(id): AppPath<i64>,
+    State(state): State<AppState>,
+    AppJson(payload): AppJson<ReviewRequest>,
+) -> Result<SuccessResponse<PendingEdit>, ApiError> {
+    let res = corrections::reject(
+        state.db.as_ref(),
+        id,
+        &moderator.name,
+        payload.note.as_deref(),
+    )
+    .await?;
+
+    Ok(SuccessResponse::new(res))
+}
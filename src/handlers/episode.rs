@@ -7,15 +7,21 @@ use compact_str::CompactString;
 use crate::app_state::AppState;
 use crate::conduit::sqlite::pka_episode;
 use crate::conduit::sqlite::pka_episode::find_youtube_link;
-use crate::extractors::AppPath;
+use crate::extractors::{AppJson, AppPath, AppQuery, CurrentShow};
+use crate::models::episode_batch::{EpisodeBatchItem, EpisodeBatchRequest};
+use crate::models::episode_number::EpisodeNumber;
 use crate::models::errors::{ApiError, ErrorResponseBody};
+use crate::models::pka_episode::{EpisodePath, EpisodeTitleQuery, VideoPath};
 use crate::models::pka_episode_with_all::PkaEpisodeWithAll;
 use crate::models::success_response::SuccessResponse;
+use crate::updater::pka::episode_number_from_title;
+
+const MAX_BATCH_SIZE: usize = 100;
 
 #[utoipa::path(
     get,
     path = "/api/v1/episodes/{number}",
-    params(("number" = f32, Path, description = "Episode number")),
+    params(("number" = String, Path, description = "Episode number, e.g. `481` or `481.5`")),
     responses(
         (
             status = 200,
@@ -29,10 +35,11 @@ use crate::models::success_response::SuccessResponse;
     tag = "Episodes"
 )]
 pub async fn watch_pka_episode(
-    AppPath(number): AppPath<f32>,
+    CurrentShow(show): CurrentShow,
+    AppPath(EpisodePath { number }): AppPath<EpisodePath>,
     State(state): State<AppState>,
 ) -> Result<SuccessResponse<PkaEpisodeWithAll>, ApiError> {
-    let res = pka_episode::find_with_all(state.db.as_ref(), number).await?;
+    let res = pka_episode::find_with_all(state.db.as_ref(), &show.slug, number).await?;
 
     Ok(SuccessResponse::new(res))
 }
@@ -40,7 +47,7 @@ pub async fn watch_pka_episode(
 #[utoipa::path(
     get,
     path = "/api/v1/episodes/{number}/youtube-link",
-    params(("number" = f32, Path, description = "Episode number")),
+    params(("number" = String, Path, description = "Episode number, e.g. `481` or `481.5`")),
     responses(
         (
             status = 200,
@@ -54,12 +61,18 @@ pub async fn watch_pka_episode(
     tag = "Episodes"
 )]
 pub async fn find_pka_episode_youtube_link(
-    AppPath(number): AppPath<f32>,
+    CurrentShow(show): CurrentShow,
+    AppPath(EpisodePath { number }): AppPath<EpisodePath>,
     State(state): State<AppState>,
 ) -> Result<SuccessResponse<CompactString>, ApiError> {
-    let res = find_youtube_link(state.db.as_ref(), number)
+    let res = find_youtube_link(state.db.as_ref(), &show.slug, number)
         .await
-        .map_err(|_| ApiError::new("Couldn't find episode number", StatusCode::NOT_FOUND))?;
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => {
+                ApiError::new("Couldn't find episode number", StatusCode::NOT_FOUND)
+            }
+            e => e.into(),
+        })?;
 
     Ok(SuccessResponse::new(res))
 }
@@ -78,13 +91,15 @@ pub async fn find_pka_episode_youtube_link(
     tag = "Episodes"
 )]
 pub async fn latest_pka_episode(
+    CurrentShow(show): CurrentShow,
     State(state): State<AppState>,
 ) -> Result<SuccessResponse<PkaEpisodeWithAll>, ApiError> {
-    let latest_episode_number = pka_episode::latest(state.db.as_ref())
+    let latest_episode_number = pka_episode::latest(state.db.as_ref(), &show.slug)
         .await
         .context("Couldn't get latest episode number.")?;
 
-    let res = pka_episode::find_with_all(state.db.as_ref(), latest_episode_number).await?;
+    let res =
+        pka_episode::find_with_all(state.db.as_ref(), &show.slug, latest_episode_number).await?;
 
     Ok(SuccessResponse::new(res))
 }
@@ -103,13 +118,124 @@ pub async fn latest_pka_episode(
     tag = "Episodes"
 )]
 pub async fn random_pka_episode(
+    CurrentShow(show): CurrentShow,
     State(state): State<AppState>,
 ) -> Result<SuccessResponse<PkaEpisodeWithAll>, ApiError> {
-    let random_episode_number = pka_episode::random(state.db.as_ref())
+    let random_episode_number = pka_episode::random(state.db.as_ref(), &show.slug)
         .await
         .context("Couldn't get random episode number.")?;
 
-    let res = pka_episode::find_with_all(state.db.as_ref(), random_episode_number).await?;
+    let res =
+        pka_episode::find_with_all(state.db.as_ref(), &show.slug, random_episode_number).await?;
+
+    Ok(SuccessResponse::new(res))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/episodes/by-video/{video_id}",
+    params(("video_id" = String, Path, description = "YouTube video id")),
+    responses(
+        (
+            status = 200,
+            description = "Episode details",
+            body = SuccessResponse<PkaEpisodeWithAll>
+        ),
+        (status = 404, description = "Episode not found", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    tag = "Episodes"
+)]
+pub async fn find_pka_episode_by_video_id(
+    CurrentShow(show): CurrentShow,
+    AppPath(VideoPath { video_id }): AppPath<VideoPath>,
+    State(state): State<AppState>,
+) -> Result<SuccessResponse<PkaEpisodeWithAll>, ApiError> {
+    let number =
+        pka_episode::find_number_by_video_id(state.db.as_ref(), &show.slug, &video_id).await?;
+
+    let res = pka_episode::find_with_all(state.db.as_ref(), &show.slug, number).await?;
+
+    Ok(SuccessResponse::new(res))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/episodes/by-title",
+    params(EpisodeTitleQuery),
+    responses(
+        (
+            status = 200,
+            description = "Episode details",
+            body = SuccessResponse<PkaEpisodeWithAll>
+        ),
+        (status = 400, description = "No episode number in title", body = ErrorResponseBody),
+        (status = 404, description = "Episode not found", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    tag = "Episodes"
+)]
+pub async fn find_pka_episode_by_title(
+    CurrentShow(show): CurrentShow,
+    AppQuery(query): AppQuery<EpisodeTitleQuery>,
+    State(state): State<AppState>,
+) -> Result<SuccessResponse<PkaEpisodeWithAll>, ApiError> {
+    let title_regex = show
+        .compile_title_regex()
+        .with_context(|| format!("Invalid title regex for show '{}'", show.slug))?;
+
+    let number = episode_number_from_title(&title_regex, &query.title).ok_or_else(|| {
+        ApiError::new(
+            "Couldn't find an episode number in title",
+            StatusCode::BAD_REQUEST,
+        )
+    })?;
+
+    let res = pka_episode::find_with_all(state.db.as_ref(), &show.slug, EpisodeNumber::new(number))
+        .await?;
+
+    Ok(SuccessResponse::new(res))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/episodes/batch",
+    request_body = EpisodeBatchRequest,
+    responses(
+        (
+            status = 200,
+            description = "Episode details in request order, with not-found markers",
+            body = SuccessResponse<Vec<EpisodeBatchItem>>
+        ),
+        (status = 400, description = "Invalid batch request", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    tag = "Episodes"
+)]
+pub async fn batch_pka_episodes(
+    CurrentShow(show): CurrentShow,
+    State(state): State<AppState>,
+    AppJson(payload): AppJson<EpisodeBatchRequest>,
+) -> Result<SuccessResponse<Vec<EpisodeBatchItem>>, ApiError> {
+    if payload.numbers.len() > MAX_BATCH_SIZE {
+        return Err(ApiError::new(
+            format!("Cannot request more than {MAX_BATCH_SIZE} episodes at once"),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let found =
+        pka_episode::find_many_with_all(state.db.as_ref(), &show.slug, &payload.numbers).await?;
+
+    let res = payload
+        .numbers
+        .into_iter()
+        .map(|number| {
+            let episode = found.iter().find(|e| e.number() == number).cloned();
+
+            EpisodeBatchItem::new(number, episode)
+        })
+        .collect();
 
     Ok(SuccessResponse::new(res))
 }
@@ -2,6 +2,7 @@ use axum::extract::State;
 
 use crate::app_state::AppState;
 use crate::conduit::sqlite::pka_event;
+use crate::extractors::CurrentShow;
 use crate::models::errors::{ApiError, ErrorResponseBody};
 use crate::models::search::PkaEventSearchResult;
 use crate::models::success_response::SuccessResponse;
@@ -20,9 +21,10 @@ use crate::models::success_response::SuccessResponse;
     tag = "Events"
 )]
 pub async fn random_pka_event(
+    CurrentShow(show): CurrentShow,
     State(state): State<AppState>,
 ) -> Result<SuccessResponse<PkaEventSearchResult>, ApiError> {
-    let random_event = pka_event::random_amount(state.db.as_ref())
+    let random_event = pka_event::random_amount(state.db.as_ref(), &show.slug)
         .await?
         .ok_or_else(|| ApiError::new_internal_error("Couldn't find random events."))?;
 
@@ -0,0 +1,125 @@
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use tokio_util::io::ReaderStream;
+
+use crate::app_state::AppState;
+use crate::conduit::sqlite::export as export_rows;
+use crate::export::{self, stream_rows};
+use crate::extractors::{AppPath, ExportRateLimit};
+use crate::handlers::http_date;
+use crate::models::errors::{ApiError, ErrorResponseBody};
+use crate::models::export::{ExportDataset, ExportEpisode, ExportEvent, ExportFile};
+
+const CACHE_CONTROL: HeaderValue = HeaderValue::from_static("public, max-age=3600");
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/export/{file}",
+    params(
+        (
+            "file" = String,
+            Path,
+            description = "`episodes` or `events` with a `.json`, `.csv` or `.ndjson` extension, e.g. `events.csv`"
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Every episode or event of every show, streamed as a download",
+            content(
+                (Vec<ExportEpisode> = "application/json"),
+                (Vec<ExportEvent> = "application/json"),
+                (String = "text/csv"),
+                (String = "application/x-ndjson")
+            )
+        ),
+        (status = 404, description = "Unknown export file", body = ErrorResponseBody),
+        (status = 429, description = "Too many exports from this client", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    tag = "Export"
+)]
+pub async fn export_dataset(
+    _rate_limit: ExportRateLimit,
+    AppPath(file): AppPath<String>,
+    State(state): State<AppState>,
+) -> Result<Response, ApiError> {
+    let ExportFile { dataset, format } = file
+        .parse()
+        .map_err(|_| ApiError::new("Couldn't find export file", StatusCode::NOT_FOUND))?;
+
+    let repo = state.db.as_ref().clone();
+
+    let body = match dataset {
+        ExportDataset::Episodes => {
+            stream_rows::<ExportEpisode>(repo, format, export_rows::episodes)
+        }
+        ExportDataset::Events => stream_rows::<ExportEvent>(repo, format, export_rows::events),
+    };
+
+    Ok((
+        StatusCode::OK,
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(format.content_type()),
+            ),
+            (header::CONTENT_DISPOSITION, attachment(&file)?),
+            (header::CACHE_CONTROL, CACHE_CONTROL),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/export/snapshot.sqlite3",
+    responses(
+        (
+            status = 200,
+            description = "SQLite copy of the public tables, rebuilt at most every 6 hours",
+            content_type = "application/vnd.sqlite3",
+            body = Vec<u8>
+        ),
+        (status = 429, description = "Too many exports from this client", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    tag = "Export"
+)]
+pub async fn export_snapshot(
+    _rate_limit: ExportRateLimit,
+    State(state): State<AppState>,
+) -> Result<Response, ApiError> {
+    let (path, built_at) = export::snapshot(state.db.as_ref()).await?;
+
+    let file = tokio::fs::File::open(&path).await?;
+    let length = file.metadata().await?.len();
+
+    Ok((
+        StatusCode::OK,
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/vnd.sqlite3"),
+            ),
+            (header::CONTENT_LENGTH, HeaderValue::from(length)),
+            (
+                header::LAST_MODIFIED,
+                http_date(DateTime::<Utc>::from(built_at)),
+            ),
+            (header::CONTENT_DISPOSITION, attachment("snapshot.sqlite3")?),
+            (header::CACHE_CONTROL, CACHE_CONTROL),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    )
+        .into_response())
+}
+
+fn attachment(file: &str) -> Result<HeaderValue, ApiError> {
+    HeaderValue::from_str(&format!("attachment; filename=\"pka_index_{file}\""))
+        .map_err(|_| ApiError::new("Couldn't find export file", StatusCode::NOT_FOUND))
+}
@@ -0,0 +1,302 @@
+use axum::extract::State;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::DateTime;
+use sha2::{Digest, Sha256};
+
+use crate::app_state::AppState;
+use crate::conduit::sqlite::{pka_episode, pka_event, show};
+use crate::extractors::AppPath;
+use crate::handlers::{http_date, is_not_modified, matches_etag};
+use crate::models::errors::{ApiError, ErrorResponseBody};
+use crate::models::pka_episode::EpisodePath;
+use crate::models::podcast_feed::{
+    AtomFeed, FeedChannel, FeedItem, PodcastChapter, PodcastChapters, RssFeed,
+};
+use crate::models::show::DEFAULT_SHOW;
+use crate::site::Site;
+use crate::Repo;
+
+const CACHE_CONTROL: HeaderValue = HeaderValue::from_static("public, max-age=900");
+
+#[derive(Debug, Clone, Copy)]
+enum FeedKind {
+    Rss,
+    Atom,
+}
+
+#[utoipa::path(
+    get,
+    path = "/feed/episodes.rss",
+    responses(
+        (
+            status = 200,
+            description = "RSS 2.0 feed of every episode, with chapter links",
+            content_type = "application/rss+xml",
+            body = String
+        ),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag or `If-Modified-Since`"),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    tag = "Feeds"
+)]
+pub async fn episodes_rss(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    episode_feed(
+        state.db.as_ref(),
+        state.site.as_ref(),
+        &headers,
+        FeedKind::Rss,
+    )
+    .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/feed/episodes.atom",
+    responses(
+        (
+            status = 200,
+            description = "Atom feed of every episode, with chapter links",
+            content_type = "application/atom+xml",
+            body = String
+        ),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag or `If-Modified-Since`"),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    tag = "Feeds"
+)]
+pub async fn episodes_atom(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    episode_feed(
+        state.db.as_ref(),
+        state.site.as_ref(),
+        &headers,
+        FeedKind::Atom,
+    )
+    .await
+}
+
+/// The feed changes when an episode is added and when one gets a timeline or YouTube details,
+/// all of which bump the show's change time. That time answers `If-Modified-Since` before the
+/// feed is built, and a hash of the rendered feed answers `If-None-Match`.
+async fn episode_feed(
+    repo: &Repo,
+    site: &Site,
+    headers: &HeaderMap,
+    kind: FeedKind,
+) -> Result<Response, ApiError> {
+    let changed_at = show::updated_at(repo, DEFAULT_SHOW).await?;
+    let updated = DateTime::from_timestamp(changed_at, 0).unwrap_or_default();
+
+    // `If-None-Match` takes precedence when a client sends both.
+    if !headers.contains_key(header::IF_NONE_MATCH) && is_not_modified(headers, updated) {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [
+                (header::LAST_MODIFIED, http_date(updated)),
+                (header::CACHE_CONTROL, CACHE_CONTROL),
+            ],
+        )
+            .into_response());
+    }
+
+    let show = show::find(repo, DEFAULT_SHOW).await?;
+    let episodes = pka_episode::feed(repo, DEFAULT_SHOW).await?;
+
+    let items = episodes
+        .iter()
+        .map(|episode| FeedItem {
+            episode,
+            chapters_url: (episode.event_count > 0)
+                .then(|| site.url(&format!("/feed/episodes/{}/chapters.json", episode.number))),
+        })
+        .collect();
+
+    let (extension, content_type) = match kind {
+        FeedKind::Rss => ("rss", "application/rss+xml; charset=utf-8"),
+        FeedKind::Atom => ("atom", "application/atom+xml; charset=utf-8"),
+    };
+
+    let site_url = site.url("/");
+    let self_url = site.url(&format!("/feed/episodes.{extension}"));
+
+    let channel = FeedChannel {
+        title: &show.name,
+        site_url: &site_url,
+        self_url: &self_url,
+        updated,
+    };
+
+    let xml = match kind {
+        FeedKind::Rss => RssFeed::new(&channel, items).to_xml_string()?,
+        FeedKind::Atom => AtomFeed::new(&channel, items).to_xml_string()?,
+    };
+
+    let digest = format!("{:x}", Sha256::digest(xml.as_bytes()));
+    let etag = HeaderValue::from_str(&format!("\"{}\"", &digest[..32]))
+        .expect("hex digests are valid header values");
+
+    if matches_etag(headers, &etag) {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [
+                (header::ETAG, etag),
+                (header::LAST_MODIFIED, http_date(updated)),
+                (header::CACHE_CONTROL, CACHE_CONTROL),
+            ],
+        )
+            .into_response());
+    }
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static(content_type)),
+            (header::ETAG, etag),
+            (header::LAST_MODIFIED, http_date(updated)),
+            (header::CACHE_CONTROL, CACHE_CONTROL),
+        ],
+        xml,
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/feed/episodes/{number}/chapters.json",
+    params(("number" = String, Path, description = "Episode number, e.g. `481` or `481.5`")),
+    responses(
+        (
+            status = 200,
+            description = "Podcasting 2.0 JSON chapters from the episode's timeline",
+            content_type = "application/json+chapters",
+            body = String
+        ),
+        (status = 400, description = "Invalid episode number", body = ErrorResponseBody),
+        (status = 404, description = "Episode has no timeline", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    tag = "Feeds"
+)]
+pub async fn episode_chapters(
+    AppPath(EpisodePath { number }): AppPath<EpisodePath>,
+    State(state): State<AppState>,
+) -> Result<Response, ApiError> {
+    let events = pka_event::for_episode(state.db.as_ref(), DEFAULT_SHOW, number).await?;
+
+    if events.is_empty() {
+        return Err(ApiError::new(
+            "Couldn't find a timeline for this episode",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    let chapters = events
+        .into_iter()
+        .map(|e| PodcastChapter {
+            start_time: e.timestamp,
+            end_time: e.timestamp + e.length_seconds,
+            title: e.description,
+        })
+        .collect();
+
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json+chapters"),
+            ),
+            (header::CACHE_CONTROL, CACHE_CONTROL),
+        ],
+        Json(PodcastChapters::new(chapters)),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conduit::sqlite::pka_event;
+    use crate::db::test_pool;
+    use crate::models::episode_number::EpisodeNumber;
+    use crate::models::pka_episode::PkaEpisode;
+    use crate::models::pka_event::PkaEvent;
+
+    #[tokio::test]
+    async fn unchanged_feed_is_not_modified() {
+        let pool = test_pool("feed_etag").await;
+        let site = Site::new("https://www.pkaindextest.com", Vec::new());
+        let number = EpisodeNumber::new(481);
+
+        let episode = PkaEpisode::new(
+            DEFAULT_SHOW.into(),
+            number,
+            "PKA 481".into(),
+            "HuxCJNLOtoU".into(),
+            1583539200,
+        );
+        pka_episode::insert(&pool, episode).await.unwrap();
+
+        // Back-date the change so a change made during the test lands on a later second.
+        sqlx::query("UPDATE show SET updated_at = 1583539200 WHERE slug = ?")
+            .bind(DEFAULT_SHOW)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let fetch = |validator: Option<(header::HeaderName, HeaderValue)>| {
+            let mut headers = HeaderMap::new();
+            if let Some((name, value)) = validator {
+                headers.insert(name, value);
+            }
+
+            let (pool, site) = (&pool, &site);
+
+            async move {
+                episode_feed(pool, site, &headers, FeedKind::Rss)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let response = fetch(None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[header::ETAG].clone();
+        let last_modified = response.headers()[header::LAST_MODIFIED].clone();
+        assert_eq!(last_modified, "Sat, 07 Mar 2020 00:00:00 GMT");
+
+        let response = fetch(Some((header::IF_NONE_MATCH, etag.clone()))).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag);
+
+        let response = fetch(Some((header::IF_MODIFIED_SINCE, last_modified.clone()))).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::LAST_MODIFIED], last_modified);
+
+        // A timeline arriving changes the feed without a new upload.
+        let event = PkaEvent::new(
+            PkaEvent::id_for(DEFAULT_SHOW, number, 0),
+            DEFAULT_SHOW.into(),
+            number,
+            0,
+            "Intro".into(),
+            750,
+            1583539200,
+        );
+        pka_event::insert(&pool, event).await.unwrap();
+
+        let response = fetch(Some((header::IF_NONE_MATCH, etag.clone()))).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[header::ETAG], etag);
+
+        let response = fetch(Some((header::IF_MODIFIED_SINCE, last_modified.clone()))).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[header::LAST_MODIFIED], last_modified);
+    }
+}
@@ -1,4 +1,47 @@
+use axum::http::{header, HeaderMap, HeaderValue};
+use chrono::{DateTime, Utc};
+
+pub mod admin;
+pub mod corrections;
 pub mod episode;
 pub mod event;
+pub mod export;
+pub mod feed;
+pub mod on_this_day;
+pub mod reports;
 pub mod search;
+pub mod show;
 pub mod static_files;
+pub mod stats;
+
+/// Formats a time for `Last-Modified` and similar headers.
+fn http_date(time: DateTime<Utc>) -> HeaderValue {
+    let date = time.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+
+    HeaderValue::from_str(&date).expect("HTTP dates are valid header values")
+}
+
+/// Whether the client's `If-Modified-Since` copy is still current.
+fn is_not_modified(headers: &HeaderMap, last_modified: DateTime<Utc>) -> bool {
+    headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+        .is_some_and(|since| last_modified.timestamp() <= since.timestamp())
+}
+
+/// Whether the client's `If-None-Match` copy is still current. Validators are compared weakly,
+/// so a `W/` prefix added by a proxy doesn't force a refetch.
+fn matches_etag(headers: &HeaderMap, etag: &HeaderValue) -> bool {
+    let Ok(etag) = etag.to_str() else {
+        return false;
+    };
+
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use axum::extract::State;
+use chrono::{DateTime, Datelike, Utc};
+
+use crate::app_state::AppState;
+use crate::conduit::sqlite::{pka_episode, pka_event};
+use crate::extractors::{AppQuery, CurrentShow};
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::errors::{ApiError, ErrorResponseBody};
+use crate::models::on_this_day::{MonthDay, OnThisDayEpisode, OnThisDayQuery};
+use crate::models::pka_event::PkaEvent;
+use crate::models::success_response::SuccessResponse;
+
+const TOP_EVENTS_PER_EPISODE: i64 = 5;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/on-this-day",
+    params(OnThisDayQuery),
+    responses(
+        (
+            status = 200,
+            description = "Episodes uploaded on this calendar day in previous years",
+            body = SuccessResponse<Vec<OnThisDayEpisode>>
+        ),
+        (status = 400, description = "Invalid date", body = ErrorResponseBody),
+        (status = 404, description = "Show not found", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    tag = "Episodes"
+)]
+pub async fn on_this_day(
+    CurrentShow(show): CurrentShow,
+    AppQuery(query): AppQuery<OnThisDayQuery>,
+    State(state): State<AppState>,
+) -> Result<SuccessResponse<Vec<OnThisDayEpisode>>, ApiError> {
+    let month_day = query.date.unwrap_or_else(MonthDay::today);
+    let current_year = Utc::now().year();
+
+    let episodes = pka_episode::on_this_day(
+        state.db.as_ref(),
+        &show.slug,
+        &month_day.to_string(),
+        current_year,
+    )
+    .await?;
+
+    let mut top_events: HashMap<EpisodeNumber, Vec<PkaEvent>> = HashMap::new();
+
+    for event in pka_event::top_on_this_day(
+        state.db.as_ref(),
+        &show.slug,
+        &month_day.to_string(),
+        current_year,
+        TOP_EVENTS_PER_EPISODE,
+    )
+    .await?
+    {
+        top_events
+            .entry(event.episode_number())
+            .or_default()
+            .push(event);
+    }
+
+    let res = episodes
+        .into_iter()
+        .map(|episode| {
+            let upload_year = DateTime::from_timestamp(episode.upload_date, 0)
+                .map(|d| d.year())
+                .unwrap_or(current_year);
+            let events = top_events.remove(&episode.number()).unwrap_or_default();
+
+            OnThisDayEpisode::new(episode, current_year - upload_year, events)
+        })
+        .collect();
+
+    Ok(SuccessResponse::new(res))
+}
@@ -0,0 +1,32 @@
+use axum::extract::State;
+
+use crate::app_state::AppState;
+use crate::extractors::AppQuery;
+use crate::models::errors::{ApiError, ErrorResponseBody};
+use crate::models::success_response::SuccessResponse;
+use crate::models::timeline_coverage::{TimelineCoverageQuery, TimelineCoverageReport};
+use crate::reports::timeline_coverage;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/timeline-coverage",
+    params(TimelineCoverageQuery),
+    responses(
+        (
+            status = 200,
+            description = "Episodes and events whose timelines need curating",
+            body = SuccessResponse<TimelineCoverageReport>
+        ),
+        (status = 400, description = "Invalid report options", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    tag = "Reports"
+)]
+pub async fn timeline_coverage(
+    AppQuery(query): AppQuery<TimelineCoverageQuery>,
+    State(state): State<AppState>,
+) -> Result<SuccessResponse<TimelineCoverageReport>, ApiError> {
+    let res = timeline_coverage::generate(state.db.as_ref(), query.into()).await?;
+
+    Ok(SuccessResponse::new(res))
+}
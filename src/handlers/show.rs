@@ -0,0 +1,51 @@
+use axum::extract::State;
+
+use crate::app_state::AppState;
+use crate::conduit::sqlite::show;
+use crate::extractors::CurrentShow;
+use crate::models::errors::{ApiError, ErrorResponseBody};
+use crate::models::show::Show;
+use crate::models::success_response::SuccessResponse;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/shows",
+    responses(
+        (
+            status = 200,
+            description = "Shows in the index",
+            body = SuccessResponse<Vec<Show>>
+        ),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    tag = "Shows"
+)]
+pub async fn list_shows(
+    State(state): State<AppState>,
+) -> Result<SuccessResponse<Vec<Show>>, ApiError> {
+    let res = show::all_enabled(state.db.as_ref()).await?;
+
+    Ok(SuccessResponse::new(res))
+}
+
+/// The episode, event, on-this-day and stats routes are also served under
+/// `/api/v1/shows/{slug}`, e.g. `/api/v1/shows/{slug}/episodes/latest`. Without the prefix
+/// they serve PKA.
+#[utoipa::path(
+    get,
+    path = "/api/v1/shows/{slug}",
+    params(("slug" = String, Path, description = "Show slug, e.g. `pka`")),
+    responses(
+        (
+            status = 200,
+            description = "Show details",
+            body = SuccessResponse<Show>
+        ),
+        (status = 404, description = "Show not found", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    tag = "Shows"
+)]
+pub async fn find_show(CurrentShow(show): CurrentShow) -> SuccessResponse<Show> {
+    SuccessResponse::new(show)
+}
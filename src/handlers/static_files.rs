@@ -1,13 +1,13 @@
-use float_ord::FloatOrd;
-
 use anyhow::Context;
 use axum::extract::State;
 use axum::http::{header, HeaderValue, StatusCode};
 use axum::response::IntoResponse;
+use chrono::DateTime;
 
 use crate::app_state::AppState;
 use crate::conduit::sqlite::pka_episode;
 use crate::models::errors::{ApiError, ErrorResponseBody};
+use crate::models::show::DEFAULT_SHOW;
 use crate::models::sitemap_xml::{SiteMap, Url};
 
 #[utoipa::path(
@@ -49,65 +49,32 @@ pub async fn sitemap_xml(State(state): State<AppState>) -> Result<impl IntoRespo
         .await
         .context("Failed to load episodes for sitemap")?;
 
-    res.sort_by_key(|a| FloatOrd(a.number()));
+    // Only PKA has episode pages on the site.
+    res.retain(|e| e.show_slug == DEFAULT_SHOW);
+    res.sort_by_key(|a| a.number());
 
-    type Type<'a> = &'a [(&'a str, Option<&'a str>, Option<&'a str>, Option<&'a str>)];
+    let site = state.site.as_ref();
 
-    const STATIC_URLS: Type = &[
-        (
-            "https://www.pkaindex.com/",
-            None,
-            Some("weekly"),
-            Some("1.0"),
-        ),
-        (
-            "https://www.pkaindex.com/watch",
-            None,
-            Some("weekly"),
-            Some("1.0"),
-        ),
-        (
-            "https://www.pkaindex.com/watch/latest",
-            None,
-            Some("weekly"),
-            Some("1.0"),
-        ),
-        (
-            "https://www.pkaindex.com/episodes",
-            None,
-            Some("weekly"),
-            Some("0.9"),
-        ),
-        (
-            "https://www.pkaindex.com/events",
-            None,
-            Some("weekly"),
-            Some("0.9"),
-        ),
-        (
-            "https://www.pkaindex.com/watch/random",
-            None,
-            Some("weekly"),
-            Some("0.8"),
-        ),
-    ];
-
-    let mut urls = STATIC_URLS
+    let mut urls = site
+        .pages()
         .iter()
-        .map(|(loc, last_mod, change_freq, priority)| {
+        .map(|page| {
             Url::new(
-                *loc,
-                last_mod.map(str::to_owned),
-                change_freq.map(str::to_owned),
-                priority.map(str::to_owned),
+                site.url(&page.path),
+                None,
+                page.change_freq.as_ref().map(|f| f.to_string()),
+                page.priority.as_ref().map(|p| p.to_string()),
             )
         })
         .collect::<Vec<_>>();
 
     urls.extend(res.into_iter().map(|p| {
+        let last_mod = DateTime::from_timestamp(p.upload_date, 0)
+            .map(|date| date.format("%Y-%m-%d").to_string());
+
         Url::new(
-            format!("https://www.pkaindex.com/watch/{}", p.number()),
-            None,
+            site.url(&format!("/watch/{}", p.number())),
+            last_mod,
             Some("weekly".to_owned()),
             Some("0.7".to_owned()),
         )
@@ -0,0 +1,47 @@
+use axum::extract::State;
+use tracing::error;
+
+use crate::app_state::AppState;
+use crate::conduit::redis::stats_cache;
+use crate::conduit::sqlite::stats;
+use crate::extractors::CurrentShow;
+use crate::models::errors::{ApiError, ErrorResponseBody};
+use crate::models::stats::PkaStats;
+use crate::models::success_response::SuccessResponse;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/stats",
+    responses(
+        (
+            status = 200,
+            description = "Archive statistics",
+            body = SuccessResponse<PkaStats>
+        ),
+        (status = 404, description = "Show not found", body = ErrorResponseBody),
+        (status = 500, description = "Internal server error", body = ErrorResponseBody)
+    ),
+    tag = "Stats"
+)]
+pub async fn archive_stats(
+    CurrentShow(show): CurrentShow,
+    State(state): State<AppState>,
+) -> Result<SuccessResponse<PkaStats>, ApiError> {
+    let cached = stats_cache::get(state.redis.as_ref(), &show.slug)
+        .await
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<PkaStats>(&bytes).ok());
+
+    if let Some(res) = cached {
+        return Ok(SuccessResponse::new(res));
+    }
+
+    let res = stats::all(state.db.as_ref(), &show.slug).await?;
+    let bytes = serde_json::to_vec(&res)?;
+
+    if let Err(e) = stats_cache::set(state.redis.as_ref(), &show.slug, &bytes).await {
+        error!("Failed to cache archive stats: {}", e);
+    }
+
+    Ok(SuccessResponse::new(res))
+}
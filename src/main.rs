@@ -1,6 +1,8 @@
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 use anyhow::Context;
+use clap::Parser;
 use dotenv::dotenv;
 use mimalloc::MiMalloc;
 use sqlx::SqlitePool;
@@ -10,31 +12,35 @@ use tokio::sync::RwLock;
 use tracing::info;
 use tracing_subscriber::prelude::*;
 
+use crate::cli::{Cli, Command};
 use crate::config::Config;
 use crate::models::pka_event::PkaEvent;
 use crate::routes::build_router;
-use crate::yt_api_key::YtApiKey;
 
 mod app_state;
+mod auth;
+mod cli;
 mod conduit;
 mod config;
+mod corrections;
 mod db;
 mod docs;
+mod export;
 mod extractors;
 mod handlers;
 mod models;
 mod redis_db;
+mod reports;
 mod routes;
 mod search;
+mod site;
 mod startup;
 mod updater;
 mod workers;
-mod yt_api_key;
 
 type Repo = SqlitePool;
 type EventIndexType = Arc<RwLock<Box<[PkaEvent]>>>;
 
-static YT_API_KEY: LazyLock<YtApiKey> = LazyLock::new(YtApiKey::default);
 static PKA_EVENTS_INDEX: LazyLock<EventIndexType> =
     LazyLock::new(|| Arc::new(RwLock::new(Box::default())));
 
@@ -55,9 +61,25 @@ async fn run() -> anyhow::Result<()> {
 
     init_tracing();
 
+    let cli = Cli::parse();
+
     let config = Config::from_env().context("Failed to load configuration")?;
 
-    let startup::InitializedApp { app_state, cors } = startup::initialize(&config)
+    match cli.command.unwrap_or_default() {
+        Command::Serve => serve(&config).await,
+        Command::Report(report) => cli::report(&config, report).await,
+        Command::ReExtract(args) => cli::reextract(&config, args).await,
+        Command::Import(command) => cli::import(&config, command).await,
+        Command::Export(command) => cli::export(&config, command).await,
+        Command::Migrate => cli::migrate(&config).await,
+        Command::CheckNew => cli::check_new(&config).await,
+        Command::FlushCache => cli::flush_cache(&config).await,
+        Command::Stats(args) => cli::stats(&config, args).await,
+    }
+}
+
+async fn serve(config: &Config) -> anyhow::Result<()> {
+    let startup::InitializedApp { app_state, cors } = startup::initialize(config)
         .await
         .context("Failed to initialize application state")?;
 
@@ -74,7 +96,12 @@ async fn run() -> anyhow::Result<()> {
 
     info!(address = %config.bind_address, "HTTP server listening");
 
-    axum::serve(listener, app).await.context("Server error")?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .context("Server error")?;
 
     Ok(())
 }
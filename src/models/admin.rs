@@ -0,0 +1,33 @@
+use compact_str::CompactString;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminEventUpdate {
+    /// New timestamp, e.g. `1:02:03`.
+    #[schema(value_type = Option<String>)]
+    pub timestamp: Option<CompactString>,
+    #[schema(value_type = Option<String>)]
+    pub description: Option<CompactString>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReExtractRequest {
+    /// `revision` of the previewed re-extraction.
+    #[schema(value_type = String)]
+    pub revision: CompactString,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckNewEpisodesResult {
+    pub episodes_added: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FlushCacheResult {
+    pub keys_deleted: usize,
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::pka_episode_with_all::PkaEpisodeWithAll;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeBatchRequest {
+    /// Episode numbers, e.g. `481` or `481.5`.
+    #[schema(value_type = Vec<f64>)]
+    pub numbers: Vec<EpisodeNumber>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeBatchItem {
+    #[schema(value_type = f64)]
+    number: EpisodeNumber,
+    found: bool,
+    episode: Option<PkaEpisodeWithAll>,
+}
+
+impl EpisodeBatchItem {
+    pub fn new(number: EpisodeNumber, episode: Option<PkaEpisodeWithAll>) -> Self {
+        Self {
+            number,
+            found: episode.is_some(),
+            episode,
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::error::BoxDynError;
+use sqlx::sqlite::{SqliteTypeInfo, SqliteValueRef};
+use sqlx::{Decode, Sqlite, Type};
+
+/// Episode number with an optional single-digit part, written `481` or `481.5`.
+///
+/// Stored as separate `number` and `part` integer columns, where a part of 0 is the whole
+/// episode. Queries select it from the generated `label` (`episode_label`) text column.
+/// Serialized as a JSON number so existing clients keep working.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EpisodeNumber {
+    number: u32,
+    part: u8,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("'{0}' is not an episode number, expected e.g. 481 or 481.5")]
+pub struct ParseEpisodeNumberError(String);
+
+impl EpisodeNumber {
+    pub const fn new(number: u32) -> Self {
+        Self { number, part: 0 }
+    }
+
+    pub fn with_part(number: u32, part: u8) -> Result<Self, ParseEpisodeNumberError> {
+        if part > 9 {
+            return Err(ParseEpisodeNumberError(format!("{number}.{part}")));
+        }
+
+        Ok(Self { number, part })
+    }
+
+    pub fn number(self) -> u32 {
+        self.number
+    }
+
+    /// Stored part, 0 for a whole episode.
+    pub fn part(self) -> u8 {
+        self.part
+    }
+
+    /// For payloads that still carry episode numbers as floats.
+    pub fn as_f32(self) -> f32 {
+        self.as_f64() as f32
+    }
+
+    fn as_f64(self) -> f64 {
+        self.number as f64 + self.part as f64 / 10.0
+    }
+}
+
+impl From<u32> for EpisodeNumber {
+    fn from(number: u32) -> Self {
+        Self::new(number)
+    }
+}
+
+impl fmt::Display for EpisodeNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self.part {
+            0 => self.number.to_string(),
+            part => format!("{}.{part}", self.number),
+        };
+
+        f.pad(&s)
+    }
+}
+
+impl FromStr for EpisodeNumber {
+    type Err = ParseEpisodeNumberError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseEpisodeNumberError(s.to_owned());
+
+        let (number, part) = s.trim().split_once('.').unwrap_or((s.trim(), ""));
+
+        if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(err());
+        }
+
+        let number = number.parse::<u32>().map_err(|_| err())?;
+
+        // `481.0` and `481.50` are accepted, as f32 paths used to.
+        let part = match part.trim_end_matches('0').as_bytes() {
+            [] => 0,
+            [digit @ b'1'..=b'9'] => digit - b'0',
+            _ => return Err(err()),
+        };
+
+        Self::with_part(number, part).map_err(|_| err())
+    }
+}
+
+impl Serialize for EpisodeNumber {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.part {
+            0 => serializer.serialize_u32(self.number),
+            _ => serializer.serialize_f64(self.as_f64()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for EpisodeNumber {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EpisodeNumberVisitor;
+
+        impl Visitor<'_> for EpisodeNumberVisitor {
+            type Value = EpisodeNumber;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an episode number such as 481 or 481.5")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                u32::try_from(v)
+                    .map(EpisodeNumber::new)
+                    .map_err(|_| E::custom(ParseEpisodeNumberError(v.to_string())))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                u64::try_from(v)
+                    .map_err(|_| E::custom(ParseEpisodeNumberError(v.to_string())))
+                    .and_then(|v| self.visit_u64(v))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                let tenths = (v * 10.0).round();
+
+                if !v.is_finite() || v < 0.0 || (v * 10.0 - tenths).abs() > 1e-6 {
+                    return Err(E::custom(ParseEpisodeNumberError(v.to_string())));
+                }
+
+                let number = (tenths / 10.0).floor();
+
+                EpisodeNumber::with_part(number as u32, (tenths - number * 10.0) as u8)
+                    .map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(EpisodeNumberVisitor)
+    }
+}
+
+impl Type<Sqlite> for EpisodeNumber {
+    fn type_info() -> SqliteTypeInfo {
+        <&str as Type<Sqlite>>::type_info()
+    }
+
+    fn compatible(ty: &SqliteTypeInfo) -> bool {
+        <&str as Type<Sqlite>>::compatible(ty)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for EpisodeNumber {
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(<&str as Decode<Sqlite>>::decode(value)?.parse()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        let half = EpisodeNumber::with_part(481, 5).unwrap();
+
+        assert_eq!(
+            "481".parse::<EpisodeNumber>().unwrap(),
+            EpisodeNumber::new(481)
+        );
+        assert_eq!(
+            "481.0".parse::<EpisodeNumber>().unwrap(),
+            EpisodeNumber::new(481)
+        );
+        assert_eq!("481.5".parse::<EpisodeNumber>().unwrap(), half);
+        assert_eq!("481.50".parse::<EpisodeNumber>().unwrap(), half);
+
+        assert!("".parse::<EpisodeNumber>().is_err());
+        assert!("481.".parse::<EpisodeNumber>().is_ok());
+        assert!("481.25".parse::<EpisodeNumber>().is_err());
+        assert!("-1".parse::<EpisodeNumber>().is_err());
+        assert!("abc".parse::<EpisodeNumber>().is_err());
+
+        assert_eq!(half.to_string(), "481.5");
+        assert_eq!(format!("{:0>3}", EpisodeNumber::new(1)), "001");
+        assert!(EpisodeNumber::new(481) < half);
+        assert!(half < EpisodeNumber::new(482));
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let half = EpisodeNumber::with_part(481, 5).unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&EpisodeNumber::new(481)).unwrap(),
+            "481"
+        );
+        assert_eq!(serde_json::to_string(&half).unwrap(), "481.5");
+
+        let parse = |json| serde_json::from_str::<EpisodeNumber>(json);
+
+        assert_eq!(parse("481").unwrap(), EpisodeNumber::new(481));
+        assert_eq!(parse("481.0").unwrap(), EpisodeNumber::new(481));
+        assert_eq!(parse("481.5").unwrap(), half);
+        assert_eq!(parse("\"481.5\"").unwrap(), half);
+
+        assert!(parse("481.25").is_err());
+        assert!(parse("-481").is_err());
+    }
+}
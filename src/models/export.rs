@@ -0,0 +1,152 @@
+use std::str::FromStr;
+
+use compact_str::{CompactString, ToCompactString};
+use serde::Serialize;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+use crate::models::episode_number::EpisodeNumber;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportDataset {
+    Episodes,
+    Events,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+}
+
+/// File name such as `events.csv`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportFile {
+    pub dataset: ExportDataset,
+    pub format: ExportFormat,
+}
+
+impl FromStr for ExportFile {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (dataset, format) = s.split_once('.').ok_or(())?;
+
+        let dataset = match dataset {
+            "episodes" => ExportDataset::Episodes,
+            "events" => ExportDataset::Events,
+            _ => return Err(()),
+        };
+
+        let format = match format {
+            "json" => ExportFormat::Json,
+            "csv" => ExportFormat::Csv,
+            "ndjson" => ExportFormat::Ndjson,
+            _ => return Err(()),
+        };
+
+        Ok(Self { dataset, format })
+    }
+}
+
+/// Row of a dataset export, written as a JSON object or a CSV record.
+pub trait ExportRow: Serialize {
+    const CSV_HEADER: &'static [&'static str];
+
+    fn csv_record(&self) -> Vec<CompactString>;
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportEpisode {
+    #[schema(value_type = String)]
+    pub show_slug: CompactString,
+    #[schema(value_type = f64)]
+    pub number: EpisodeNumber,
+    #[schema(value_type = String)]
+    pub name: CompactString,
+    #[schema(value_type = String)]
+    pub youtube_link: CompactString,
+    pub upload_date: i64,
+    /// YouTube title, when the video details have been fetched.
+    #[schema(value_type = Option<String>)]
+    pub title: Option<CompactString>,
+    pub length_seconds: Option<i32>,
+}
+
+impl ExportRow for ExportEpisode {
+    const CSV_HEADER: &'static [&'static str] = &[
+        "show_slug",
+        "number",
+        "name",
+        "youtube_link",
+        "upload_date",
+        "title",
+        "length_seconds",
+    ];
+
+    fn csv_record(&self) -> Vec<CompactString> {
+        vec![
+            self.show_slug.clone(),
+            self.number.to_compact_string(),
+            self.name.clone(),
+            self.youtube_link.clone(),
+            self.upload_date.to_compact_string(),
+            self.title.clone().unwrap_or_default(),
+            self.length_seconds
+                .map(|l| l.to_compact_string())
+                .unwrap_or_default(),
+        ]
+    }
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportEvent {
+    #[schema(value_type = String)]
+    pub event_id: CompactString,
+    #[schema(value_type = String)]
+    pub show_slug: CompactString,
+    #[schema(value_type = f64)]
+    pub episode_number: EpisodeNumber,
+    pub timestamp: i32,
+    #[schema(value_type = String)]
+    pub description: CompactString,
+    pub length_seconds: i32,
+    pub upload_date: i64,
+}
+
+impl ExportRow for ExportEvent {
+    const CSV_HEADER: &'static [&'static str] = &[
+        "event_id",
+        "show_slug",
+        "episode_number",
+        "timestamp",
+        "description",
+        "length_seconds",
+        "upload_date",
+    ];
+
+    fn csv_record(&self) -> Vec<CompactString> {
+        vec![
+            self.event_id.clone(),
+            self.show_slug.clone(),
+            self.episode_number.to_compact_string(),
+            self.timestamp.to_compact_string(),
+            self.description.clone(),
+            self.length_seconds.to_compact_string(),
+            self.upload_date.to_compact_string(),
+        ]
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+
+use compact_str::CompactString;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::timeline_diff::TimelineDiff;
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeImportReport {
+    pub added: Vec<EpisodeNumber>,
+    pub updated: Vec<EpisodeNumber>,
+    pub unchanged: usize,
+    pub applied: bool,
+}
+
+impl EpisodeImportReport {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty()
+    }
+}
+
+impl fmt::Display for EpisodeImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Episodes: {} added, {} updated, {} unchanged",
+            self.added.len(),
+            self.updated.len(),
+            self.unchanged
+        )?;
+
+        for number in &self.added {
+            writeln!(f, "+ {number}")?;
+        }
+
+        for number in &self.updated {
+            writeln!(f, "~ {number}")?;
+        }
+
+        if self.applied {
+            writeln!(f, "Changes applied.")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineImportReport {
+    pub diffs: Vec<TimelineDiff>,
+    pub failed: Vec<TimelineImportFailure>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineImportFailure {
+    pub file: CompactString,
+    pub error: CompactString,
+}
+
+impl fmt::Display for TimelineImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (changed, unchanged): (Vec<_>, Vec<_>) = self
+            .diffs
+            .iter()
+            .partition(|d| !d.is_empty() || !d.warnings.is_empty());
+
+        for diff in &changed {
+            write!(f, "{diff}")?;
+        }
+
+        for failure in &self.failed {
+            writeln!(f, "x {}: {}", failure.file, failure.error)?;
+        }
+
+        writeln!(
+            f,
+            "Timelines: {} changed, {} unchanged, {} failed",
+            changed.iter().filter(|d| !d.is_empty()).count(),
+            unchanged.len() + changed.iter().filter(|d| d.is_empty()).count(),
+            self.failed.len()
+        )
+    }
+}
+
+/// One event of a curated timeline, e.g. a spreadsheet row.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CuratedTimelineRow {
+    #[schema(value_type = f64)]
+    pub episode: EpisodeNumber,
+    /// e.g. `1:02:03`.
+    #[schema(value_type = String)]
+    pub timestamp: CompactString,
+    #[schema(value_type = String)]
+    pub description: CompactString,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CuratedTimelineReport {
+    pub diffs: Vec<TimelineDiff>,
+    /// Nothing is applied while there are errors.
+    pub errors: Vec<CuratedRowError>,
+    pub applied: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CuratedRowError {
+    /// Position of the row in the upload, starting at 1 and not counting a CSV header.
+    pub row: usize,
+    #[schema(value_type = String)]
+    pub error: CompactString,
+}
@@ -0,0 +1,55 @@
+use compact_str::CompactString;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::timeline_warning::{TimelineWarning, TimelineWarnings};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct IngestionLogQuery {
+    /// Maximum number of attempts to return, newest first. Defaults to 50.
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum IngestionOutcome {
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestionLogEntry {
+    pub id: i64,
+    #[serde(rename = "show")]
+    #[schema(value_type = String)]
+    pub show_slug: CompactString,
+    #[schema(value_type = f64)]
+    pub episode_number: EpisodeNumber,
+    #[schema(value_type = String)]
+    pub video_id: CompactString,
+    pub attempted_at: i64,
+    pub outcome: IngestionOutcome,
+    pub events_inserted: i64,
+    #[schema(value_type = Option<String>)]
+    pub error: Option<CompactString>,
+    /// Problems found in the timeline, for attempts that got as far as extracting it.
+    #[schema(value_type = Vec<TimelineWarning>)]
+    pub warnings: TimelineWarnings,
+}
+
+#[derive(Debug)]
+pub struct NewIngestionLogEntry {
+    pub show_slug: CompactString,
+    pub episode_number: EpisodeNumber,
+    pub video_id: CompactString,
+    pub attempted_at: i64,
+    pub outcome: IngestionOutcome,
+    pub events_inserted: i64,
+    pub error: Option<CompactString>,
+    pub warnings: TimelineWarnings,
+}
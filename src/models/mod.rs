@@ -1,10 +1,26 @@
+pub mod admin;
+pub mod episode_batch;
+pub mod episode_number;
 pub mod errors;
+pub mod export;
+pub mod import;
+pub mod ingestion_log;
+pub mod on_this_day;
+pub mod pending_edit;
 pub mod pka_episode;
 pub mod pka_episode_with_all;
 pub mod pka_event;
 pub mod pka_guest;
 pub mod pka_youtube_details;
+pub mod podcast_feed;
+pub mod rss_feed;
 pub mod search;
+pub mod show;
 pub mod sitemap_xml;
+pub mod stats;
 pub mod success_response;
+pub mod timeline_coverage;
+pub mod timeline_diff;
+pub mod timeline_warning;
 pub mod updater;
+pub mod youtube_drift;
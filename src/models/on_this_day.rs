@@ -0,0 +1,113 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate, Utc};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::models::pka_episode::PkaEpisode;
+use crate::models::pka_event::PkaEvent;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OnThisDayQuery {
+    /// Calendar day formatted as `MM-DD`. Defaults to today (UTC).
+    #[param(value_type = Option<String>, example = "03-07")]
+    pub date: Option<MonthDay>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonthDay {
+    month: u32,
+    day: u32,
+}
+
+impl MonthDay {
+    pub fn today() -> Self {
+        let now = Utc::now();
+
+        Self {
+            month: now.month(),
+            day: now.day(),
+        }
+    }
+}
+
+impl fmt::Display for MonthDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}-{:02}", self.month, self.day)
+    }
+}
+
+impl FromStr for MonthDay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid date '{s}', expected MM-DD");
+
+        let (month, day) = s.trim().split_once('-').ok_or_else(invalid)?;
+
+        if month.len() != 2 || day.len() != 2 {
+            return Err(invalid());
+        }
+
+        let month = month.parse::<u32>().map_err(|_| invalid())?;
+        let day = day.parse::<u32>().map_err(|_| invalid())?;
+
+        // 2000 is a leap year so 02-29 is accepted.
+        NaiveDate::from_ymd_opt(2000, month, day).ok_or_else(invalid)?;
+
+        Ok(Self { month, day })
+    }
+}
+
+impl<'de> Deserialize<'de> for MonthDay {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OnThisDayEpisode {
+    episode: PkaEpisode,
+    years_ago: i32,
+    top_events: Vec<PkaEvent>,
+}
+
+impl OnThisDayEpisode {
+    pub fn new(episode: PkaEpisode, years_ago: i32, top_events: Vec<PkaEvent>) -> Self {
+        Self {
+            episode,
+            years_ago,
+            top_events,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_month_day() {
+        assert_eq!(
+            "03-07".parse::<MonthDay>().unwrap(),
+            MonthDay { month: 3, day: 7 }
+        );
+        assert_eq!("02-29".parse::<MonthDay>().unwrap().to_string(), "02-29");
+        assert_eq!("12-31".parse::<MonthDay>().unwrap().to_string(), "12-31");
+
+        assert!("3-7".parse::<MonthDay>().is_err());
+        assert!("13-01".parse::<MonthDay>().is_err());
+        assert!("02-30".parse::<MonthDay>().is_err());
+        assert!("00-10".parse::<MonthDay>().is_err());
+        assert!("0307".parse::<MonthDay>().is_err());
+        assert!("ab-cd".parse::<MonthDay>().is_err());
+    }
+}
@@ -0,0 +1,86 @@
+use compact_str::CompactString;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+use crate::models::episode_number::EpisodeNumber;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum EditKind {
+    Add,
+    Edit,
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum EditStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[derive(Clone, Debug, Serialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingEdit {
+    pub id: i64,
+    pub kind: EditKind,
+    #[schema(value_type = f64)]
+    pub episode_number: EpisodeNumber,
+    #[schema(value_type = Option<String>)]
+    pub event_id: Option<CompactString>,
+    pub timestamp: Option<i32>,
+    #[schema(value_type = Option<String>)]
+    pub description: Option<CompactString>,
+    #[schema(value_type = Option<String>)]
+    pub reason: Option<CompactString>,
+    pub status: EditStatus,
+    pub submitted_at: i64,
+    pub reviewed_at: Option<i64>,
+    #[schema(value_type = Option<String>)]
+    pub reviewer: Option<CompactString>,
+    #[schema(value_type = Option<String>)]
+    pub review_note: Option<CompactString>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EditSubmission {
+    pub kind: EditKind,
+    /// Episode number, e.g. `481` or `481.5`.
+    #[schema(value_type = f64)]
+    pub episode_number: EpisodeNumber,
+    /// Event being edited or deleted.
+    #[schema(value_type = Option<String>)]
+    pub event_id: Option<CompactString>,
+    /// New timestamp for added or edited events, e.g. `1:02:03`.
+    #[schema(value_type = Option<String>)]
+    pub timestamp: Option<CompactString>,
+    /// New description for added or edited events.
+    #[schema(value_type = Option<String>)]
+    pub description: Option<CompactString>,
+    #[schema(value_type = Option<String>)]
+    pub reason: Option<CompactString>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewRequest {
+    #[schema(value_type = Option<String>)]
+    pub note: Option<CompactString>,
+}
+
+/// Validated submission ready to be queued.
+#[derive(Debug, PartialEq)]
+pub struct NewPendingEdit {
+    pub kind: EditKind,
+    pub episode_number: EpisodeNumber,
+    pub event_id: Option<CompactString>,
+    pub timestamp: Option<i32>,
+    pub description: Option<CompactString>,
+    pub reason: Option<CompactString>,
+    pub submitted_at: i64,
+}
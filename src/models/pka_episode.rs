@@ -1,15 +1,20 @@
 use std::cmp::Ordering;
 
 use compact_str::CompactString;
-use float_ord::FloatOrd;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::models::episode_number::EpisodeNumber;
 
 #[derive(Clone, Debug, Serialize, FromRow, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PkaEpisode {
-    pub number: f32,
+    #[serde(rename = "show")]
+    #[schema(value_type = String)]
+    pub show_slug: CompactString,
+    #[schema(value_type = f64)]
+    pub number: EpisodeNumber,
     #[schema(value_type = String)]
     pub name: CompactString,
     #[serde(skip_serializing)]
@@ -20,12 +25,14 @@ pub struct PkaEpisode {
 
 impl PkaEpisode {
     pub fn new(
-        number: f32,
+        show_slug: CompactString,
+        number: EpisodeNumber,
         name: CompactString,
         youtube_link: CompactString,
         upload_date: i64,
     ) -> Self {
         Self {
+            show_slug,
             number,
             name,
             youtube_link,
@@ -33,14 +40,33 @@ impl PkaEpisode {
         }
     }
 
-    pub fn number(&self) -> f32 {
+    pub fn number(&self) -> EpisodeNumber {
         self.number
     }
 }
 
+/// Path parameters of episode routes. Any `{slug}` is read by the show extractor.
+#[derive(Debug, Deserialize)]
+pub struct EpisodePath {
+    pub number: EpisodeNumber,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VideoPath {
+    pub video_id: CompactString,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EpisodeTitleQuery {
+    /// Title containing an episode identifier, e.g. `PKA 481`.
+    #[param(example = "PKA 481")]
+    pub title: String,
+}
+
 impl std::cmp::Ord for PkaEpisode {
     fn cmp(&self, other: &Self) -> Ordering {
-        FloatOrd(self.number).cmp(&FloatOrd(other.number))
+        self.number.cmp(&other.number)
     }
 }
 
@@ -52,7 +78,7 @@ impl std::cmp::PartialOrd for PkaEpisode {
 
 impl std::cmp::PartialEq for PkaEpisode {
     fn eq(&self, other: &Self) -> bool {
-        FloatOrd(self.number).eq(&FloatOrd(other.number))
+        self.number == other.number
     }
 }
 
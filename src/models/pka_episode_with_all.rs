@@ -1,11 +1,12 @@
 use serde::Serialize;
 use utoipa::ToSchema;
 
+use crate::models::episode_number::EpisodeNumber;
 use crate::models::pka_episode::PkaEpisode;
 use crate::models::pka_event::PkaEvent;
 use crate::models::pka_youtube_details::PkaYoutubeDetails;
 
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Clone, Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PkaEpisodeWithAll {
     episode: PkaEpisode,
@@ -25,4 +26,8 @@ impl PkaEpisodeWithAll {
             events,
         }
     }
+
+    pub fn number(&self) -> EpisodeNumber {
+        self.episode.number()
+    }
 }
@@ -1,11 +1,12 @@
 use std::cmp::Ordering;
 
-use compact_str::CompactString;
-use float_ord::FloatOrd;
+use compact_str::{CompactString, ToCompactString};
 use serde::Serialize;
 use sqlx::FromRow;
 use utoipa::ToSchema;
 
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::show::DEFAULT_SHOW;
 use crate::search::Searchable;
 
 #[derive(Clone, Debug, Serialize, FromRow, ToSchema)]
@@ -15,7 +16,11 @@ pub struct PkaEvent {
     #[schema(value_type = String)]
     pub event_id: CompactString,
     #[serde(skip_serializing)]
-    pub episode_number: f32,
+    #[schema(value_type = String)]
+    pub show_slug: CompactString,
+    #[serde(skip_serializing)]
+    #[schema(value_type = f64)]
+    pub episode_number: EpisodeNumber,
     pub timestamp: i32,
     #[schema(value_type = String)]
     pub description: CompactString,
@@ -32,7 +37,8 @@ impl AsRef<PkaEvent> for &PkaEvent {
 impl PkaEvent {
     pub fn new(
         event_id: CompactString,
-        episode_number: f32,
+        show_slug: CompactString,
+        episode_number: EpisodeNumber,
         timestamp: i32,
         description: CompactString,
         length_seconds: i32,
@@ -40,6 +46,7 @@ impl PkaEvent {
     ) -> Self {
         PkaEvent {
             event_id,
+            show_slug,
             episode_number,
             timestamp,
             description,
@@ -48,7 +55,16 @@ impl PkaEvent {
         }
     }
 
-    pub fn episode_number(&self) -> f32 {
+    /// Ids of PKA events predate multi-show support, so only other shows carry their slug.
+    pub fn id_for(show_slug: &str, episode_number: EpisodeNumber, timestamp: i32) -> CompactString {
+        if show_slug == DEFAULT_SHOW {
+            format!("{:0>3}-{}", episode_number, timestamp).to_compact_string()
+        } else {
+            format!("{}-{:0>3}-{}", show_slug, episode_number, timestamp).to_compact_string()
+        }
+    }
+
+    pub fn episode_number(&self) -> EpisodeNumber {
         self.episode_number
     }
 
@@ -81,7 +97,7 @@ impl Searchable for PkaEvent {
 
 impl std::cmp::Ord for PkaEvent {
     fn cmp(&self, other: &Self) -> Ordering {
-        FloatOrd(self.episode_number()).cmp(&FloatOrd(other.episode_number()))
+        self.episode_number().cmp(&other.episode_number())
     }
 }
 
@@ -93,7 +109,7 @@ impl std::cmp::PartialOrd for PkaEvent {
 
 impl std::cmp::PartialEq for PkaEvent {
     fn eq(&self, other: &Self) -> bool {
-        FloatOrd(self.episode_number()).eq(&FloatOrd(other.episode_number()))
+        self.episode_number() == other.episode_number()
     }
 }
 
@@ -3,9 +3,11 @@ use serde::Serialize;
 
 use sqlx::FromRow;
 
+use crate::models::episode_number::EpisodeNumber;
+
 #[allow(dead_code)]
 #[derive(Clone, Debug, Serialize, FromRow)]
 pub struct PkaGuest {
     pub name: CompactString,
-    pub episode_number: f32,
+    pub episode_number: EpisodeNumber,
 }
@@ -3,13 +3,19 @@ use serde::Serialize;
 use sqlx::FromRow;
 use utoipa::ToSchema;
 
+use crate::models::episode_number::EpisodeNumber;
+
 #[derive(Clone, Debug, Serialize, FromRow, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PkaYoutubeDetails {
     #[schema(value_type = String)]
     pub video_id: CompactString,
     #[serde(skip_serializing)]
-    pub episode_number: f32,
+    #[schema(value_type = String)]
+    pub show_slug: CompactString,
+    #[serde(skip_serializing)]
+    #[schema(value_type = f64)]
+    pub episode_number: EpisodeNumber,
     #[schema(value_type = String)]
     pub title: CompactString,
     pub length_seconds: i32,
@@ -18,12 +24,14 @@ pub struct PkaYoutubeDetails {
 impl PkaYoutubeDetails {
     pub fn new(
         video_id: CompactString,
-        episode_number: f32,
+        show_slug: CompactString,
+        episode_number: EpisodeNumber,
         title: CompactString,
         length_seconds: i32,
     ) -> Self {
         PkaYoutubeDetails {
             video_id,
+            show_slug,
             episode_number,
             title,
             length_seconds,
@@ -0,0 +1,369 @@
+//! Episode feeds in RSS 2.0 and Atom, with Podcasting 2.0 `<podcast:chapters>` links to
+//! per-episode [`PodcastChapters`].
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use compact_str::CompactString;
+use serde::Serialize;
+use sqlx::FromRow;
+
+use crate::models::episode_number::EpisodeNumber;
+
+const PODCAST_NAMESPACE: &str = "https://podcastindex.org/namespace/1.0";
+const CHAPTERS_TYPE: &str = "application/json+chapters";
+
+#[derive(Debug, FromRow)]
+pub struct FeedEpisode {
+    pub number: EpisodeNumber,
+    pub name: CompactString,
+    pub youtube_link: CompactString,
+    pub upload_date: i64,
+    pub title: Option<CompactString>,
+    pub length_seconds: Option<i32>,
+    pub event_count: i64,
+}
+
+impl FeedEpisode {
+    pub fn title(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.name)
+    }
+
+    pub fn youtube_url(&self) -> String {
+        format!("https://www.youtube.com/watch?v={}", self.youtube_link)
+    }
+
+    pub fn published(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.upload_date, 0).unwrap_or_default()
+    }
+}
+
+/// Feed-wide details shared by the RSS and Atom versions.
+#[derive(Debug)]
+pub struct FeedChannel<'a> {
+    pub title: &'a str,
+    pub site_url: &'a str,
+    pub self_url: &'a str,
+    pub updated: DateTime<Utc>,
+}
+
+/// Link to an episode's chapters, when it has a timeline.
+pub struct FeedItem<'a> {
+    pub episode: &'a FeedEpisode,
+    pub chapters_url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename = "rss")]
+pub struct RssFeed {
+    #[serde(rename = "@version")]
+    version: &'static str,
+    #[serde(rename = "@xmlns:itunes")]
+    xmlns_itunes: &'static str,
+    #[serde(rename = "@xmlns:podcast")]
+    xmlns_podcast: &'static str,
+    #[serde(rename = "@xmlns:atom")]
+    xmlns_atom: &'static str,
+    channel: RssChannel,
+}
+
+#[derive(Debug, Serialize)]
+struct RssChannel {
+    title: String,
+    link: String,
+    description: String,
+    language: &'static str,
+    #[serde(rename = "lastBuildDate")]
+    last_build_date: String,
+    #[serde(rename = "atom:link")]
+    atom_link: Link,
+    #[serde(rename = "item")]
+    items: Vec<RssItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct RssItem {
+    title: String,
+    link: String,
+    guid: Guid,
+    #[serde(rename = "pubDate")]
+    pub_date: String,
+    #[serde(rename = "itunes:duration", skip_serializing_if = "Option::is_none")]
+    duration: Option<i32>,
+    #[serde(rename = "podcast:chapters", skip_serializing_if = "Option::is_none")]
+    chapters: Option<Chapters>,
+}
+
+#[derive(Debug, Serialize)]
+struct Guid {
+    #[serde(rename = "@isPermaLink")]
+    is_perma_link: bool,
+    #[serde(rename = "$text")]
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Link {
+    #[serde(rename = "@href")]
+    href: String,
+    #[serde(rename = "@rel")]
+    rel: &'static str,
+    #[serde(rename = "@type", skip_serializing_if = "Option::is_none")]
+    kind: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+struct Chapters {
+    #[serde(rename = "@url")]
+    url: String,
+    #[serde(rename = "@type")]
+    kind: &'static str,
+}
+
+impl Chapters {
+    fn new(url: String) -> Self {
+        Self {
+            url,
+            kind: CHAPTERS_TYPE,
+        }
+    }
+}
+
+impl RssFeed {
+    pub fn new(channel: &FeedChannel, items: Vec<FeedItem>) -> Self {
+        let items = items
+            .into_iter()
+            .map(
+                |FeedItem {
+                     episode,
+                     chapters_url,
+                 }| RssItem {
+                    title: episode.title().to_owned(),
+                    link: episode.youtube_url(),
+                    guid: Guid {
+                        is_perma_link: false,
+                        value: episode.youtube_link.to_string(),
+                    },
+                    pub_date: episode.published().to_rfc2822(),
+                    duration: episode.length_seconds,
+                    chapters: chapters_url.map(Chapters::new),
+                },
+            )
+            .collect();
+
+        Self {
+            version: "2.0",
+            xmlns_itunes: "http://www.itunes.com/dtds/podcast-1.0.dtd",
+            xmlns_podcast: PODCAST_NAMESPACE,
+            xmlns_atom: "http://www.w3.org/2005/Atom",
+            channel: RssChannel {
+                title: channel.title.to_owned(),
+                link: channel.site_url.to_owned(),
+                description: format!("Every {} episode with its timeline", channel.title),
+                language: "en",
+                last_build_date: channel.updated.to_rfc2822(),
+                atom_link: Link {
+                    href: channel.self_url.to_owned(),
+                    rel: "self",
+                    kind: Some("application/rss+xml"),
+                },
+                items,
+            },
+        }
+    }
+
+    pub fn to_xml_string(&self) -> anyhow::Result<String> {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str(&quick_xml::se::to_string(&self)?);
+
+        Ok(xml)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename = "feed")]
+pub struct AtomFeed {
+    #[serde(rename = "@xmlns")]
+    xmlns: &'static str,
+    #[serde(rename = "@xmlns:podcast")]
+    xmlns_podcast: &'static str,
+    id: String,
+    title: String,
+    updated: String,
+    author: AtomAuthor,
+    link: Vec<Link>,
+    #[serde(rename = "entry")]
+    entries: Vec<AtomEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct AtomAuthor {
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct AtomEntry {
+    id: String,
+    title: String,
+    published: String,
+    updated: String,
+    link: Link,
+    #[serde(rename = "podcast:chapters", skip_serializing_if = "Option::is_none")]
+    chapters: Option<Chapters>,
+}
+
+impl AtomFeed {
+    pub fn new(channel: &FeedChannel, items: Vec<FeedItem>) -> Self {
+        let entries = items
+            .into_iter()
+            .map(
+                |FeedItem {
+                     episode,
+                     chapters_url,
+                 }| {
+                    let published = atom_date(episode.published());
+
+                    AtomEntry {
+                        id: episode.youtube_url(),
+                        title: episode.title().to_owned(),
+                        updated: published.clone(),
+                        published,
+                        link: Link {
+                            href: episode.youtube_url(),
+                            rel: "alternate",
+                            kind: None,
+                        },
+                        chapters: chapters_url.map(Chapters::new),
+                    }
+                },
+            )
+            .collect();
+
+        Self {
+            xmlns: "http://www.w3.org/2005/Atom",
+            xmlns_podcast: PODCAST_NAMESPACE,
+            id: channel.self_url.to_owned(),
+            title: channel.title.to_owned(),
+            updated: atom_date(channel.updated),
+            author: AtomAuthor {
+                name: channel.title.to_owned(),
+            },
+            link: vec![
+                Link {
+                    href: channel.self_url.to_owned(),
+                    rel: "self",
+                    kind: Some("application/atom+xml"),
+                },
+                Link {
+                    href: channel.site_url.to_owned(),
+                    rel: "alternate",
+                    kind: None,
+                },
+            ],
+            entries,
+        }
+    }
+
+    pub fn to_xml_string(&self) -> anyhow::Result<String> {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str(&quick_xml::se::to_string(&self)?);
+
+        Ok(xml)
+    }
+}
+
+fn atom_date(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Podcasting 2.0 JSON chapters, see
+/// <https://github.com/Podcastindex-org/podcast-namespace/blob/main/docs/examples/chapters/jsonChapters.md>.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodcastChapters {
+    pub version: &'static str,
+    pub chapters: Vec<PodcastChapter>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodcastChapter {
+    pub start_time: i32,
+    pub end_time: i32,
+    pub title: CompactString,
+}
+
+impl PodcastChapters {
+    pub fn new(chapters: Vec<PodcastChapter>) -> Self {
+        Self {
+            version: "1.2.0",
+            chapters,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_feeds() {
+        let episodes = [
+            FeedEpisode {
+                number: EpisodeNumber::new(481),
+                name: "PKA 481".into(),
+                youtube_link: "HuxCJNLOtoU".into(),
+                upload_date: 1583539200,
+                title: Some("PKA 481 - Woody & Kyle".into()),
+                length_seconds: Some(10800),
+                event_count: 2,
+            },
+            FeedEpisode {
+                number: EpisodeNumber::new(480),
+                name: "PKA 480".into(),
+                youtube_link: "ArTDpBUAFrY".into(),
+                upload_date: 1582934400,
+                title: None,
+                length_seconds: None,
+                event_count: 0,
+            },
+        ];
+
+        let channel = FeedChannel {
+            title: "Painkiller Already",
+            site_url: "https://example.com",
+            self_url: "https://example.com/feed/episodes.rss",
+            updated: episodes[0].published(),
+        };
+
+        let items = || {
+            episodes
+                .iter()
+                .map(|episode| FeedItem {
+                    episode,
+                    chapters_url: (episode.event_count > 0)
+                        .then(|| "https://example.com/feed/episodes/481/chapters.json".to_owned()),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let rss = RssFeed::new(&channel, items()).to_xml_string().unwrap();
+
+        assert!(rss.contains("<lastBuildDate>Sat, 7 Mar 2020 00:00:00 +0000</lastBuildDate>"));
+        assert!(rss.contains(
+            "<item><title>PKA 481 - Woody &amp; Kyle</title>\
+             <link>https://www.youtube.com/watch?v=HuxCJNLOtoU</link>\
+             <guid isPermaLink=\"false\">HuxCJNLOtoU</guid>\
+             <pubDate>Sat, 7 Mar 2020 00:00:00 +0000</pubDate>\
+             <itunes:duration>10800</itunes:duration>\
+             <podcast:chapters url=\"https://example.com/feed/episodes/481/chapters.json\" type=\"application/json+chapters\"/>\
+             </item>"
+        ));
+        assert!(rss.contains("<item><title>PKA 480</title>"));
+        assert_eq!(rss.matches("<podcast:chapters").count(), 1);
+
+        let atom = AtomFeed::new(&channel, items()).to_xml_string().unwrap();
+
+        assert!(atom.contains("<updated>2020-03-07T00:00:00Z</updated>"));
+        assert_eq!(atom.matches("<entry>").count(), 2);
+        assert_eq!(atom.matches("<podcast:chapters").count(), 1);
+    }
+}
@@ -1,8 +1,11 @@
+use chrono::{DateTime, Utc};
+use compact_str::CompactString;
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename = "feed")]
 pub struct YoutubeRssFeed {
+    #[serde(default)]
     entry: Vec<Entry>,
 }
 
@@ -17,7 +20,7 @@ pub struct Entry {
     #[serde(rename = "videoId")]
     video_id: CompactString,
     title: CompactString,
-    published: CompactString,
+    published: DateTime<Utc>,
 }
 
 impl Entry {
@@ -29,7 +32,27 @@ impl Entry {
         &self.title
     }
 
-    pub fn published(&self) -> &str {
-        &self.published
+    pub fn published(&self) -> DateTime<Utc> {
+        self.published
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_feed() {
+        let feed: YoutubeRssFeed =
+            quick_xml::de::from_str(include_str!("../updater/youtube_api/fixtures/feed.xml"))
+                .expect("Failed to read feed");
+
+        let entries = feed.entry();
+        assert_eq!(entries.len(), 6);
+
+        let latest = &entries[0];
+        assert_eq!(latest.video_id(), "vidPKA703");
+        assert_eq!(latest.title(), "PKA 703 - Woody, Kyle & Taylor");
+        assert_eq!(latest.published().timestamp(), 1709845212);
     }
 }
@@ -33,7 +33,7 @@ impl<T: AsRef<PkaEvent>> From<T> for PkaEventSearchResult {
         let evt = evt.as_ref();
 
         Self {
-            episode_number: evt.episode_number(),
+            episode_number: evt.episode_number().as_f32(),
             timestamp: evt.timestamp(),
             description: evt.description().to_owned(),
             length_seconds: evt.length_seconds(),
@@ -0,0 +1,32 @@
+use compact_str::CompactString;
+use regex::Regex;
+use serde::Serialize;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Show served by the un-prefixed `/api/v1/episodes` and `/api/v1/events` routes.
+pub const DEFAULT_SHOW: &str = "pka";
+
+#[derive(Clone, Debug, Serialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Show {
+    #[schema(value_type = String)]
+    pub slug: CompactString,
+    #[schema(value_type = String)]
+    pub name: CompactString,
+    #[schema(value_type = String)]
+    pub episode_prefix: CompactString,
+    #[schema(value_type = String)]
+    pub playlist_id: CompactString,
+    #[serde(skip_serializing)]
+    #[schema(value_type = String)]
+    pub title_regex: CompactString,
+    #[serde(skip_serializing)]
+    pub first_episode_number: i64,
+}
+
+impl Show {
+    pub fn compile_title_regex(&self) -> Result<Regex, regex::Error> {
+        Regex::new(&self.title_regex)
+    }
+}
@@ -0,0 +1,79 @@
+use std::fmt;
+
+use compact_str::CompactString;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PkaStats {
+    pub totals: PkaStatsTotals,
+    pub uploads_per_year: Vec<YearCount>,
+    pub top_guests: Vec<GuestCount>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PkaStatsTotals {
+    pub episode_count: i64,
+    pub total_runtime_seconds: i64,
+    pub event_count: i64,
+    pub average_events_per_episode: f64,
+    pub max_events_per_episode: i64,
+    pub episodes_without_timeline: i64,
+    pub average_event_length_seconds: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct YearCount {
+    #[schema(value_type = String)]
+    pub year: CompactString,
+    pub episode_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GuestCount {
+    #[schema(value_type = String)]
+    pub name: CompactString,
+    pub episode_count: i64,
+}
+
+impl fmt::Display for PkaStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let t = &self.totals;
+
+        writeln!(f, "Episodes: {}", t.episode_count)?;
+        writeln!(f, "Total runtime: {}h", t.total_runtime_seconds / 3600)?;
+        writeln!(f, "Events: {}", t.event_count)?;
+        writeln!(
+            f,
+            "Events per episode: {:.1} average, {} max",
+            t.average_events_per_episode, t.max_events_per_episode
+        )?;
+        writeln!(
+            f,
+            "Episodes without a timeline: {}",
+            t.episodes_without_timeline
+        )?;
+        writeln!(
+            f,
+            "Average event length: {:.0}s",
+            t.average_event_length_seconds
+        )?;
+
+        writeln!(f, "Uploads per year:")?;
+        for year in &self.uploads_per_year {
+            writeln!(f, "  {}: {}", year.year, year.episode_count)?;
+        }
+
+        writeln!(f, "Top guests:")?;
+        for guest in &self.top_guests {
+            writeln!(f, "  {}: {}", guest.name, guest.episode_count)?;
+        }
+
+        Ok(())
+    }
+}
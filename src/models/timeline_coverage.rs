@@ -0,0 +1,109 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::models::episode_number::EpisodeNumber;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineCoverageQuery {
+    /// Episodes with fewer events than this are reported. Defaults to 5.
+    pub min_events: Option<usize>,
+    /// Stretches without an event longer than this are reported. Defaults to 1800.
+    pub max_gap_seconds: Option<i32>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct EpisodeLength {
+    pub episode_number: EpisodeNumber,
+    pub length_seconds: Option<i32>,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineCoverageReport {
+    #[schema(value_type = Vec<f64>)]
+    pub episodes_without_events: Vec<EpisodeNumber>,
+    pub episodes_with_few_events: Vec<EpisodeEventCount>,
+    pub events_exceeding_video: Vec<EventOverrun>,
+    pub timeline_gaps: Vec<TimelineGap>,
+}
+
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeEventCount {
+    #[schema(value_type = f64)]
+    pub episode_number: EpisodeNumber,
+    pub event_count: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventOverrun {
+    #[schema(value_type = f64)]
+    pub episode_number: EpisodeNumber,
+    pub timestamp: i32,
+    pub length_seconds: i32,
+    pub video_length_seconds: i32,
+}
+
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineGap {
+    #[schema(value_type = f64)]
+    pub episode_number: EpisodeNumber,
+    pub start: i32,
+    pub end: i32,
+}
+
+impl fmt::Display for TimelineCoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Episodes without events ({}):",
+            self.episodes_without_events.len()
+        )?;
+        for number in &self.episodes_without_events {
+            writeln!(f, "  PKA {number}")?;
+        }
+
+        writeln!(
+            f,
+            "\nEpisodes with few events ({}):",
+            self.episodes_with_few_events.len()
+        )?;
+        for e in &self.episodes_with_few_events {
+            writeln!(f, "  PKA {} - {} events", e.episode_number, e.event_count)?;
+        }
+
+        writeln!(
+            f,
+            "\nEvents exceeding video length ({}):",
+            self.events_exceeding_video.len()
+        )?;
+        for e in &self.events_exceeding_video {
+            writeln!(
+                f,
+                "  PKA {} @ {}s - ends at {}s, video is {}s",
+                e.episode_number,
+                e.timestamp,
+                e.timestamp + e.length_seconds,
+                e.video_length_seconds
+            )?;
+        }
+
+        writeln!(f, "\nTimeline gaps ({}):", self.timeline_gaps.len())?;
+        for g in &self.timeline_gaps {
+            writeln!(
+                f,
+                "  PKA {} - no events between {}s and {}s",
+                g.episode_number, g.start, g.end
+            )?;
+        }
+
+        Ok(())
+    }
+}
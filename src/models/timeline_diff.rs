@@ -0,0 +1,85 @@
+use std::fmt;
+
+use compact_str::CompactString;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::pka_event::PkaEvent;
+use crate::models::timeline_warning::TimelineWarning;
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineDiff {
+    #[schema(value_type = f64)]
+    pub episode_number: EpisodeNumber,
+    pub added: Vec<PkaEvent>,
+    pub removed: Vec<PkaEvent>,
+    pub changed: Vec<EventChange>,
+    pub unchanged: usize,
+    /// Problems found in the current description's timeline.
+    pub warnings: Vec<TimelineWarning>,
+    /// Fingerprint of the description the timeline was extracted from. Sent back to apply
+    /// a previewed re-extraction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub revision: Option<CompactString>,
+    pub applied: bool,
+}
+
+impl TimelineDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventChange {
+    pub before: PkaEvent,
+    pub after: PkaEvent,
+}
+
+impl fmt::Display for TimelineDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "PKA {}: {} added, {} removed, {} changed, {} unchanged",
+            self.episode_number,
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len(),
+            self.unchanged
+        )?;
+
+        for e in &self.removed {
+            writeln!(f, "- [{}] {}", e.timestamp(), e.description())?;
+        }
+
+        for e in &self.added {
+            writeln!(f, "+ [{}] {}", e.timestamp(), e.description())?;
+        }
+
+        for c in &self.changed {
+            writeln!(
+                f,
+                "~ [{}] {} ({}s) -> {} ({}s)",
+                c.before.timestamp(),
+                c.before.description(),
+                c.before.length_seconds(),
+                c.after.description(),
+                c.after.length_seconds()
+            )?;
+        }
+
+        for w in &self.warnings {
+            writeln!(f, "! {w}")?;
+        }
+
+        if self.applied {
+            writeln!(f, "Changes applied.")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use std::fmt;
+
+use compact_str::CompactString;
+use serde::{Deserialize, Serialize};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::sqlite::{SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef};
+use sqlx::{Decode, Encode, Sqlite, Type};
+use utoipa::ToSchema;
+
+/// A problem found while extracting an episode's timeline. The offending event is left
+/// out unless noted otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum TimelineWarning {
+    /// A timestamp that isn't a valid time, e.g. `2:80:05`.
+    InvalidTimestamp {
+        #[schema(value_type = String)]
+        raw: CompactString,
+    },
+    /// Starts at or after the end of the video.
+    PastEnd {
+        timestamp: i32,
+        video_length_seconds: i32,
+    },
+    /// Another event already starts at the same time.
+    Duplicate { timestamp: i32 },
+    /// Listed after a later timestamp. The event is kept.
+    OutOfOrder { timestamp: i32, previous: i32 },
+    /// A range running past the end of the video. The event is kept and cut short.
+    EndPastVideo { timestamp: i32, end: i32 },
+}
+
+impl TimelineWarning {
+    /// Start of the entry the warning is about, when it has one.
+    pub fn timestamp(&self) -> Option<i32> {
+        match self {
+            Self::InvalidTimestamp { .. } => None,
+            Self::PastEnd { timestamp, .. }
+            | Self::Duplicate { timestamp }
+            | Self::OutOfOrder { timestamp, .. }
+            | Self::EndPastVideo { timestamp, .. } => Some(*timestamp),
+        }
+    }
+}
+
+impl fmt::Display for TimelineWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidTimestamp { raw } => write!(f, "invalid timestamp '{raw}'"),
+            Self::PastEnd {
+                timestamp,
+                video_length_seconds,
+            } => write!(
+                f,
+                "timestamp {timestamp}s is past the end of the video ({video_length_seconds}s)"
+            ),
+            Self::Duplicate { timestamp } => write!(f, "duplicate timestamp {timestamp}s"),
+            Self::OutOfOrder {
+                timestamp,
+                previous,
+            } => write!(f, "timestamp {timestamp}s is listed after {previous}s"),
+            Self::EndPastVideo { timestamp, end } => write!(
+                f,
+                "event at {timestamp}s ends at {end}s, past the end of the video"
+            ),
+        }
+    }
+}
+
+/// Stored as a JSON array.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct TimelineWarnings(pub Vec<TimelineWarning>);
+
+impl Type<Sqlite> for TimelineWarnings {
+    fn type_info() -> SqliteTypeInfo {
+        <&str as Type<Sqlite>>::type_info()
+    }
+
+    fn compatible(ty: &SqliteTypeInfo) -> bool {
+        <&str as Type<Sqlite>>::compatible(ty)
+    }
+}
+
+impl<'q> Encode<'q, Sqlite> for TimelineWarnings {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'q>>) -> Result<IsNull, BoxDynError> {
+        <String as Encode<Sqlite>>::encode(serde_json::to_string(&self.0)?, buf)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for TimelineWarnings {
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Self(serde_json::from_str(
+            <&str as Decode<Sqlite>>::decode(value)?,
+        )?))
+    }
+}
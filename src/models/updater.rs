@@ -1,7 +1,8 @@
-#![allow(dead_code)]
-
 use serde::{Deserialize, Serialize};
 
+use crate::models::episode_number::EpisodeNumber;
+
+/// Episode list such as `pka_episode_list.json`.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct EpisodesFileRoot {
     pub episodes: Vec<EpisodeFromFile>,
@@ -11,13 +12,13 @@ pub struct EpisodesFileRoot {
 #[serde(rename_all = "PascalCase")]
 pub struct EpisodeFromFile {
     pub identifier: String,
-    pub number: f32,
+    pub number: EpisodeNumber,
+    /// Upload date as `YYYY-MM-DD`, or RFC 3339 when it has a time of day.
     pub date_time: String,
-    date: String,
     pub you_tube: String,
-    timelined: bool,
 }
 
+/// Episode dump from the painkilleralready.info API.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PkaInfoRoot {
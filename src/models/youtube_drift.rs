@@ -0,0 +1,60 @@
+use compact_str::CompactString;
+use serde::Serialize;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+use crate::models::episode_number::EpisodeNumber;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum DriftKind {
+    Title,
+    Duration,
+    /// The timeline extracted from the current description differs from the stored events.
+    Description,
+    /// The video is private, removed or otherwise missing from the API response.
+    Unavailable,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct YoutubeDrift {
+    #[schema(value_type = String)]
+    pub video_id: CompactString,
+    #[serde(rename = "show")]
+    #[schema(value_type = String)]
+    pub show_slug: CompactString,
+    #[schema(value_type = f64)]
+    pub episode_number: EpisodeNumber,
+    pub kind: DriftKind,
+    #[schema(value_type = Option<String>)]
+    pub stored_value: Option<CompactString>,
+    #[schema(value_type = Option<String>)]
+    pub current_value: Option<CompactString>,
+    pub first_detected_at: i64,
+    pub last_checked_at: i64,
+}
+
+impl YoutubeDrift {
+    pub fn new(
+        video_id: CompactString,
+        show_slug: CompactString,
+        episode_number: EpisodeNumber,
+        kind: DriftKind,
+        stored_value: Option<CompactString>,
+        current_value: Option<CompactString>,
+        checked_at: i64,
+    ) -> Self {
+        Self {
+            video_id,
+            show_slug,
+            episode_number,
+            kind,
+            stored_value,
+            current_value,
+            first_detected_at: checked_at,
+            last_checked_at: checked_at,
+        }
+    }
+}
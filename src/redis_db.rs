@@ -10,6 +10,7 @@ pub struct RedisDb {
 
 impl RedisDb {
     const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+    const DEFAULT_EXPIRY_SECONDS: u64 = 30;
 
     pub async fn new(redis_url: &str) -> anyhow::Result<Self> {
         let manager = RedisConnectionManager::new(redis_url)
@@ -46,6 +47,17 @@ impl RedisDb {
     }
 
     pub async fn set(&self, redis_tag: String, key: String, value: &[u8]) -> anyhow::Result<()> {
+        self.set_with_expiry(redis_tag, key, value, Self::DEFAULT_EXPIRY_SECONDS)
+            .await
+    }
+
+    pub async fn set_with_expiry(
+        &self,
+        redis_tag: String,
+        key: String,
+        value: &[u8],
+        expiry_seconds: u64,
+    ) -> anyhow::Result<()> {
         ensure!(!value.is_empty(), "Redis will not cache empty vector");
 
         let value = value.to_vec();
@@ -54,13 +66,65 @@ impl RedisDb {
 
         let key = format!("{}-{}", redis_tag, key);
 
-        conn.set_ex::<_, _, ()>(key.clone(), value, 30)
+        conn.set_ex::<_, _, ()>(key.clone(), value, expiry_seconds)
             .await
             .with_context(|| format!("Failed to cache redis value for key '{key}'"))?;
 
         Ok(())
     }
 
+    /// Increments a counter, starting its expiry when it's first created, and returns the
+    /// new count.
+    pub async fn incr_with_expiry(
+        &self,
+        redis_tag: String,
+        key: String,
+        expiry_seconds: i64,
+    ) -> anyhow::Result<u64> {
+        let mut conn = Self::acquire_connection(&self.connection_pool).await?;
+
+        let key = format!("{}-{}", redis_tag, key);
+
+        let count: u64 = conn
+            .incr(&key, 1)
+            .await
+            .with_context(|| format!("Failed to increment redis key '{key}'"))?;
+
+        if count == 1 {
+            conn.expire::<_, ()>(&key, expiry_seconds)
+                .await
+                .with_context(|| format!("Failed to set expiry on redis key '{key}'"))?;
+        }
+
+        Ok(count)
+    }
+
+    pub async fn delete_tag(&self, redis_tag: &str) -> anyhow::Result<usize> {
+        let mut conn = Self::acquire_connection(&self.connection_pool).await?;
+
+        let pattern = format!("{}-*", redis_tag);
+
+        let mut keys: Vec<String> = Vec::new();
+        {
+            let mut iter = conn
+                .scan_match::<_, String>(&pattern)
+                .await
+                .with_context(|| format!("Failed to scan redis keys matching '{pattern}'"))?;
+
+            while let Some(key) = iter.next_item().await {
+                keys.push(key?);
+            }
+        }
+
+        if !keys.is_empty() {
+            conn.del::<_, ()>(&keys)
+                .await
+                .with_context(|| format!("Failed to delete redis keys matching '{pattern}'"))?;
+        }
+
+        Ok(keys.len())
+    }
+
     async fn acquire_connection(
         pool: &Pool<RedisConnectionManager>,
     ) -> anyhow::Result<PooledConnection<'_, RedisConnectionManager>> {
@@ -0,0 +1 @@
+pub mod timeline_coverage;
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+
+use crate::conduit::sqlite::{pka_episode, pka_event};
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::pka_event::PkaEvent;
+use crate::models::show::DEFAULT_SHOW;
+use crate::models::timeline_coverage::{
+    EpisodeEventCount, EpisodeLength, EventOverrun, TimelineCoverageQuery, TimelineCoverageReport,
+    TimelineGap,
+};
+use crate::Repo;
+
+pub const DEFAULT_MIN_EVENTS: usize = 5;
+pub const DEFAULT_MAX_GAP_SECONDS: i32 = 30 * 60;
+
+#[derive(Debug, Clone, Copy)]
+pub struct CoverageOptions {
+    pub min_events: usize,
+    pub max_gap_seconds: i32,
+}
+
+impl Default for CoverageOptions {
+    fn default() -> Self {
+        Self {
+            min_events: DEFAULT_MIN_EVENTS,
+            max_gap_seconds: DEFAULT_MAX_GAP_SECONDS,
+        }
+    }
+}
+
+impl From<TimelineCoverageQuery> for CoverageOptions {
+    fn from(query: TimelineCoverageQuery) -> Self {
+        let defaults = Self::default();
+
+        Self {
+            min_events: query.min_events.unwrap_or(defaults.min_events),
+            max_gap_seconds: query.max_gap_seconds.unwrap_or(defaults.max_gap_seconds),
+        }
+    }
+}
+
+pub async fn generate(
+    repo: &Repo,
+    options: CoverageOptions,
+) -> anyhow::Result<TimelineCoverageReport> {
+    let episodes = pka_episode::all_lengths(repo, DEFAULT_SHOW)
+        .await
+        .context("Failed to load episodes for timeline coverage report")?;
+
+    let events = pka_event::for_show(repo, DEFAULT_SHOW)
+        .await
+        .context("Failed to load events for timeline coverage report")?;
+
+    Ok(build(episodes, events, options))
+}
+
+fn build(
+    mut episodes: Vec<EpisodeLength>,
+    mut events: Vec<PkaEvent>,
+    options: CoverageOptions,
+) -> TimelineCoverageReport {
+    episodes.sort_by_key(|e| e.episode_number);
+    events.sort_by_key(|e| e.timestamp());
+
+    let mut events_by_episode: HashMap<EpisodeNumber, Vec<&PkaEvent>> = HashMap::new();
+    for event in &events {
+        events_by_episode
+            .entry(event.episode_number())
+            .or_default()
+            .push(event);
+    }
+
+    let mut report = TimelineCoverageReport::default();
+
+    for episode in episodes {
+        let number = episode.episode_number;
+
+        let episode_events = events_by_episode
+            .get(&number)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        if episode_events.is_empty() {
+            report.episodes_without_events.push(number);
+            continue;
+        }
+
+        if episode_events.len() < options.min_events {
+            report.episodes_with_few_events.push(EpisodeEventCount {
+                episode_number: number,
+                event_count: episode_events.len(),
+            });
+        }
+
+        if let Some(video_length_seconds) = episode.length_seconds {
+            report.events_exceeding_video.extend(
+                episode_events
+                    .iter()
+                    .filter(|e| e.timestamp() + e.length_seconds() > video_length_seconds)
+                    .map(|e| EventOverrun {
+                        episode_number: number,
+                        timestamp: e.timestamp(),
+                        length_seconds: e.length_seconds(),
+                        video_length_seconds,
+                    }),
+            );
+        }
+
+        let mut previous = 0;
+        let boundaries = episode_events
+            .iter()
+            .map(|e| e.timestamp())
+            .chain(episode.length_seconds);
+
+        for boundary in boundaries {
+            if boundary - previous > options.max_gap_seconds {
+                report.timeline_gaps.push(TimelineGap {
+                    episode_number: number,
+                    start: previous,
+                    end: boundary,
+                });
+            }
+
+            previous = previous.max(boundary);
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use compact_str::ToCompactString;
+
+    use super::*;
+
+    fn event(episode_number: u32, timestamp: i32, length_seconds: i32) -> PkaEvent {
+        PkaEvent::new(
+            format!("{episode_number}-{timestamp}").to_compact_string(),
+            DEFAULT_SHOW.to_compact_string(),
+            EpisodeNumber::new(episode_number),
+            timestamp,
+            "Event".to_compact_string(),
+            length_seconds,
+            0,
+        )
+    }
+
+    fn episode(episode_number: u32, length_seconds: Option<i32>) -> EpisodeLength {
+        EpisodeLength {
+            episode_number: EpisodeNumber::new(episode_number),
+            length_seconds,
+        }
+    }
+
+    #[test]
+    fn test_build_report() {
+        let episodes = vec![
+            episode(1, Some(3000)),
+            episode(2, Some(10_000)),
+            episode(3, None),
+        ];
+
+        let events = vec![
+            event(1, 0, 1000),
+            event(1, 1000, 1000),
+            event(1, 2000, 1500),
+            event(2, 2500, 1000),
+            event(2, 3500, 6500),
+        ];
+
+        let options = CoverageOptions {
+            min_events: 3,
+            max_gap_seconds: 2000,
+        };
+
+        let report = build(episodes, events, options);
+
+        assert_eq!(report.episodes_without_events, vec![EpisodeNumber::new(3)]);
+        assert_eq!(
+            report.episodes_with_few_events,
+            vec![EpisodeEventCount {
+                episode_number: EpisodeNumber::new(2),
+                event_count: 2,
+            }]
+        );
+        assert_eq!(
+            report.events_exceeding_video,
+            vec![EventOverrun {
+                episode_number: EpisodeNumber::new(1),
+                timestamp: 2000,
+                length_seconds: 1500,
+                video_length_seconds: 3000,
+            }]
+        );
+        assert_eq!(
+            report.timeline_gaps,
+            vec![
+                TimelineGap {
+                    episode_number: EpisodeNumber::new(2),
+                    start: 0,
+                    end: 2500,
+                },
+                TimelineGap {
+                    episode_number: EpisodeNumber::new(2),
+                    start: 3500,
+                    end: 10_000,
+                },
+            ]
+        );
+    }
+}
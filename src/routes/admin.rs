@@ -0,0 +1,28 @@
+use axum::{
+    routing::{get, post, put},
+    Router,
+};
+
+use crate::app_state::AppState;
+use crate::handlers::admin;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/episodes/check-new", post(admin::check_new_episodes))
+        .route(
+            "/episodes/{number}/re-extract",
+            get(admin::preview_reextract_pka_episode).post(admin::reextract_pka_episode),
+        )
+        .route(
+            "/events/{event_id}",
+            put(admin::edit_pka_event).delete(admin::delete_pka_event),
+        )
+        .route(
+            "/timelines/import/preview",
+            post(admin::preview_timeline_import),
+        )
+        .route("/timelines/import", post(admin::import_timelines))
+        .route("/cache/flush", post(admin::flush_caches))
+        .route("/drift", get(admin::drift_report))
+        .route("/ingestion-log", get(admin::ingestion_log))
+}
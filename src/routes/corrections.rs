@@ -0,0 +1,15 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::app_state::AppState;
+use crate::handlers::corrections;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", post(corrections::submit_correction))
+        .route("/pending", get(corrections::pending_corrections))
+        .route("/{id}/approve", post(corrections::approve_correction))
+        .route("/{id}/reject", post(corrections::reject_correction))
+}
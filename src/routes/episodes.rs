@@ -1,4 +1,7 @@
-use axum::{routing::get, Router};
+use axum::{
+    routing::{get, post},
+    Router,
+};
 
 use crate::app_state::AppState;
 use crate::handlers::episode;
@@ -11,5 +14,11 @@ pub fn router() -> Router<AppState> {
             get(episode::find_pka_episode_youtube_link),
         )
         .route("/latest", get(episode::latest_pka_episode))
+        .route("/batch", post(episode::batch_pka_episodes))
+        .route("/by-title", get(episode::find_pka_episode_by_title))
+        .route(
+            "/by-video/{video_id}",
+            get(episode::find_pka_episode_by_video_id),
+        )
         .route("/random", get(episode::random_pka_episode))
 }
@@ -0,0 +1,10 @@
+use axum::{routing::get, Router};
+
+use crate::app_state::AppState;
+use crate::handlers::export;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/snapshot.sqlite3", get(export::export_snapshot))
+        .route("/{file}", get(export::export_dataset))
+}
@@ -0,0 +1,14 @@
+use axum::{routing::get, Router};
+
+use crate::app_state::AppState;
+use crate::handlers::feed;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/episodes.rss", get(feed::episodes_rss))
+        .route("/episodes.atom", get(feed::episodes_atom))
+        .route(
+            "/episodes/{number}/chapters.json",
+            get(feed::episode_chapters),
+        )
+}
@@ -1,8 +1,16 @@
+mod admin;
+mod corrections;
 pub mod docs;
 mod episodes;
 mod events;
+mod export;
+mod feeds;
+mod on_this_day;
+mod reports;
 mod search;
+mod shows;
 mod static_assets;
+mod stats;
 
 use axum::http::StatusCode;
 use axum::routing::any;
@@ -13,12 +21,20 @@ use crate::models::errors::ApiError;
 
 pub fn build_router() -> Router<AppState> {
     let api = Router::new()
+        .nest("/admin", admin::router())
+        .nest("/corrections", corrections::router())
         .nest("/episodes", episodes::router())
         .nest("/events", events::router())
-        .nest("/search", search::router());
+        .nest("/export", export::router())
+        .nest("/on-this-day", on_this_day::router())
+        .nest("/reports", reports::router())
+        .nest("/search", search::router())
+        .nest("/shows", shows::router())
+        .nest("/stats", stats::router());
 
     Router::new()
         .nest("/api/v1", api)
+        .nest("/feed", feeds::router())
         .merge(static_assets::router())
         .fallback(any(not_found))
 }
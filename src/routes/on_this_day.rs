@@ -0,0 +1,8 @@
+use axum::{routing::get, Router};
+
+use crate::app_state::AppState;
+use crate::handlers::on_this_day;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", get(on_this_day::on_this_day))
+}
@@ -0,0 +1,8 @@
+use axum::{routing::get, Router};
+
+use crate::app_state::AppState;
+use crate::handlers::reports;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/timeline-coverage", get(reports::timeline_coverage))
+}
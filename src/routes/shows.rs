@@ -0,0 +1,16 @@
+use axum::{routing::get, Router};
+
+use crate::app_state::AppState;
+use crate::handlers::show;
+
+use super::{episodes, events, on_this_day, stats};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(show::list_shows))
+        .route("/{slug}", get(show::find_show))
+        .nest("/{slug}/episodes", episodes::router())
+        .nest("/{slug}/events", events::router())
+        .nest("/{slug}/on-this-day", on_this_day::router())
+        .nest("/{slug}/stats", stats::router())
+}
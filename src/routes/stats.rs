@@ -0,0 +1,8 @@
+use axum::{routing::get, Router};
+
+use crate::app_state::AppState;
+use crate::handlers::stats;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", get(stats::archive_stats))
+}
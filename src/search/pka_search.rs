@@ -5,13 +5,16 @@ use rayon::prelude::*;
 use crate::conduit::redis::event_cache;
 use crate::conduit::sqlite::pka_episode;
 use crate::models::search::PkaEventSearchResult;
+use crate::models::show::DEFAULT_SHOW;
 use crate::redis_db::RedisDb;
 use crate::search::{Encodeable, Searchable};
 use crate::Repo;
 use crate::PKA_EVENTS_INDEX;
 
+pub const EVENTS_REDIS_TAG: &str = "EVENTS";
+
 pub async fn search_episode(state: &Repo, query: &str) -> anyhow::Result<Vec<u8>> {
-    let all_episodes = pka_episode::all_with_yt_details(state)
+    let all_episodes = pka_episode::all_with_yt_details(state, DEFAULT_SHOW)
         .await
         .with_context(|| format!("Failed to load episodes for search with query '{query}'"))?;
 
@@ -29,7 +32,7 @@ pub async fn search_episode(state: &Repo, query: &str) -> anyhow::Result<Vec<u8>
 }
 
 pub async fn search_events(redis: &RedisDb, query: &str) -> anyhow::Result<Vec<u8>> {
-    let redis_tag = "EVENTS";
+    let redis_tag = EVENTS_REDIS_TAG;
 
     match event_cache::get(redis, redis_tag, query.to_owned()).await {
         Ok(results) => Ok(results),
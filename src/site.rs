@@ -0,0 +1,145 @@
+use anyhow::{ensure, Context};
+use compact_str::{CompactString, ToCompactString};
+
+use crate::config::Config;
+
+/// Page of the site listed in the sitemap besides the episode pages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitemapPage {
+    pub path: CompactString,
+    pub change_freq: Option<CompactString>,
+    pub priority: Option<CompactString>,
+}
+
+impl SitemapPage {
+    /// Values the sitemap protocol allows for `<changefreq>`.
+    const CHANGE_FREQS: [&'static str; 7] = [
+        "always", "hourly", "daily", "weekly", "monthly", "yearly", "never",
+    ];
+
+    /// Parses a `path[:changefreq[:priority]]` entry, e.g. `/episodes:weekly:0.9`.
+    fn parse(entry: &str) -> anyhow::Result<Self> {
+        let mut parts = entry.trim().split(':').map(str::trim);
+
+        let path = parts.next().unwrap_or_default();
+        ensure!(path.starts_with('/'), "path '{path}' must start with '/'");
+
+        let mut next = || parts.next().filter(|part| !part.is_empty());
+
+        let change_freq = next()
+            .map(|freq| {
+                ensure!(
+                    Self::CHANGE_FREQS.contains(&freq),
+                    "changefreq '{freq}' must be one of {}",
+                    Self::CHANGE_FREQS.join(", ")
+                );
+                Ok(freq.to_compact_string())
+            })
+            .transpose()?;
+
+        let priority = next()
+            .map(|priority| {
+                ensure!(
+                    priority
+                        .parse::<f32>()
+                        .is_ok_and(|p| (0.0..=1.0).contains(&p)),
+                    "priority '{priority}' must be between 0.0 and 1.0"
+                );
+                Ok(priority.to_compact_string())
+            })
+            .transpose()?;
+
+        ensure!(parts.next().is_none(), "too many ':' separated parts");
+
+        Ok(Self {
+            path: path.to_compact_string(),
+            change_freq,
+            priority,
+        })
+    }
+}
+
+/// Public address of the site and the pages it serves, for links in the sitemap and feeds.
+#[derive(Debug)]
+pub struct Site {
+    base_url: CompactString,
+    pages: Vec<SitemapPage>,
+}
+
+impl Site {
+    pub fn new(base_url: &str, pages: Vec<SitemapPage>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_compact_string(),
+            pages,
+        }
+    }
+
+    /// Fails on malformed `SITEMAP_PAGES` entries rather than leaving pages out of the sitemap.
+    /// Blank entries, e.g. from a trailing comma, are skipped.
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        let pages = config
+            .sitemap_pages
+            .iter()
+            .filter(|entry| !entry.trim().is_empty())
+            .map(|entry| {
+                SitemapPage::parse(entry)
+                    .with_context(|| format!("Invalid SITEMAP_PAGES entry '{entry}'"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self::new(&config.site_url, pages))
+    }
+
+    /// Absolute URL of a site path, e.g. `/watch/481`.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+
+    pub fn pages(&self) -> &[SitemapPage] {
+        &self.pages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sitemap_pages() {
+        assert_eq!(
+            SitemapPage::parse(" /episodes:weekly:0.9 ").unwrap(),
+            SitemapPage {
+                path: "/episodes".into(),
+                change_freq: Some("weekly".into()),
+                priority: Some("0.9".into()),
+            }
+        );
+
+        let bare = SitemapPage::parse("/about").unwrap();
+        assert_eq!(bare.change_freq, None);
+        assert_eq!(bare.priority, None);
+
+        assert_eq!(SitemapPage::parse("/about::0.5").unwrap().change_freq, None);
+
+        assert!(SitemapPage::parse("/about::2").is_err());
+        assert!(SitemapPage::parse("/about:weakly").is_err());
+        assert!(SitemapPage::parse("/about:weekly:high").is_err());
+        assert!(SitemapPage::parse("/about:weekly:0.5:extra").is_err());
+        assert!(SitemapPage::parse("about:weekly").is_err());
+        assert!(SitemapPage::parse("").is_err());
+    }
+
+    #[test]
+    fn site_urls() {
+        let site = Site {
+            base_url: "https://www.pkaindextest.com".into(),
+            pages: Vec::new(),
+        };
+
+        assert_eq!(site.url("/"), "https://www.pkaindextest.com/");
+        assert_eq!(
+            site.url("/watch/481"),
+            "https://www.pkaindextest.com/watch/481"
+        );
+    }
+}
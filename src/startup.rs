@@ -3,14 +3,20 @@ use std::sync::Arc;
 use anyhow::Context;
 use axum::http::{header, Method};
 use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::info;
 
 use crate::app_state::AppState;
+use crate::auth::AuthTokens;
 use crate::conduit::sqlite::pka_event;
 use crate::config::Config;
+use crate::models::show::DEFAULT_SHOW;
 use crate::redis_db::RedisDb;
+use crate::site::Site;
+use crate::updater::source::YoutubeSource;
+use crate::workers::drift::youtube_drift;
 use crate::workers::events::update_events;
 use crate::workers::new_episode::latest_episode;
-use crate::{db, Repo, PKA_EVENTS_INDEX, YT_API_KEY};
+use crate::{db, Repo, PKA_EVENTS_INDEX};
 
 pub struct InitializedApp {
     pub app_state: AppState,
@@ -22,24 +28,61 @@ pub async fn initialize(config: &Config) -> anyhow::Result<InitializedApp> {
 
     let db_pool: Arc<Repo> = Arc::new(db::create_pool(&config.database_url).await?);
 
-    let all_events = pka_event::all(db_pool.as_ref())
+    prepare_schema(config, &db_pool).await?;
+
+    let all_events = pka_event::for_show(db_pool.as_ref(), DEFAULT_SHOW)
         .await
         .context("Failed to prime event index")?;
 
     *PKA_EVENTS_INDEX.write().await = all_events.into_boxed_slice();
-    YT_API_KEY.set(config.yt_api_key.clone()).await;
+
+    let youtube: Arc<YoutubeSource> = Arc::new(YoutubeSource::from_config(config, &db_pool)?);
 
     let worker_state = || db_pool.clone();
 
-    tokio::task::spawn(latest_episode(worker_state()));
+    tokio::task::spawn(latest_episode(
+        worker_state(),
+        redis_client.clone(),
+        youtube.clone(),
+    ));
     tokio::task::spawn(update_events(worker_state()));
+    tokio::task::spawn(youtube_drift(worker_state(), youtube.clone()));
+
+    let auth = Arc::new(AuthTokens::from_config(config));
 
-    let app_state = AppState::new(db_pool.clone(), redis_client.clone());
+    let site = Arc::new(Site::from_config(config)?);
+
+    let app_state = AppState::new(
+        db_pool.clone(),
+        redis_client.clone(),
+        auth,
+        youtube,
+        site,
+        config.trusted_proxies.as_slice().into(),
+    );
     let cors = CorsLayer::new()
-        .allow_methods([Method::GET, Method::POST])
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
         .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE])
         .allow_origin(AllowOrigin::predicate(|_, _| true))
         .allow_credentials(true);
 
     Ok(InitializedApp { app_state, cors })
 }
+
+async fn prepare_schema(config: &Config, db: &Repo) -> anyhow::Result<()> {
+    let version = db::schema_version();
+
+    if config.run_migrations {
+        let applied = db::migrate(db).await?;
+
+        if applied > 0 {
+            info!(applied, version, "Applied database migrations");
+        } else {
+            info!(version, "Database schema is up to date");
+        }
+    } else {
+        db::ensure_up_to_date(db).await?;
+    }
+
+    Ok(())
+}
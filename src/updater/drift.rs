@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use chrono::Utc;
+use compact_str::{format_compact, CompactString, ToCompactString};
+use tracing::info;
+
+use crate::conduit::sqlite::{pka_episode, pka_event, pka_youtube_details, youtube_drift};
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::pka_event::PkaEvent;
+use crate::models::pka_youtube_details::PkaYoutubeDetails;
+use crate::models::youtube_drift::{DriftKind, YoutubeDrift};
+use crate::updater::pka::extract_pka_episode_events;
+use crate::updater::reextract::diff_events;
+use crate::updater::youtube_api::models::videos::VideosItem;
+use crate::updater::youtube_api::YoutubeApi;
+use crate::Repo;
+
+/// Compares every stored video against YouTube and records what has changed.
+/// Returns the number of drifts currently outstanding.
+pub async fn detect_drift(state: &Repo, yt_api: &YoutubeApi) -> anyhow::Result<usize> {
+    let stored = pka_youtube_details::all(state)
+        .await
+        .context("Failed to load youtube details")?;
+
+    let upload_dates = pka_episode::all(state)
+        .await
+        .context("Failed to load episodes")?
+        .into_iter()
+        .map(|e| ((e.show_slug.clone(), e.number()), e.upload_date))
+        .collect::<HashMap<_, _>>();
+
+    let mut events_by_episode: HashMap<(CompactString, EpisodeNumber), Vec<PkaEvent>> =
+        HashMap::new();
+    for event in pka_event::all(state)
+        .await
+        .context("Failed to load events")?
+    {
+        events_by_episode
+            .entry((event.show_slug.clone(), event.episode_number()))
+            .or_default()
+            .push(event);
+    }
+
+    let checked_at = Utc::now().timestamp();
+
+    let mut drifts = Vec::new();
+
+    for chunk in stored.chunks(YoutubeApi::MAX_IDS_PER_REQUEST) {
+        let ids = chunk
+            .iter()
+            .map(|d| d.video_id.as_str())
+            .collect::<Vec<_>>();
+
+        let items = yt_api.get_videos(&ids).await?;
+
+        for details in chunk {
+            let key = (details.show_slug.clone(), details.episode_number);
+
+            let current = items.iter().find(|item| item.id == details.video_id);
+            let events = events_by_episode
+                .get(&key)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let upload_date = upload_dates.get(&key).copied().unwrap_or_default();
+
+            drifts.extend(compare(details, events, upload_date, current, checked_at));
+        }
+    }
+
+    let outstanding = drifts.len();
+
+    let mut tx = state.begin().await?;
+
+    for drift in drifts {
+        youtube_drift::upsert(&mut *tx, drift).await?;
+    }
+
+    let resolved = youtube_drift::delete_resolved(&mut *tx, checked_at).await?;
+
+    tx.commit().await?;
+
+    info!(
+        "Checked {} videos for drift: {outstanding} outstanding, {resolved} resolved.",
+        stored.len()
+    );
+
+    Ok(outstanding)
+}
+
+fn compare(
+    details: &PkaYoutubeDetails,
+    events: &[PkaEvent],
+    upload_date: i64,
+    current: Option<&VideosItem>,
+    checked_at: i64,
+) -> Vec<YoutubeDrift> {
+    let drift = |kind, stored_value: Option<CompactString>, current_value| {
+        YoutubeDrift::new(
+            details.video_id.clone(),
+            details.show_slug.clone(),
+            details.episode_number,
+            kind,
+            stored_value,
+            current_value,
+            checked_at,
+        )
+    };
+
+    let Some(current) = current else {
+        return vec![drift(
+            DriftKind::Unavailable,
+            Some(details.title.clone()),
+            None,
+        )];
+    };
+
+    let mut drifts = Vec::new();
+
+    if current.snippet.title != details.title {
+        drifts.push(drift(
+            DriftKind::Title,
+            Some(details.title.clone()),
+            Some(current.snippet.title.clone()),
+        ));
+    }
+
+    if current.content_details.duration != details.length_seconds {
+        drifts.push(drift(
+            DriftKind::Duration,
+            Some(details.length_seconds.to_compact_string()),
+            Some(current.content_details.duration.to_compact_string()),
+        ));
+    }
+
+    let stored_summary = format_compact!("{} events", events.len());
+
+    match extract_pka_episode_events(
+        &details.show_slug,
+        details.episode_number,
+        &current.snippet.description,
+        &current.content_details.duration,
+        &upload_date,
+    ) {
+        Ok(extracted) => {
+            let extracted_len = extracted.events.len();
+            let diff = diff_events(details.episode_number, events.to_vec(), extracted.events);
+
+            if !diff.is_empty() {
+                drifts.push(drift(
+                    DriftKind::Description,
+                    Some(stored_summary),
+                    Some(format_compact!(
+                        "{extracted_len} events ({} added, {} removed, {} changed)",
+                        diff.added.len(),
+                        diff.removed.len(),
+                        diff.changed.len()
+                    )),
+                ));
+            }
+        }
+        Err(_) if !events.is_empty() => {
+            drifts.push(drift(
+                DriftKind::Description,
+                Some(stored_summary),
+                Some("no timeline".into()),
+            ));
+        }
+        Err(_) => {}
+    }
+
+    drifts
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::show::DEFAULT_SHOW;
+    use crate::updater::youtube_api::models::videos::{ContentDetails, Snippet};
+
+    use super::*;
+
+    fn details() -> PkaYoutubeDetails {
+        PkaYoutubeDetails::new(
+            "abc".into(),
+            DEFAULT_SHOW.into(),
+            EpisodeNumber::new(481),
+            "PKA 481".into(),
+            600,
+        )
+    }
+
+    fn video(title: &str, description: &str, duration: i32) -> VideosItem {
+        VideosItem {
+            id: "abc".into(),
+            snippet: Snippet {
+                title: title.into(),
+                description: description.into(),
+                published_at: "2020-03-07T00:00:00Z".into(),
+            },
+            content_details: ContentDetails { duration },
+        }
+    }
+
+    fn stored_events() -> Vec<PkaEvent> {
+        extract_pka_episode_events(
+            DEFAULT_SHOW,
+            EpisodeNumber::new(481),
+            "0:00 - Intro\n5:00 - Boat",
+            &600,
+            &0,
+        )
+        .unwrap()
+        .events
+    }
+
+    #[test]
+    fn no_drift() {
+        let current = video("PKA 481", "0:00 - Intro\n5:00 - Boat", 600);
+
+        assert!(compare(&details(), &stored_events(), 0, Some(&current), 1).is_empty());
+    }
+
+    #[test]
+    fn unavailable() {
+        let drifts = compare(&details(), &stored_events(), 0, None, 1);
+
+        assert_eq!(drifts.len(), 1);
+        assert_eq!(drifts[0].kind, DriftKind::Unavailable);
+    }
+
+    #[test]
+    fn changed_fields() {
+        let current = video("PKA 481 - Boats", "0:00 - Intro\n5:00 - Yacht", 610);
+
+        let drifts = compare(&details(), &stored_events(), 0, Some(&current), 1);
+        let kinds = drifts.iter().map(|d| d.kind).collect::<Vec<_>>();
+
+        assert_eq!(
+            kinds,
+            vec![
+                DriftKind::Title,
+                DriftKind::Duration,
+                DriftKind::Description
+            ]
+        );
+        assert_eq!(drifts[1].stored_value.as_deref(), Some("600"));
+        assert_eq!(drifts[1].current_value.as_deref(), Some("610"));
+    }
+
+    #[test]
+    fn timeline_removed() {
+        let current = video("PKA 481", "No timestamps here", 600);
+
+        let drifts = compare(&details(), &stored_events(), 0, Some(&current), 1);
+
+        assert_eq!(drifts.len(), 1);
+        assert_eq!(drifts[0].current_value.as_deref(), Some("no timeline"));
+    }
+}
@@ -0,0 +1,87 @@
+//! Exports in the formats read by [`crate::updater::import`], so an export can be edited
+//! and imported again.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Context;
+use chrono::{DateTime, SecondsFormat};
+
+use crate::conduit::sqlite::{pka_episode, pka_event};
+use crate::models::updater::{EpisodeFromFile, EpisodesFileRoot};
+use crate::updater::timeline::format_timestamp;
+use crate::Repo;
+
+pub async fn episode_list(repo: &Repo, show: &str) -> anyhow::Result<EpisodesFileRoot> {
+    let episodes = pka_episode::for_show(repo, show)
+        .await?
+        .into_iter()
+        .rev()
+        .map(|e| EpisodeFromFile {
+            identifier: e.name.into(),
+            number: e.number,
+            date_time: format_upload_date(e.upload_date),
+            you_tube: e.youtube_link.into(),
+        })
+        .collect();
+
+    Ok(EpisodesFileRoot { episodes })
+}
+
+/// Writes one `<name>.txt` file per episode with a timeline, e.g. `PKA 481.txt`. Returns the
+/// number of files written.
+pub async fn timeline_files(repo: &Repo, show: &str, dir: &Path) -> anyhow::Result<usize> {
+    let names = pka_episode::for_show(repo, show)
+        .await?
+        .into_iter()
+        .map(|e| (e.number, e.name))
+        .collect::<HashMap<_, _>>();
+
+    let mut events = pka_event::for_show(repo, show).await?;
+    events.sort_by_key(|e| (e.episode_number(), e.timestamp()));
+
+    tokio::fs::create_dir_all(dir)
+        .await
+        .with_context(|| format!("Failed to create '{}'", dir.display()))?;
+
+    let mut written = 0;
+
+    for chunk in events.chunk_by(|a, b| a.episode_number() == b.episode_number()) {
+        let number = chunk[0].episode_number();
+
+        let text = chunk
+            .iter()
+            .map(|e| {
+                format!(
+                    "{} - {}\n",
+                    format_timestamp(e.timestamp()),
+                    e.description()
+                )
+            })
+            .collect::<String>();
+
+        let name = names
+            .get(&number)
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| number.to_string());
+
+        let path = dir.join(format!("{name}.txt"));
+
+        tokio::fs::write(&path, text)
+            .await
+            .with_context(|| format!("Failed to write '{}'", path.display()))?;
+
+        written += 1;
+    }
+
+    Ok(written)
+}
+
+/// Midnight uploads keep the `YYYY-MM-DD` form of `pka_episode_list.json`.
+fn format_upload_date(upload_date: i64) -> String {
+    match DateTime::from_timestamp(upload_date, 0) {
+        Some(date) if upload_date % 86_400 == 0 => date.format("%Y-%m-%d").to_string(),
+        Some(date) => date.to_rfc3339_opts(SecondsFormat::Secs, true),
+        None => upload_date.to_string(),
+    }
+}
@@ -0,0 +1,690 @@
+//! Offline import of episode lists and timelines from files, e.g. `pka_episode_list.json` and
+//! a folder of `PKA 481.txt` descriptions or painkilleralready.info JSON dumps. Imports are
+//! diffed against what's stored and only the differences are written, so re-running one is
+//! harmless.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, ensure, Context};
+use chrono::{DateTime, NaiveDate};
+use compact_str::ToCompactString;
+use tracing::info;
+
+use crate::conduit::sqlite::{pka_episode, pka_event, pka_youtube_details};
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::import::{
+    CuratedRowError, CuratedTimelineReport, CuratedTimelineRow, EpisodeImportReport,
+    TimelineImportFailure, TimelineImportReport,
+};
+use crate::models::pka_episode::PkaEpisode;
+use crate::models::timeline_diff::TimelineDiff;
+use crate::models::timeline_warning::TimelineWarning;
+use crate::models::updater::{EpisodesFileRoot, PkaInfoRoot};
+use crate::updater::pka::{events_from_timeline, extract_pka_episode_events, ExtractedTimeline};
+use crate::updater::reextract::{diff_events, replace_events, replace_events_in};
+use crate::updater::timeline::{normalize_timestamp, Timeline, TimelineEntry};
+use crate::Repo;
+
+pub fn parse_episode_list(show: &str, json: &str) -> anyhow::Result<Vec<PkaEpisode>> {
+    let root = serde_json::from_str::<EpisodesFileRoot>(json).context("Invalid episode list")?;
+
+    root.episodes
+        .into_iter()
+        .map(|ep| {
+            let upload_date = parse_upload_date(&ep.date_time)
+                .with_context(|| format!("Invalid date '{}' for {}", ep.date_time, ep.number))?;
+
+            Ok(PkaEpisode::new(
+                show.to_compact_string(),
+                ep.number,
+                ep.identifier.into(),
+                ep.you_tube.into(),
+                upload_date,
+            ))
+        })
+        .collect()
+}
+
+fn parse_upload_date(raw: &str) -> Option<i64> {
+    match NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+        Ok(date) => Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp()),
+        Err(_) => DateTime::parse_from_rfc3339(raw)
+            .ok()
+            .map(|d| d.timestamp()),
+    }
+}
+
+/// Adds new episodes and overwrites stored ones whose name, video or upload day differ.
+/// Lists only carry the day, so a stored upload time on that day is kept.
+pub async fn import_episodes(
+    repo: &Repo,
+    episodes: Vec<PkaEpisode>,
+    dry_run: bool,
+) -> anyhow::Result<EpisodeImportReport> {
+    let mut report = EpisodeImportReport::default();
+    let mut changed = Vec::new();
+
+    for mut episode in episodes {
+        match pka_episode::find(repo, &episode.show_slug, episode.number()).await {
+            Ok(stored) if upload_day(stored.upload_date) == upload_day(episode.upload_date) => {
+                if stored.name == episode.name && stored.youtube_link == episode.youtube_link {
+                    report.unchanged += 1;
+                    continue;
+                }
+
+                episode.upload_date = stored.upload_date;
+                report.updated.push(episode.number());
+            }
+            Ok(_) => report.updated.push(episode.number()),
+            Err(sqlx::Error::RowNotFound) => report.added.push(episode.number()),
+            Err(e) => return Err(e.into()),
+        }
+
+        changed.push(episode);
+    }
+
+    if !dry_run && !report.is_empty() {
+        let mut tx = repo.begin().await?;
+
+        for episode in changed {
+            pka_episode::upsert(&mut *tx, episode).await?;
+        }
+
+        tx.commit().await?;
+
+        report.applied = true;
+
+        info!(
+            "Imported episodes: {} added, {} updated.",
+            report.added.len(),
+            report.updated.len()
+        );
+    }
+
+    Ok(report)
+}
+
+fn upload_day(upload_date: i64) -> Option<NaiveDate> {
+    DateTime::from_timestamp(upload_date, 0).map(|d| d.date_naive())
+}
+
+/// Imports every `.txt` and `.json` file, looking one level into directories. Files that
+/// fail are reported and don't stop the rest.
+pub async fn import_timelines(
+    repo: &Repo,
+    show: &str,
+    paths: &[PathBuf],
+    dry_run: bool,
+) -> anyhow::Result<TimelineImportReport> {
+    let mut report = TimelineImportReport::default();
+
+    for path in timeline_files(paths).await? {
+        let res = async {
+            let number = episode_number_from_path(&path)?;
+            let contents = tokio::fs::read_to_string(&path).await?;
+            let text = timeline_text(&path, &contents)?;
+
+            import_timeline(repo, show, number, &text, dry_run).await
+        }
+        .await;
+
+        match res {
+            Ok(diff) => report.diffs.push(diff),
+            Err(e) => report.failed.push(TimelineImportFailure {
+                file: path.display().to_compact_string(),
+                error: format!("{e:#}").into(),
+            }),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Extracts the timeline from a description and replaces the stored one when they differ.
+pub async fn import_timeline(
+    repo: &Repo,
+    show: &str,
+    number: EpisodeNumber,
+    text: &str,
+    dry_run: bool,
+) -> anyhow::Result<TimelineDiff> {
+    let episode = pka_episode::find(repo, show, number)
+        .await
+        .with_context(|| format!("Failed to find {show} episode {number}"))?;
+
+    // Without a stored video length the last event gets the minimum length.
+    let length_seconds = pka_youtube_details::find_length(repo, show, number)
+        .await?
+        .unwrap_or_default();
+
+    let ExtractedTimeline { events, warnings } =
+        extract_pka_episode_events(show, number, text, &length_seconds, &episode.upload_date)?;
+
+    let existing = pka_event::for_episode(repo, show, number).await?;
+
+    let mut diff = diff_events(number, existing, events.clone());
+    diff.warnings = warnings;
+
+    if !dry_run && !diff.is_empty() {
+        replace_events(repo, show, number, events).await?;
+
+        diff.applied = true;
+    }
+
+    Ok(diff)
+}
+
+async fn timeline_files(paths: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+    let is_timeline = |path: &Path| {
+        path.extension()
+            .is_some_and(|ext| ext == "txt" || ext == "json")
+    };
+
+    let mut files = Vec::new();
+
+    for path in paths {
+        if !tokio::fs::metadata(path)
+            .await
+            .with_context(|| format!("Failed to read '{}'", path.display()))?
+            .is_dir()
+        {
+            files.push(path.clone());
+            continue;
+        }
+
+        let mut entries = tokio::fs::read_dir(path).await?;
+        let mut dir_files = Vec::new();
+
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await?.is_file() && is_timeline(&entry.path()) {
+                dir_files.push(entry.path());
+            }
+        }
+
+        dir_files.sort();
+        files.extend(dir_files);
+    }
+
+    Ok(files)
+}
+
+/// Reads the episode number from names such as `PKA 481.txt`, `481.json` or `PKA 481.5.txt`.
+fn episode_number_from_path(path: &Path) -> anyhow::Result<EpisodeNumber> {
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .context("File name isn't valid UTF-8")?;
+
+    stem.trim_start_matches(|c: char| !c.is_ascii_digit())
+        .parse()
+        .with_context(|| format!("No episode number in file name '{stem}'"))
+}
+
+/// JSON dumps are turned into `H:MM:SS - Description` lines for the timeline parser.
+fn timeline_text(path: &Path, contents: &str) -> anyhow::Result<String> {
+    if path.extension().is_none_or(|ext| ext != "json") {
+        return Ok(contents.to_owned());
+    }
+
+    let root = serde_json::from_str::<PkaInfoRoot>(contents).context("Invalid timeline dump")?;
+
+    if root.timeline.timestamps.is_empty() {
+        bail!("Timeline dump has no timestamps");
+    }
+
+    Ok(root
+        .timeline
+        .timestamps
+        .iter()
+        .map(|t| format!("{} - {}\n", t.hms, t.value))
+        .collect())
+}
+
+/// Reads curated rows from a CSV with `episode`, `timestamp` and `description` columns in any
+/// order. Other columns, such as notes, are ignored.
+pub fn parse_curated_csv(text: &str) -> anyhow::Result<Vec<CuratedTimelineRow>> {
+    let mut records = csv_records(text)?.into_iter();
+
+    let header = records.next().context("CSV is empty")?;
+
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name))
+            .with_context(|| format!("CSV has no '{name}' column"))
+    };
+
+    let (episode, timestamp, description) = (
+        column("episode")?,
+        column("timestamp")?,
+        column("description")?,
+    );
+
+    records
+        .enumerate()
+        .map(|(i, record)| {
+            let field = |column: usize| record.get(column).map_or("", |f| f.trim());
+
+            let number = field(episode);
+
+            Ok(CuratedTimelineRow {
+                episode: number
+                    .parse()
+                    .with_context(|| format!("Row {}: invalid episode number '{number}'", i + 1))?,
+                timestamp: field(timestamp).into(),
+                description: field(description).into(),
+            })
+        })
+        .collect()
+}
+
+/// Splits RFC 4180 CSV into records. Quoted fields may contain commas, doubled quotes and line
+/// breaks, and trailing blank rows left by spreadsheets are dropped.
+fn csv_records(text: &str) -> anyhow::Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n' | '\r') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (_, c) => field.push(c),
+        }
+    }
+
+    ensure!(!quoted, "CSV has an unterminated quoted field");
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    while records
+        .last()
+        .is_some_and(|r| r.iter().all(|f| f.trim().is_empty()))
+    {
+        records.pop();
+    }
+
+    Ok(records)
+}
+
+/// Validates curated rows like a timeline extracted from a description and replaces the
+/// timelines of every episode they cover in one transaction. Nothing is written while any row
+/// has an error.
+pub async fn import_curated_timelines(
+    repo: &Repo,
+    show: &str,
+    rows: Vec<CuratedTimelineRow>,
+    dry_run: bool,
+) -> anyhow::Result<CuratedTimelineReport> {
+    let mut report = CuratedTimelineReport::default();
+    let mut replacements = Vec::new();
+
+    let mut episodes = BTreeMap::<EpisodeNumber, Vec<(usize, CuratedTimelineRow)>>::new();
+
+    for (i, row) in rows.into_iter().enumerate() {
+        episodes.entry(row.episode).or_default().push((i + 1, row));
+    }
+
+    let error = |row: usize, error: String| CuratedRowError {
+        row,
+        error: error.into(),
+    };
+
+    for (number, rows) in episodes {
+        let first_row = rows[0].0;
+
+        let episode = match pka_episode::find(repo, show, number).await {
+            Ok(episode) => episode,
+            Err(sqlx::Error::RowNotFound) => {
+                let message = format!("Couldn't find {show} episode {number}");
+                report.errors.push(error(first_row, message));
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut timeline = Timeline::default();
+        let mut starts = Vec::new();
+
+        for (
+            row,
+            CuratedTimelineRow {
+                timestamp,
+                description,
+                ..
+            },
+        ) in rows
+        {
+            if description.trim().is_empty() {
+                report
+                    .errors
+                    .push(error(row, "Description is empty".to_owned()));
+                continue;
+            }
+
+            match normalize_timestamp(timestamp.trim()) {
+                Ok(start) if start >= 0 => {
+                    starts.push((row, start));
+                    timeline.entries.push(TimelineEntry {
+                        start,
+                        end: None,
+                        description: description.trim().into(),
+                        confidence: 100,
+                    });
+                }
+                Ok(_) => report
+                    .errors
+                    .push(error(row, "Timestamp cannot be negative".to_owned())),
+                Err(e) => report.errors.push(error(row, e.to_string())),
+            }
+        }
+
+        // Every row already has an error.
+        if timeline.entries.is_empty() {
+            continue;
+        }
+
+        let length_seconds = pka_youtube_details::find_length(repo, show, number)
+            .await?
+            .unwrap_or_default();
+
+        let ExtractedTimeline { events, warnings } = match events_from_timeline(
+            show,
+            number,
+            timeline,
+            &length_seconds,
+            &episode.upload_date,
+        ) {
+            Ok(extracted) => extracted,
+            Err(e) => {
+                report
+                    .errors
+                    .push(error(first_row, format!("Episode {number}: {e:#}")));
+                continue;
+            }
+        };
+
+        // Extraction drops these rows, so they'd silently go missing from a curated timeline.
+        let rejected = rejected_rows(&starts, &warnings);
+
+        if !rejected.is_empty() {
+            report.errors.extend(
+                rejected
+                    .into_iter()
+                    .map(|(row, warning)| error(row, format!("Episode {number}: {warning}"))),
+            );
+            continue;
+        }
+
+        let existing = pka_event::for_episode(repo, show, number).await?;
+
+        let mut diff = diff_events(number, existing, events.clone());
+        diff.warnings = warnings;
+
+        if !diff.is_empty() {
+            replacements.push((number, events));
+        }
+
+        report.diffs.push(diff);
+    }
+
+    report.errors.sort_by_key(|e| e.row);
+
+    if dry_run || !report.errors.is_empty() || replacements.is_empty() {
+        return Ok(report);
+    }
+
+    let mut tx = repo.begin().await?;
+
+    for (number, events) in replacements {
+        replace_events_in(&mut tx, show, number, events).await?;
+    }
+
+    tx.commit().await?;
+
+    for diff in report.diffs.iter_mut().filter(|d| !d.is_empty()) {
+        diff.applied = true;
+    }
+
+    report.applied = true;
+
+    info!(
+        "Imported curated timelines for {} episodes.",
+        report.diffs.iter().filter(|d| d.applied).count()
+    );
+
+    Ok(report)
+}
+
+/// Matches the warnings for entries that extraction dropped back to the rows they came from.
+/// The first row at a duplicated timestamp is kept; every later one is rejected.
+fn rejected_rows<'a>(
+    starts: &[(usize, i32)],
+    warnings: &'a [TimelineWarning],
+) -> Vec<(usize, &'a TimelineWarning)> {
+    let mut seen = Vec::new();
+    let mut rejected = Vec::new();
+
+    for &(row, start) in starts {
+        let warning = warnings.iter().find(|warning| match warning {
+            TimelineWarning::PastEnd { timestamp, .. } => *timestamp == start,
+            TimelineWarning::Duplicate { timestamp } => {
+                *timestamp == start && seen.contains(&start)
+            }
+            _ => false,
+        });
+
+        if let Some(warning) = warning {
+            rejected.push((row, warning));
+        }
+
+        seen.push(start);
+    }
+
+    rejected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+    use crate::models::show::DEFAULT_SHOW;
+
+    const EPISODE_LIST: &str = r#"{
+        "episodes": [
+            {"Identifier": "PKA 481", "Number": 481, "DateTime": "2020-03-07", "Date": "March 07, 2020", "YouTube": "HuxCJNLOtoU", "Timelined": false},
+            {"Identifier": "PKA 480", "Number": 480, "DateTime": "2020-02-29", "Date": "February 29, 2020", "YouTube": "ArTDpBUAFrY", "Timelined": false}
+        ]
+    }"#;
+
+    #[test]
+    fn parse_file_names_and_dumps() {
+        let number = |name: &str| episode_number_from_path(Path::new(name)).ok();
+
+        assert_eq!(number("PKA 481.txt"), Some(EpisodeNumber::new(481)));
+        assert_eq!(number("dir/481.json"), Some(EpisodeNumber::new(481)));
+        assert_eq!(
+            number("PKA 481.5.txt"),
+            Some(EpisodeNumber::with_part(481, 5).unwrap())
+        );
+        assert_eq!(number("notes.txt"), None);
+
+        let dump = r#"{"Timeline": {"Timestamps": [
+            {"HMS": "0:00:00", "Value": "Intro"},
+            {"HMS": "0:12:30", "Value": "Woody bought a boat"}
+        ]}}"#;
+
+        assert_eq!(
+            timeline_text(Path::new("481.json"), dump).unwrap(),
+            "0:00:00 - Intro\n0:12:30 - Woody bought a boat\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn imports_are_idempotent() {
+        let pool = test_pool("import_idempotent").await;
+
+        let episodes = parse_episode_list(DEFAULT_SHOW, EPISODE_LIST).unwrap();
+
+        let dry_run = import_episodes(&pool, episodes, true).await.unwrap();
+        assert_eq!(dry_run.added.len(), 2);
+        assert!(!dry_run.applied);
+        assert!(pka_episode::all(&pool).await.unwrap().is_empty());
+
+        let episodes = parse_episode_list(DEFAULT_SHOW, EPISODE_LIST).unwrap();
+        assert!(
+            import_episodes(&pool, episodes, false)
+                .await
+                .unwrap()
+                .applied
+        );
+
+        let episodes = parse_episode_list(DEFAULT_SHOW, EPISODE_LIST).unwrap();
+        let again = import_episodes(&pool, episodes, false).await.unwrap();
+        assert!(again.is_empty());
+        assert_eq!(again.unchanged, 2);
+
+        let number = EpisodeNumber::new(481);
+        let text = "0:00 - Intro\n12:30 - Woody bought a boat";
+
+        let diff = import_timeline(&pool, DEFAULT_SHOW, number, text, false)
+            .await
+            .unwrap();
+        assert_eq!(diff.added.len(), 2);
+        assert!(diff.applied);
+
+        let diff = import_timeline(&pool, DEFAULT_SHOW, number, text, false)
+            .await
+            .unwrap();
+        assert!(diff.is_empty());
+        assert_eq!(diff.unchanged, 2);
+    }
+
+    #[tokio::test]
+    async fn imports_keep_upload_time_on_the_same_day() {
+        let pool = test_pool("import_upload_time").await;
+
+        // Ingested from YouTube with the time of day the episodes went up.
+        let stored = [
+            (481, "PKA 481", "HuxCJNLOtoU", 1583607600), // 2020-03-07T19:00:00Z
+            (480, "PKA 480 - Old title", "ArTDpBUAFrY", 1583006400), // 2020-02-29T20:00:00Z
+        ];
+        for (number, name, video_id, upload_date) in stored {
+            let episode = PkaEpisode::new(
+                DEFAULT_SHOW.into(),
+                EpisodeNumber::new(number),
+                name.into(),
+                video_id.into(),
+                upload_date,
+            );
+            pka_episode::insert(&pool, episode).await.unwrap();
+        }
+
+        let episodes = parse_episode_list(DEFAULT_SHOW, EPISODE_LIST).unwrap();
+        let report = import_episodes(&pool, episodes, false).await.unwrap();
+
+        assert_eq!(report.unchanged, 1);
+        assert_eq!(report.updated, vec![EpisodeNumber::new(480)]);
+
+        let renamed = pka_episode::find(&pool, DEFAULT_SHOW, EpisodeNumber::new(480))
+            .await
+            .unwrap();
+        assert_eq!(renamed.name, "PKA 480");
+        assert_eq!(renamed.upload_date, 1583006400);
+    }
+
+    #[test]
+    fn parse_curated_csv_rows() {
+        let csv = "\u{feff}Timestamp,Episode,Description,Notes\r\n\
+                   0:00,481,Intro,\r\n\
+                   12:30,481.5,\"Woody, \"\"the boat\"\" guy\nreturns\",checked\r\n\
+                   ,,,\r\n";
+
+        let rows = parse_curated_csv(csv).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].episode, EpisodeNumber::new(481));
+        assert_eq!(rows[0].timestamp, "0:00");
+        assert_eq!(rows[1].episode, EpisodeNumber::with_part(481, 5).unwrap());
+        assert_eq!(rows[1].description, "Woody, \"the boat\" guy\nreturns");
+
+        assert!(parse_curated_csv("episode,description\n481,Intro").is_err());
+        assert!(parse_curated_csv("episode,timestamp,description\nabc,0:00,Intro").is_err());
+        assert!(parse_curated_csv("episode,timestamp,description\n481,0:00,\"Intro").is_err());
+    }
+
+    #[tokio::test]
+    async fn curated_imports_are_atomic() {
+        let pool = test_pool("import_curated").await;
+
+        let episodes = parse_episode_list(DEFAULT_SHOW, EPISODE_LIST).unwrap();
+        import_episodes(&pool, episodes, false).await.unwrap();
+
+        let row = |episode: u32, timestamp: &str, description: &str| CuratedTimelineRow {
+            episode: EpisodeNumber::new(episode),
+            timestamp: timestamp.into(),
+            description: description.into(),
+        };
+
+        let mut rows = vec![
+            row(481, "0:00", "Intro"),
+            row(481, "12:30", "Woody bought a boat"),
+            row(480, "1:00", "Taylor's new car"),
+            row(480, "2:80", "Bad timestamp"),
+            row(479, "0:00", "Missing episode"),
+        ];
+
+        let report = import_curated_timelines(&pool, DEFAULT_SHOW, rows.clone(), false)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            report.errors.iter().map(|e| e.row).collect::<Vec<_>>(),
+            [4, 5]
+        );
+        assert!(!report.applied);
+        assert!(pka_event::all(&pool).await.unwrap().is_empty());
+
+        let duplicate = vec![row(481, "0:00", "Intro"), row(481, "0:00", "Intro again")];
+
+        let report = import_curated_timelines(&pool, DEFAULT_SHOW, duplicate, false)
+            .await
+            .unwrap();
+
+        assert_eq!(report.errors.iter().map(|e| e.row).collect::<Vec<_>>(), [2]);
+        assert!(!report.applied);
+        assert!(pka_event::all(&pool).await.unwrap().is_empty());
+
+        rows.truncate(3);
+
+        let report = import_curated_timelines(&pool, DEFAULT_SHOW, rows, false)
+            .await
+            .unwrap();
+
+        assert!(report.errors.is_empty());
+        assert!(report.applied);
+
+        let events = pka_event::for_episode(&pool, DEFAULT_SHOW, EpisodeNumber::new(481))
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].length_seconds(), 750);
+    }
+}
@@ -1,2 +1,9 @@
+pub mod drift;
+pub mod export;
+pub mod import;
 pub mod pka;
+pub mod reextract;
+pub mod source;
+pub mod timeline;
 pub mod youtube_api;
+pub mod youtube_feed;
@@ -1,83 +1,134 @@
-use std::sync::LazyLock;
+use std::collections::HashSet;
 
 use anyhow::{bail, Context};
+use chrono::Utc;
 use compact_str::{CompactString, ToCompactString};
-use regex::{Regex, RegexBuilder};
+use regex::Regex;
 use tracing::{error, info, warn};
 
-use crate::conduit::sqlite::{pka_episode, pka_event, pka_youtube_details};
+use crate::conduit::sqlite::{ingestion_log, pka_episode, pka_event, pka_youtube_details, show};
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::ingestion_log::{IngestionOutcome, NewIngestionLogEntry};
 use crate::models::pka_episode::PkaEpisode;
 use crate::models::pka_event::PkaEvent;
 use crate::models::pka_youtube_details::PkaYoutubeDetails;
+use crate::models::show::Show;
+use crate::models::timeline_warning::{TimelineWarning, TimelineWarnings};
+use crate::updater::source::VideoSource;
+use crate::updater::timeline::{parse_timeline, Timeline};
 use crate::updater::youtube_api::models::playlist_items::PlaylistItem;
-use crate::updater::youtube_api::YoutubeApi;
 use crate::Repo;
 
-const WOODY_YOUTUBE_UPLOAD_PLAYLIST_ID: &str = "UUIPVJoHb_A5S3kcv3TJlyEg";
+/// Upper bound on playlist items fetched per check, in case no known video is found.
+const MAX_UPLOADS_TO_SCAN: usize = 500;
 
-static TITLE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    RegexBuilder::new(r"PKA\s*(?P<number>\d{3,})")
-        .case_insensitive(true)
-        .build()
-        .expect("Failed to create TITLE_REGEX")
-});
+/// Reads the episode number from a title using a show's title regex.
+pub fn episode_number_from_title(title_regex: &Regex, title: &str) -> Option<u32> {
+    title_regex
+        .captures(title)
+        .and_then(|capture| capture.name("number"))
+        .and_then(|number| number.as_str().parse::<u32>().ok())
+}
 
-pub async fn load_new_episodes(state: &Repo) -> anyhow::Result<()> {
-    info!("Checking playlist for missing episodes.");
+/// Checks every enabled show for missing episodes. A failing show doesn't stop the others
+/// from being checked, the first error is returned once all of them have been.
+pub async fn load_new_episodes(state: &Repo, videos: &impl VideoSource) -> anyhow::Result<usize> {
+    let shows = show::all_enabled(state)
+        .await
+        .context("Failed to load shows")?;
+
+    let mut episodes_added = 0;
+    let mut first_error = None;
+
+    for show in shows {
+        match load_new_show_episodes(state, videos, &show).await {
+            Ok(added) => episodes_added += added,
+            Err(e) => {
+                let e = e.context(format!("Failed to load new {} episodes", show.name));
+                error!("{e:#}");
+                first_error.get_or_insert(e);
+            }
+        }
+    }
 
-    let stored_episode_number = pka_episode::latest(state).await?.floor() as usize;
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(episodes_added),
+    }
+}
 
-    let yt_api = YoutubeApi::new()?;
+async fn load_new_show_episodes(
+    state: &Repo,
+    videos: &impl VideoSource,
+    show: &Show,
+) -> anyhow::Result<usize> {
+    info!("Checking {} playlist for missing episodes.", show.name);
+
+    let title_regex = show
+        .compile_title_regex()
+        .with_context(|| format!("Invalid title regex for show '{}'", show.slug))?;
+
+    let stored_episode_number = match pka_episode::latest(state, &show.slug).await {
+        Ok(number) => number.number(),
+        Err(sqlx::Error::RowNotFound) => u32::try_from(show.first_episode_number.max(1) - 1)
+            .with_context(|| format!("Invalid first episode number for show '{}'", show.slug))?,
+        Err(e) => return Err(e.into()),
+    };
 
-    let latest_episode_title = yt_api
-        .get_latest_uploads(1, WOODY_YOUTUBE_UPLOAD_PLAYLIST_ID)
+    let latest_episode_title = videos
+        .uploads_until_known(1, &show.playlist_id, |_| false)
         .await?
-        .items
         .into_iter()
         .map(|item| item.snippet.title)
         .next()
         .context("Failed to find latest episode title")?;
 
-    let latest_episode_number = TITLE_REGEX
-        .captures(&latest_episode_title)
-        .and_then(|capture| capture.name("number"))
-        .context("Failed to find latest episode number from title")?
-        .as_str()
-        .parse::<f32>()
-        .context("Failed to parse latest episode number to f32")?
-        as usize;
+    let latest_episode_number = episode_number_from_title(&title_regex, &latest_episode_title)
+        .context("Failed to find latest episode number from title")?;
 
     let episodes_missing = (latest_episode_number).saturating_sub(stored_episode_number);
 
+    let mut episodes_added = 0;
+
     if episodes_missing > 0 {
         info!("Fetching {episodes_missing} missing episodes");
 
-        let missing_uploads = yt_api
-            .get_latest_uploads(episodes_missing, WOODY_YOUTUBE_UPLOAD_PLAYLIST_ID)
+        let known_video_ids = pka_episode::all_video_ids(state)
             .await?
-            .items;
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        // The uploads playlist also holds non-episode videos, so page back until a stored
+        // episode is reached rather than fetching only as many items as are missing.
+        let missing_uploads = videos
+            .uploads_until_known(MAX_UPLOADS_TO_SCAN, &show.playlist_id, |video_id| {
+                known_video_ids.contains(video_id)
+            })
+            .await?;
 
         for episode_number in (stored_episode_number + 1)..=latest_episode_number {
-            let episode_name = format!("PKA {}", episode_number).to_compact_string();
+            let episode_name =
+                format!("{} {}", show.episode_prefix, episode_number).to_compact_string();
 
             match missing_uploads.iter().find(|ep| {
-                TITLE_REGEX
-                    .captures(&ep.snippet.title)
-                    .and_then(|c| c.name("number"))
-                    .and_then(|n| n.as_str().parse::<usize>().ok())
-                    .is_some_and(|n| n == episode_number)
+                episode_number_from_title(&title_regex, &ep.snippet.title) == Some(episode_number)
             }) {
                 Some(matching_episode) => {
                     info!("Found {episode_name} in playlist. Attempting to extract video details.");
 
-                    extract_then_save_events(
+                    // Stop at the first failure, storing a later episode would advance the
+                    // latest number past this one and it would never be retried.
+                    ingest_episode(
                         state,
-                        &yt_api,
+                        videos,
+                        &show.slug,
                         episode_name,
-                        episode_number as f32,
+                        EpisodeNumber::new(episode_number),
                         matching_episode,
                     )
                     .await?;
+
+                    episodes_added += 1;
                 }
                 None => {
                     warn!("Could not find {episode_name} in playlist.");
@@ -86,198 +137,521 @@ pub async fn load_new_episodes(state: &Repo) -> anyhow::Result<()> {
         }
     }
 
-    Ok(())
+    Ok(episodes_added)
 }
 
-async fn extract_then_save_events(
+#[derive(Debug, thiserror::Error)]
+pub enum IngestionError {
+    #[error("Failed to fetch video details for '{video_id}'")]
+    Fetch {
+        video_id: CompactString,
+        #[source]
+        source: anyhow::Error,
+    },
+    #[error("Failed to extract timeline for episode {number}")]
+    Extract {
+        number: EpisodeNumber,
+        #[source]
+        source: anyhow::Error,
+    },
+    #[error("Failed to store episode {number}")]
+    Store {
+        number: EpisodeNumber,
+        #[source]
+        source: sqlx::Error,
+    },
+}
+
+/// Fetches, extracts and stores a single episode, recording the attempt in the ingestion log.
+/// The episode, its events and its youtube details are written in one transaction so a failure
+/// leaves nothing behind and the episode is retried on the next check.
+async fn ingest_episode(
     state: &Repo,
-    yt_api: &YoutubeApi,
+    videos: &impl VideoSource,
+    show: &str,
     name: CompactString,
-    number: f32,
+    number: EpisodeNumber,
     playlist_item: &PlaylistItem,
-) -> anyhow::Result<()> {
-    let details = yt_api
-        .get_video_details(&playlist_item.snippet.resource_id.video_id)
-        .await?;
+) -> Result<usize, IngestionError> {
+    let video_id = playlist_item
+        .snippet
+        .resource_id
+        .video_id
+        .to_compact_string();
+    let attempted_at = Utc::now().timestamp();
+
+    let res = extract_then_save_events(state, videos, show, name, number, playlist_item).await;
+
+    let (outcome, events_inserted, warnings, error) = match &res {
+        Ok((events_inserted, warnings)) => (
+            IngestionOutcome::Succeeded,
+            *events_inserted,
+            warnings.clone(),
+            None,
+        ),
+        Err(e) => (
+            IngestionOutcome::Failed,
+            0,
+            Vec::new(),
+            Some(error_chain(e)),
+        ),
+    };
+
+    for warning in &warnings {
+        warn!("Timeline of {show} episode {number}: {warning}");
+    }
+
+    let entry = NewIngestionLogEntry {
+        show_slug: show.to_compact_string(),
+        episode_number: number,
+        video_id,
+        attempted_at,
+        outcome,
+        events_inserted: events_inserted as i64,
+        error: error.map(CompactString::from),
+        warnings: TimelineWarnings(warnings),
+    };
 
-    let events = extract_pka_episode_events(
+    if let Err(e) = ingestion_log::insert(state, entry).await {
+        error!("Error recording ingestion attempt for episode {number}: {e}");
+    }
+
+    res.map(|_| events_inserted)
+}
+
+fn error_chain(e: &dyn std::error::Error) -> String {
+    let mut message = e.to_string();
+    let mut source = e.source();
+
+    while let Some(e) = source {
+        message.push_str(": ");
+        message.push_str(&e.to_string());
+        source = e.source();
+    }
+
+    message
+}
+
+async fn extract_then_save_events(
+    state: &Repo,
+    videos: &impl VideoSource,
+    show: &str,
+    name: CompactString,
+    number: EpisodeNumber,
+    playlist_item: &PlaylistItem,
+) -> Result<(usize, Vec<TimelineWarning>), IngestionError> {
+    let video_id = &playlist_item.snippet.resource_id.video_id;
+
+    let details = videos
+        .video_details(video_id)
+        .await
+        .map_err(|source| IngestionError::Fetch {
+            video_id: video_id.to_compact_string(),
+            source,
+        })?;
+
+    let ExtractedTimeline { events, warnings } = extract_pka_episode_events(
+        show,
         number,
         &details.snippet.description,
         &details.content_details.duration,
         &playlist_item.snippet.published_at,
-    )?;
+    )
+    .map_err(|source| IngestionError::Extract { number, source })?;
 
     let pka_ep = PkaEpisode::new(
+        show.to_compact_string(),
         number,
-        name.clone(),
-        playlist_item.snippet.resource_id.video_id.to_owned(),
+        name,
+        video_id.to_owned(),
         playlist_item.snippet.published_at,
     );
 
     let youtube_details = PkaYoutubeDetails::new(
         details.id,
+        show.to_compact_string(),
         number,
         details.snippet.title,
         details.content_details.duration,
     );
 
-    if let Err(e) = pka_episode::insert(state, pka_ep).await {
-        error!("Error adding latest pka_ep '{name}' (episode {number}): {e}");
-    }
+    let events_inserted = events.len();
 
-    for evt in events.into_iter() {
-        let event_id = evt.event_id();
-        if let Err(e) = pka_event::insert(state, evt).await {
-            error!("Error adding latest event '{event_id}' for episode {number}: {e}");
-        }
-    }
+    save_episode(state, pka_ep, events, youtube_details)
+        .await
+        .map_err(|source| IngestionError::Store { number, source })?;
+
+    info!("Extracted successfully.");
+
+    Ok((events_inserted, warnings))
+}
+
+async fn save_episode(
+    state: &Repo,
+    episode: PkaEpisode,
+    events: Vec<PkaEvent>,
+    youtube_details: PkaYoutubeDetails,
+) -> Result<(), sqlx::Error> {
+    let mut tx = state.begin().await?;
+
+    pka_episode::insert(&mut *tx, episode).await?;
 
-    let yt_video_id = youtube_details.video_id.clone();
-    if let Err(e) = pka_youtube_details::insert(state, youtube_details).await {
-        error!("Error adding latest youtube_details for episode {number} (video ID '{yt_video_id}'): {e}");
+    for evt in events {
+        pka_event::insert(&mut *tx, evt).await?;
     }
 
-    info!("Extracted successfully.");
+    pka_youtube_details::insert(&mut *tx, youtube_details).await?;
 
-    Ok(())
+    tx.commit().await
 }
 
-static TIMELINE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(\d{1,2}(?::|;)\d{2}(?::|;)?\d*)(?:\s*-\s*)*\s*(.+)")
-        .expect("Failed to create TIMELINE_REGEX")
-});
+/// Events extracted from a description, along with the problems found in its timeline.
+#[derive(Debug)]
+pub struct ExtractedTimeline {
+    pub events: Vec<PkaEvent>,
+    pub warnings: Vec<TimelineWarning>,
+}
 
 pub fn extract_pka_episode_events(
-    ep_number: f32,
+    show: &str,
+    ep_number: EpisodeNumber,
     data: &str,
     ep_length_seconds: &i32,
     upload_date: &i64,
-) -> anyhow::Result<Vec<PkaEvent>> {
-    let mut events_without_duration: Vec<(CompactString, f32, i32, CompactString)> = Vec::new();
-
-    for result in TIMELINE_REGEX.captures_iter(data) {
-        let time_fragment = result
-            .get(1)
-            .map(|m| m.as_str())
-            .context("Timeline regex missing timestamp capture")?;
-        let timestamp = normalize_timestamp(time_fragment)?;
+) -> anyhow::Result<ExtractedTimeline> {
+    events_from_timeline(
+        show,
+        ep_number,
+        parse_timeline(data),
+        ep_length_seconds,
+        upload_date,
+    )
+}
 
-        let description = result
-            .get(2)
-            .map(|m| m.as_str())
-            .context("Timeline regex missing description capture")
-            .map(clean_description)?;
+/// Validates a parsed or curated timeline against the video and turns its entries into events
+/// lasting until the next one.
+pub fn events_from_timeline(
+    show: &str,
+    ep_number: EpisodeNumber,
+    timeline: Timeline,
+    ep_length_seconds: &i32,
+    upload_date: &i64,
+) -> anyhow::Result<ExtractedTimeline> {
+    let (entries, warnings) = timeline.validate(*ep_length_seconds);
 
-        let event_id = format!("{:<03}-{}", ep_number, timestamp).to_compact_string();
+    let mut events = entries
+        .iter()
+        .map(|entry| {
+            PkaEvent::new(
+                PkaEvent::id_for(show, ep_number, entry.start),
+                show.to_compact_string(),
+                ep_number,
+                entry.start,
+                entry.description.clone(),
+                0,
+                *upload_date,
+            )
+        })
+        .collect::<Vec<_>>();
 
-        events_without_duration.push((event_id, ep_number, timestamp, description));
+    if events.is_empty() && !warnings.is_empty() {
+        let warnings = warnings.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+        bail!("Could not find any valid events: {}", warnings.join(", "));
     }
 
-    if events_without_duration.is_empty() {
+    if events.is_empty() {
         bail!("Could not find any events");
     }
 
-    //sort events by timestamp
-    events_without_duration.sort_by(|a, b| a.2.cmp(&b.2));
+    compute_event_lengths(&mut events, *ep_length_seconds);
+
+    // Entries are already sorted, so lengths line up with the events they came from.
+    for (event, entry) in events.iter_mut().zip(&entries) {
+        if let Some(end) = entry.end {
+            event.length_seconds = end - entry.start;
+        }
+    }
+
+    Ok(ExtractedTimeline { events, warnings })
+}
+
+/// Sorts events by timestamp and sets each length to the time until the next event,
+/// or until the end of the video for the last one.
+pub fn compute_event_lengths(events: &mut [PkaEvent], ep_length_seconds: i32) {
+    events.sort_by_key(|e| e.timestamp);
 
-    let events = events_without_duration
+    let next_timestamps = events
         .iter()
-        .enumerate()
-        .map(|(index, event)| {
-            let length_seconds = if let Some(next_event) = events_without_duration.get(index + 1) {
-                next_event.2 - event.2
-            } else {
-                *ep_length_seconds - event.2
-            }
-            .max(1);
+        .skip(1)
+        .map(|e| e.timestamp)
+        .chain(std::iter::once(ep_length_seconds))
+        .collect::<Vec<_>>();
 
+    for (event, next_timestamp) in events.iter_mut().zip(next_timestamps) {
+        event.length_seconds = (next_timestamp - event.timestamp).max(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::show::DEFAULT_SHOW;
+
+    use super::*;
+
+    #[test]
+    fn ingestion_error_chain() {
+        let e = IngestionError::Extract {
+            number: EpisodeNumber::new(481),
+            source: anyhow::anyhow!("Could not find any events"),
+        };
+
+        assert_eq!(
+            error_chain(&e),
+            "Failed to extract timeline for episode 481: Could not find any events"
+        );
+    }
+
+    #[test]
+    fn test_episode_number_from_title() {
+        let pka = Regex::new(r"(?i)PKA\s*(?P<number>\d{3,})").unwrap();
+
+        assert_eq!(episode_number_from_title(&pka, "PKA 481"), Some(481));
+        assert_eq!(
+            episode_number_from_title(&pka, "pka481 w/ Guest"),
+            Some(481)
+        );
+        assert_eq!(
+            episode_number_from_title(&pka, "PKA 612 - Woody Gets A Dog"),
+            Some(612)
+        );
+
+        assert_eq!(episode_number_from_title(&pka, "PKA 12"), None);
+        assert_eq!(episode_number_from_title(&pka, "PKN 481"), None);
+        assert_eq!(episode_number_from_title(&pka, ""), None);
+        assert_eq!(episode_number_from_title(&pka, "PKA 4294967296"), None);
+    }
+
+    #[test]
+    fn test_compute_event_lengths() {
+        let event = |timestamp| {
             PkaEvent::new(
-                event.0.clone(),
-                event.1,
-                event.2,
-                event.3.clone(),
-                length_seconds,
-                *upload_date,
+                PkaEvent::id_for(DEFAULT_SHOW, EpisodeNumber::new(1), timestamp),
+                DEFAULT_SHOW.into(),
+                EpisodeNumber::new(1),
+                timestamp,
+                "Event".to_compact_string(),
+                0,
+                0,
             )
-        })
-        .collect();
+        };
 
-    Ok(events)
-}
+        let mut events = vec![event(300), event(0), event(120), event(120)];
 
-fn normalize_timestamp(raw: &str) -> anyhow::Result<i32> {
-    let time_fragment = raw.replace(';', ":").trim_end_matches(':').to_owned();
+        compute_event_lengths(&mut events, 400);
 
-    let parts: Vec<&str> = time_fragment.split(':').collect();
+        let res = events
+            .iter()
+            .map(|e| (e.timestamp(), e.length_seconds()))
+            .collect::<Vec<_>>();
 
-    let seconds = match parts.len() {
-        1 => {
-            parts[0].parse::<i32>()
-                .with_context(|| format!("Failed to parse seconds from '{time_fragment}' (derived from '{raw}')"))?
+        assert_eq!(res, vec![(0, 120), (120, 1), (120, 180), (300, 100)]);
+    }
+
+    mod end_to_end {
+        use super::*;
+        use crate::conduit::sqlite::ingestion_log;
+        use crate::db::test_pool;
+        use crate::updater::source::YoutubeSource;
+        use crate::updater::youtube_api::mock_server::{Fixtures, MockYoutube};
+        use crate::updater::youtube_api::quota::QuotaTracker;
+        use crate::updater::youtube_api::YoutubeApi;
+
+        async fn seeded_pool(name: &str) -> Repo {
+            let pool = test_pool(name).await;
+
+            let episode = PkaEpisode::new(
+                DEFAULT_SHOW.into(),
+                EpisodeNumber::new(699),
+                "PKA 699".into(),
+                "vidPKA699".into(),
+                1707426000,
+            );
+            pka_episode::insert(&pool, episode).await.unwrap();
+
+            pool
         }
-        2 => {
-            let minutes = parts[0].parse::<i32>()
-                .with_context(|| format!("Failed to parse minutes from '{time_fragment}' (derived from '{raw}')"))?;
-            let seconds = parts[1].parse::<i32>()
-                .with_context(|| format!("Failed to parse seconds from '{time_fragment}' (derived from '{raw}')"))?;
-            minutes * 60 + seconds
+
+        #[tokio::test]
+        async fn loads_missing_episodes() {
+            let pool = seeded_pool("loads_missing_episodes").await;
+            let mock = MockYoutube::start(Fixtures::recorded()).await;
+
+            let added = load_new_episodes(&pool, &mock.api()).await.unwrap();
+
+            assert_eq!(added, 4);
+            assert_eq!(
+                pka_episode::latest(&pool, DEFAULT_SHOW).await.unwrap(),
+                EpisodeNumber::new(703)
+            );
+
+            let events = pka_event::for_episode(&pool, DEFAULT_SHOW, EpisodeNumber::new(703))
+                .await
+                .unwrap();
+            assert_eq!(events.len(), 4);
+            assert_eq!(events[1].description, "Woody bought a boat");
+            assert_eq!(
+                pka_youtube_details::find_length(&pool, DEFAULT_SHOW, EpisodeNumber::new(703))
+                    .await
+                    .unwrap(),
+                Some(9900)
+            );
+
+            let log = ingestion_log::recent(&pool, 10).await.unwrap();
+            assert_eq!(log.len(), 4);
+            assert!(log
+                .iter()
+                .all(|e| e.outcome == IngestionOutcome::Succeeded && e.show_slug == DEFAULT_SHOW));
+
+            // Everything is stored so a second check is a no-op.
+            assert_eq!(load_new_episodes(&pool, &mock.api()).await.unwrap(), 0);
         }
-        3 => {
-            let hours = parts[0].parse::<i32>()
-                .with_context(|| format!("Failed to parse hours from '{time_fragment}' (derived from '{raw}')"))?;
-            let minutes = parts[1].parse::<i32>()
-                .with_context(|| format!("Failed to parse minutes from '{time_fragment}' (derived from '{raw}')"))?;
-            let seconds = parts[2].parse::<i32>()
-                .with_context(|| format!("Failed to parse seconds from '{time_fragment}' (derived from '{raw}')"))?;
-            hours * 3600 + minutes * 60 + seconds
+
+        #[tokio::test]
+        async fn failed_episode_is_rolled_back() {
+            let pool = seeded_pool("failed_episode_is_rolled_back").await;
+
+            let mut fixtures = Fixtures::recorded();
+            fixtures.set_description("vidPKA701", "No timeline this week.");
+            let mock = MockYoutube::start(fixtures).await;
+
+            assert!(load_new_episodes(&pool, &mock.api()).await.is_err());
+
+            // 700 went in, 701 left nothing behind and stays the next episode to retry.
+            assert_eq!(
+                pka_episode::latest(&pool, DEFAULT_SHOW).await.unwrap(),
+                EpisodeNumber::new(700)
+            );
+            assert!(
+                pka_event::for_episode(&pool, DEFAULT_SHOW, EpisodeNumber::new(701))
+                    .await
+                    .unwrap()
+                    .is_empty()
+            );
+            assert_eq!(
+                pka_youtube_details::find_length(&pool, DEFAULT_SHOW, EpisodeNumber::new(701))
+                    .await
+                    .unwrap(),
+                None
+            );
+
+            let log = ingestion_log::recent(&pool, 10).await.unwrap();
+            assert_eq!(log.len(), 2);
+            assert_eq!(log[0].episode_number, EpisodeNumber::new(701));
+            assert_eq!(log[0].outcome, IngestionOutcome::Failed);
+            assert!(log[0]
+                .error
+                .as_deref()
+                .unwrap()
+                .ends_with("Could not find any events"));
         }
-        _ => bail!("Unknown timestamp format: '{time_fragment}' (derived from '{raw}')"),
-    };
 
-    Ok(seconds)
-}
+        #[tokio::test]
+        async fn failing_show_does_not_block_others() {
+            let pool = seeded_pool("failing_show_does_not_block_others").await;
+            let mock = MockYoutube::start(Fixtures::recorded()).await;
 
-fn clean_description(raw: &str) -> CompactString {
-    raw.trim()
-        .replace('’', "'")
-        .replace("â€™", "'")
-        .replace("â€¦", "…")
-        .replace("â€“", "–")
-        .replace("â€œ", "“")
-        .replace("â€", "”")
-        .to_compact_string()
-}
+            // Shares the uploads playlist but none of its titles match.
+            sqlx::query(
+                r"INSERT INTO show (slug, name, episode_prefix, playlist_id, title_regex)
+                  VALUES ('pkn', 'Painkiller News', 'PKN', 'UUIPVJoHb_A5S3kcv3TJlyEg', '(?i)PKN\s*(?P<number>\d+)')",
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            let e = load_new_episodes(&pool, &mock.api()).await.unwrap_err();
+
+            assert!(e.to_string().contains("Painkiller News"));
+            assert_eq!(
+                pka_episode::latest(&pool, DEFAULT_SHOW).await.unwrap(),
+                EpisodeNumber::new(703)
+            );
+            assert!(matches!(
+                pka_episode::latest(&pool, "pkn").await,
+                Err(sqlx::Error::RowNotFound)
+            ));
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        #[tokio::test]
+        async fn rejects_wrong_api_key() {
+            let pool = seeded_pool("rejects_wrong_api_key").await;
+            let mock = MockYoutube::start(Fixtures::recorded()).await;
 
-    #[test]
-    fn test_normalize_timestamp() {
-        // Standard formats
-        assert_eq!(normalize_timestamp("12:34").unwrap(), 754);
-        assert_eq!(normalize_timestamp("2:34").unwrap(), 154);
-        assert_eq!(normalize_timestamp("1:02:03").unwrap(), 3723);
-
-        // Out-of-range bounds
-        assert_eq!(normalize_timestamp("2:36:66").unwrap(), 9426); // Out-of-range seconds!
-        assert_eq!(normalize_timestamp("02:36:66").unwrap(), 9426);
-        assert_eq!(normalize_timestamp("2:80:05").unwrap(), 12005); // Out-of-range minutes!
-
-        // Alternative separators (semicolons instead of colons)
-        assert_eq!(normalize_timestamp("12;34").unwrap(), 754);
-        assert_eq!(normalize_timestamp("1;02;03").unwrap(), 3723);
-
-        // Trailing separator trimming
-        assert_eq!(normalize_timestamp("12:34:").unwrap(), 754);
-        assert_eq!(normalize_timestamp("1:02:03:").unwrap(), 3723);
-
-        // Single part (seconds only)
-        assert_eq!(normalize_timestamp("45").unwrap(), 45);
-
-        // Error cases
-        assert!(normalize_timestamp("12:34:56:78").is_err()); // Too many parts
-        assert!(normalize_timestamp("abc").is_err()); // Non-digit characters
-        assert!(normalize_timestamp("12:ab").is_err()); // Non-digit parts
+            let api = YoutubeApi::new(mock.base_url(), "wrong-key").unwrap();
+
+            assert!(load_new_episodes(&pool, &api).await.is_err());
+            assert_eq!(
+                pka_episode::latest(&pool, DEFAULT_SHOW).await.unwrap(),
+                EpisodeNumber::new(699)
+            );
+        }
+
+        #[tokio::test]
+        async fn loads_missing_episodes_from_feed() {
+            let pool = seeded_pool("loads_missing_episodes_from_feed").await;
+            let mock = MockYoutube::start(Fixtures::recorded()).await;
+
+            let youtube = YoutubeSource::new(None, mock.feed());
+
+            assert_eq!(load_new_episodes(&pool, &youtube).await.unwrap(), 4);
+            assert_eq!(
+                pka_episode::latest(&pool, DEFAULT_SHOW).await.unwrap(),
+                EpisodeNumber::new(703)
+            );
+            assert_eq!(
+                pka_event::for_episode(&pool, DEFAULT_SHOW, EpisodeNumber::new(702))
+                    .await
+                    .unwrap()
+                    .len(),
+                3
+            );
+            assert_eq!(
+                pka_youtube_details::find_length(&pool, DEFAULT_SHOW, EpisodeNumber::new(702))
+                    .await
+                    .unwrap(),
+                Some(7805)
+            );
+        }
+
+        #[tokio::test]
+        async fn falls_back_to_feed_when_api_fails() {
+            let pool = seeded_pool("falls_back_to_feed_when_api_fails").await;
+            let mock = MockYoutube::start(Fixtures::recorded()).await;
+
+            let api = YoutubeApi::new(mock.base_url(), "wrong-key").unwrap();
+            let youtube = YoutubeSource::new(Some(api), mock.feed());
+
+            assert_eq!(load_new_episodes(&pool, &youtube).await.unwrap(), 4);
+            assert_eq!(
+                pka_episode::latest(&pool, DEFAULT_SHOW).await.unwrap(),
+                EpisodeNumber::new(703)
+            );
+        }
+
+        #[tokio::test]
+        async fn uses_feed_while_quota_is_spent() {
+            let pool = seeded_pool("uses_feed_while_quota_is_spent").await;
+            let mock = MockYoutube::start(Fixtures::recorded()).await;
+
+            let quota = QuotaTracker::new(pool.clone(), 10_000);
+            quota.exhaust().await.unwrap();
+
+            let api = mock.api().with_quota(quota);
+            let youtube = YoutubeSource::new(Some(api), mock.feed());
+
+            assert_eq!(load_new_episodes(&pool, &youtube).await.unwrap(), 4);
+            assert_eq!(mock.requests("/playlistItems"), 0);
+            assert_eq!(mock.requests("/videos"), 0);
+        }
     }
 }
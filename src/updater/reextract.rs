@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+
+use axum::http::StatusCode;
+use compact_str::CompactString;
+use sha2::{Digest, Sha256};
+use sqlx::SqliteConnection;
+use tracing::info;
+
+use crate::conduit::sqlite::{pka_episode, pka_event};
+use crate::models::episode_number::EpisodeNumber;
+use crate::models::errors::ApiError;
+use crate::models::pka_event::PkaEvent;
+use crate::models::timeline_diff::{EventChange, TimelineDiff};
+use crate::updater::pka::{extract_pka_episode_events, ExtractedTimeline};
+use crate::updater::source::VideoSource;
+use crate::Repo;
+
+/// Re-fetches the episode's description from YouTube and diffs the extracted timeline
+/// against the stored one. When `apply` is set the stored timeline is read and replaced in
+/// a single transaction. Passing the `revision` of an earlier preview refuses to apply if
+/// the description has been edited since.
+pub async fn reextract_episode(
+    state: &Repo,
+    videos: &impl VideoSource,
+    show: &str,
+    number: EpisodeNumber,
+    apply: bool,
+    revision: Option<&str>,
+) -> Result<TimelineDiff, ApiError> {
+    let episode = pka_episode::find(state, show, number).await?;
+
+    let details = videos.video_details(&episode.youtube_link).await?;
+    let description = &details.snippet.description;
+    let current_revision = description_revision(description);
+
+    if apply && revision.is_some_and(|r| r != current_revision) {
+        return Err(ApiError::new(
+            "The description has changed since the preview, preview the re-extraction again",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    let ExtractedTimeline { events, warnings } = extract_pka_episode_events(
+        show,
+        number,
+        description,
+        &details.content_details.duration,
+        &episode.upload_date,
+    )?;
+
+    let mut tx = state.begin().await?;
+
+    let existing = pka_event::for_episode(&mut *tx, show, number).await?;
+
+    let mut diff = diff_events(number, existing, events.clone());
+    diff.warnings = warnings;
+    diff.revision = Some(current_revision);
+
+    if apply && !diff.is_empty() {
+        replace_events_in(&mut tx, show, number, events).await?;
+        tx.commit().await?;
+
+        diff.applied = true;
+
+        info!(
+            "Re-extracted {show} episode {number}: {} added, {} removed, {} changed.",
+            diff.added.len(),
+            diff.removed.len(),
+            diff.changed.len()
+        );
+    }
+
+    Ok(diff)
+}
+
+/// Fingerprint of a description, so an apply can check it's working from what was previewed.
+pub fn description_revision(description: &str) -> CompactString {
+    let digest = format!("{:x}", Sha256::digest(description.as_bytes()));
+
+    CompactString::from(&digest[..16])
+}
+
+/// Replaces the episode's stored timeline in a single transaction.
+pub async fn replace_events(
+    state: &Repo,
+    show: &str,
+    number: EpisodeNumber,
+    events: Vec<PkaEvent>,
+) -> Result<(), sqlx::Error> {
+    let mut tx = state.begin().await?;
+
+    replace_events_in(&mut tx, show, number, events).await?;
+
+    tx.commit().await
+}
+
+/// Replaces the episode's stored timeline as part of a larger transaction.
+pub async fn replace_events_in(
+    conn: &mut SqliteConnection,
+    show: &str,
+    number: EpisodeNumber,
+    events: Vec<PkaEvent>,
+) -> Result<(), sqlx::Error> {
+    pka_event::delete_for_episode(&mut *conn, show, number).await?;
+
+    for evt in events {
+        pka_event::insert(&mut *conn, evt).await?;
+    }
+
+    Ok(())
+}
+
+/// Matches events by timestamp.
+pub fn diff_events(
+    number: EpisodeNumber,
+    existing: Vec<PkaEvent>,
+    extracted: Vec<PkaEvent>,
+) -> TimelineDiff {
+    let mut existing = existing
+        .into_iter()
+        .map(|e| (e.timestamp(), e))
+        .collect::<HashMap<_, _>>();
+
+    let mut diff = TimelineDiff {
+        episode_number: number,
+        ..Default::default()
+    };
+
+    for after in extracted {
+        match existing.remove(&after.timestamp()) {
+            Some(before)
+                if before.description() == after.description()
+                    && before.length_seconds() == after.length_seconds() =>
+            {
+                diff.unchanged += 1;
+            }
+            Some(before) => diff.changed.push(EventChange { before, after }),
+            None => diff.added.push(after),
+        }
+    }
+
+    diff.removed = existing.into_values().collect();
+    diff.removed.sort_by_key(|e| e.timestamp());
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use compact_str::ToCompactString;
+
+    use crate::models::show::DEFAULT_SHOW;
+
+    use super::*;
+
+    fn event(timestamp: i32, description: &str, length_seconds: i32) -> PkaEvent {
+        PkaEvent::new(
+            PkaEvent::id_for(DEFAULT_SHOW, EpisodeNumber::new(1), timestamp),
+            DEFAULT_SHOW.into(),
+            EpisodeNumber::new(1),
+            timestamp,
+            description.to_compact_string(),
+            length_seconds,
+            0,
+        )
+    }
+
+    #[test]
+    fn test_diff_events() {
+        let existing = vec![
+            event(0, "Intro", 60),
+            event(60, "Woody buys a boat", 60),
+            event(120, "Outro", 30),
+        ];
+
+        let extracted = vec![
+            event(0, "Intro", 60),
+            event(60, "Woody buys a yacht", 30),
+            event(90, "Taylor sinks it", 60),
+        ];
+
+        let diff = diff_events(EpisodeNumber::new(1), existing, extracted);
+
+        assert_eq!(diff.unchanged, 1);
+
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].before.description(), "Woody buys a boat");
+        assert_eq!(diff.changed[0].after.description(), "Woody buys a yacht");
+
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].timestamp(), 90);
+
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].timestamp(), 120);
+
+        assert!(!diff.is_empty());
+        assert!(diff_events(
+            EpisodeNumber::new(1),
+            vec![event(0, "Intro", 60)],
+            vec![event(0, "Intro", 60)]
+        )
+        .is_empty());
+    }
+
+    mod end_to_end {
+        use axum::response::IntoResponse;
+
+        use super::*;
+        use crate::db::test_pool;
+        use crate::models::pka_episode::PkaEpisode;
+        use crate::updater::youtube_api::mock_server::{Fixtures, MockYoutube};
+
+        async fn seeded_pool(name: &str) -> Repo {
+            let pool = test_pool(name).await;
+
+            let episode = PkaEpisode::new(
+                DEFAULT_SHOW.into(),
+                EpisodeNumber::new(703),
+                "PKA 703".into(),
+                "vidPKA703".into(),
+                1707426000,
+            );
+            pka_episode::insert(&pool, episode).await.unwrap();
+
+            pool
+        }
+
+        fn status(err: ApiError) -> StatusCode {
+            err.into_response().status()
+        }
+
+        #[tokio::test]
+        async fn applies_only_the_previewed_description() {
+            let pool = seeded_pool("applies_only_the_previewed_description").await;
+            let number = EpisodeNumber::new(703);
+            let mock = MockYoutube::start(Fixtures::recorded()).await;
+
+            let preview = reextract_episode(&pool, &mock.api(), DEFAULT_SHOW, number, false, None)
+                .await
+                .unwrap();
+            assert_eq!(preview.added.len(), 4);
+            assert!(!preview.applied);
+            let revision = preview.revision.unwrap();
+
+            let mut fixtures = Fixtures::recorded();
+            fixtures.set_description("vidPKA703", "0:00 Intro\n1:00 Edited after the preview");
+            let edited = MockYoutube::start(fixtures).await;
+
+            let err = reextract_episode(
+                &pool,
+                &edited.api(),
+                DEFAULT_SHOW,
+                number,
+                true,
+                Some(&revision),
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(status(err), StatusCode::CONFLICT);
+            assert!(pka_event::for_episode(&pool, DEFAULT_SHOW, number)
+                .await
+                .unwrap()
+                .is_empty());
+
+            let applied = reextract_episode(
+                &pool,
+                &mock.api(),
+                DEFAULT_SHOW,
+                number,
+                true,
+                Some(&revision),
+            )
+            .await
+            .unwrap();
+            assert!(applied.applied);
+            assert_eq!(
+                pka_event::for_episode(&pool, DEFAULT_SHOW, number)
+                    .await
+                    .unwrap()
+                    .len(),
+                4
+            );
+        }
+
+        #[tokio::test]
+        async fn missing_episode_is_not_found() {
+            let pool = seeded_pool("missing_episode_is_not_found").await;
+            let mock = MockYoutube::start(Fixtures::recorded()).await;
+
+            let err = reextract_episode(
+                &pool,
+                &mock.api(),
+                DEFAULT_SHOW,
+                EpisodeNumber::new(9999),
+                false,
+                None,
+            )
+            .await
+            .unwrap_err();
+
+            assert_eq!(status(err), StatusCode::NOT_FOUND);
+        }
+    }
+}
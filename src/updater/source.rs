@@ -0,0 +1,144 @@
+use tracing::warn;
+
+use crate::config::Config;
+use crate::updater::youtube_api::models::playlist_items::PlaylistItem;
+use crate::updater::youtube_api::models::videos::VideosItem;
+use crate::updater::youtube_api::YoutubeApi;
+use crate::updater::youtube_feed::YoutubeFeed;
+use crate::Repo;
+
+/// Where the updater finds new uploads and their descriptions and durations.
+pub trait VideoSource {
+    /// Uploads newest first, stopping before the first video `is_known` accepts
+    /// or once `max_results` items are collected.
+    async fn uploads_until_known(
+        &self,
+        max_results: usize,
+        playlist_id: &str,
+        is_known: impl Fn(&str) -> bool,
+    ) -> anyhow::Result<Vec<PlaylistItem>>;
+
+    async fn video_details(&self, video_id: &str) -> anyhow::Result<VideosItem>;
+}
+
+impl VideoSource for YoutubeApi {
+    async fn uploads_until_known(
+        &self,
+        max_results: usize,
+        playlist_id: &str,
+        is_known: impl Fn(&str) -> bool,
+    ) -> anyhow::Result<Vec<PlaylistItem>> {
+        self.get_uploads_until_known(max_results, playlist_id, is_known)
+            .await
+    }
+
+    async fn video_details(&self, video_id: &str) -> anyhow::Result<VideosItem> {
+        self.get_video_details(video_id).await
+    }
+}
+
+impl VideoSource for YoutubeFeed {
+    async fn uploads_until_known(
+        &self,
+        max_results: usize,
+        playlist_id: &str,
+        is_known: impl Fn(&str) -> bool,
+    ) -> anyhow::Result<Vec<PlaylistItem>> {
+        self.get_uploads_until_known(max_results, playlist_id, is_known)
+            .await
+    }
+
+    async fn video_details(&self, video_id: &str) -> anyhow::Result<VideosItem> {
+        self.get_video_details(video_id).await
+    }
+}
+
+/// Uses the Data API when a key is configured and falls back to the public feed
+/// when there is no key or the API call fails, e.g. once the daily quota is spent.
+pub struct YoutubeSource {
+    api: Option<YoutubeApi>,
+    feed: YoutubeFeed,
+}
+
+impl YoutubeSource {
+    pub fn new(api: Option<YoutubeApi>, feed: YoutubeFeed) -> Self {
+        Self { api, feed }
+    }
+
+    pub fn from_config(config: &Config, db: &Repo) -> anyhow::Result<Self> {
+        let api = if config.yt_api_key.is_empty() {
+            warn!("No YouTube API key configured, new episodes will be read from the public feed.");
+            None
+        } else {
+            Some(YoutubeApi::from_config(config, db)?)
+        };
+
+        Ok(Self::new(api, YoutubeFeed::from_config(config)?))
+    }
+
+    /// Only the Data API supports batched lookups, so drift detection needs it.
+    pub fn api(&self) -> Option<&YoutubeApi> {
+        self.api.as_ref()
+    }
+}
+
+impl VideoSource for YoutubeSource {
+    async fn uploads_until_known(
+        &self,
+        max_results: usize,
+        playlist_id: &str,
+        is_known: impl Fn(&str) -> bool,
+    ) -> anyhow::Result<Vec<PlaylistItem>> {
+        let api_error = match &self.api {
+            Some(api) => match api
+                .get_uploads_until_known(max_results, playlist_id, &is_known)
+                .await
+            {
+                Ok(items) => return Ok(items),
+                Err(e) => {
+                    warn!("Falling back to the feed for uploads: {e:#}");
+                    Some(e)
+                }
+            },
+            None => None,
+        };
+
+        let res = self
+            .feed
+            .get_uploads_until_known(max_results, playlist_id, is_known)
+            .await;
+
+        keep_api_error(res, api_error)
+    }
+
+    async fn video_details(&self, video_id: &str) -> anyhow::Result<VideosItem> {
+        let api_error = match &self.api {
+            Some(api) => match api.get_video_details(video_id).await {
+                Ok(details) => return Ok(details),
+                Err(e) => {
+                    warn!("Falling back to the watch page for '{video_id}': {e:#}");
+                    Some(e)
+                }
+            },
+            None => None,
+        };
+
+        let res = self.feed.get_video_details(video_id).await;
+
+        keep_api_error(res, api_error)
+    }
+}
+
+/// When both sources fail, reports the API error (so e.g. quota exhaustion can still be
+/// recognised) with the fallback's failure attached.
+fn keep_api_error<T>(
+    res: anyhow::Result<T>,
+    api_error: Option<anyhow::Error>,
+) -> anyhow::Result<T> {
+    match (res, api_error) {
+        (Err(feed_error), Some(api_error)) => {
+            Err(api_error.context(format!("Fallback failed too: {feed_error:#}")))
+        }
+        (res, _) => res,
+    }
+}
@@ -0,0 +1,592 @@
+//! Line-oriented parser for the timelines in video descriptions.
+//!
+//! Every line is checked for a timestamp at its start, at its end or inside a link with a
+//! `t=` parameter. Each candidate gets a confidence score so times mentioned in prose are
+//! left out, see [`MIN_CONFIDENCE`].
+
+use std::sync::LazyLock;
+
+use anyhow::{bail, Context};
+use compact_str::{CompactString, ToCompactString};
+use regex::{Captures, Regex};
+
+use crate::models::timeline_warning::TimelineWarning;
+
+/// Candidates scoring below this, out of 100, are treated as prose.
+pub const MIN_CONFIDENCE: i32 = 50;
+
+/// Words that turn a timestamp at the end of a line into part of a sentence, e.g.
+/// "see the clip at 12:30".
+const PREPOSITIONS: &[&str] = &[
+    "at", "from", "around", "until", "till", "by", "to", "about", "after", "before", "since",
+];
+
+/// Words that continue a sentence after a timestamp at the start of a line, e.g.
+/// "12:30 is when the stream died".
+const CONTINUATIONS: &[&str] = &["is", "was", "and", "or", "of", "in", "on", "pm", "am"];
+
+const TIMESTAMP: &str = r"\d{1,3}[:;]\d{2}(?:[:;]\d{2})?\b";
+
+static BULLET_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:[-*•·▪‣◦►>–—]+\s*|\d{1,2}[.)]\s+)").expect("Failed to create BULLET_REGEX")
+});
+
+static LEADING_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"^(?P<open>[\[(])?(?P<start>{TIMESTAMP})[\])]?(?:\s*(?:-|–|—|~|to)\s*[\[(]?(?P<end>{TIMESTAMP})[\])]?)?(?P<sep>\s*[-–—:|•.]+)?\s*(?P<desc>.*)$"
+    ))
+    .expect("Failed to create LEADING_REGEX")
+});
+
+static TRAILING_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"^(?P<desc>.*?\S)(?P<sep>\s*[-–—:|@]\s*|\s+)(?P<open>[\[(])?(?P<start>{TIMESTAMP})[\])]?(?:\s*(?:-|–|—|~|to)\s*[\[(]?(?P<end>{TIMESTAMP})[\])]?)?\s*$"
+    ))
+    .expect("Failed to create TRAILING_REGEX")
+});
+
+static LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"https?://\S*?[?&#]t=(?:(?P<h>\d+)h)?(?:(?P<m>\d+)m)?(?:(?P<s>\d+)s?)?\S*")
+        .expect("Failed to create LINK_REGEX")
+});
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineEntry {
+    pub start: i32,
+    /// Only set when the line gives a range, e.g. `1:02:03 - 1:05:00 Woody's boat`.
+    pub end: Option<i32>,
+    pub description: CompactString,
+    /// Out of 100.
+    pub confidence: i32,
+}
+
+#[derive(Debug, Default)]
+pub struct Timeline {
+    pub entries: Vec<TimelineEntry>,
+    /// Timestamps on lines that look like entries but aren't valid times, e.g. `2:80:05`.
+    pub invalid_timestamps: Vec<CompactString>,
+}
+
+impl Timeline {
+    /// Checks the entries against the video's length, which is skipped when it's unknown.
+    /// Entries past the end of the video or at the same time as an earlier one are dropped,
+    /// the rest are returned sorted by start.
+    pub fn validate(self, video_length_seconds: i32) -> (Vec<TimelineEntry>, Vec<TimelineWarning>) {
+        let mut warnings = self
+            .invalid_timestamps
+            .into_iter()
+            .map(|raw| TimelineWarning::InvalidTimestamp { raw })
+            .collect::<Vec<_>>();
+
+        let mut entries: Vec<TimelineEntry> = Vec::with_capacity(self.entries.len());
+        let mut latest: Option<i32> = None;
+
+        for mut entry in self.entries {
+            let timestamp = entry.start;
+
+            if let Some(previous) = latest.filter(|previous| timestamp < *previous) {
+                warnings.push(TimelineWarning::OutOfOrder {
+                    timestamp,
+                    previous,
+                });
+            }
+            latest = latest.max(Some(timestamp));
+
+            if video_length_seconds > 0 && timestamp >= video_length_seconds {
+                warnings.push(TimelineWarning::PastEnd {
+                    timestamp,
+                    video_length_seconds,
+                });
+                continue;
+            }
+
+            if entries.iter().any(|e| e.start == timestamp) {
+                warnings.push(TimelineWarning::Duplicate { timestamp });
+                continue;
+            }
+
+            if let Some(end) = entry
+                .end
+                .filter(|end| video_length_seconds > 0 && *end > video_length_seconds)
+            {
+                warnings.push(TimelineWarning::EndPastVideo { timestamp, end });
+                entry.end = Some(video_length_seconds);
+            }
+
+            entries.push(entry);
+        }
+
+        entries.sort_by_key(|e| e.start);
+
+        (entries, warnings)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Anchor {
+    Start,
+    Link,
+    End,
+}
+
+#[derive(Debug)]
+struct Candidate {
+    line: usize,
+    indent: usize,
+    anchor: Anchor,
+    /// Bracketed, a range or followed by a separator such as `-`.
+    marked: bool,
+    /// The timestamp as written when it isn't a valid time.
+    start: Result<i32, CompactString>,
+    end: Option<i32>,
+    description: String,
+}
+
+/// Parses the timeline out of a video description, in the order the entries appear.
+pub fn parse_timeline(text: &str) -> Timeline {
+    let mut candidates: Vec<Candidate> = Vec::new();
+
+    for (line, raw) in text.lines().enumerate() {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let indent = raw.len() - raw.trim_start().len();
+        let body = BULLET_REGEX.replace(trimmed, "");
+
+        if let Some(candidate) = candidate(line, indent, &body) {
+            candidates.push(candidate);
+            continue;
+        }
+
+        // Lines without a timestamp either describe an entry whose own line had no text,
+        // or continue the previous entry when indented below it.
+        if let Some(last) = candidates.last_mut() {
+            let follows = line == last.line + 1;
+
+            if follows && last.description.is_empty() {
+                last.description = body.into_owned();
+                last.line = line;
+            } else if follows && indent > last.indent {
+                last.description.push(' ');
+                last.description.push_str(&body);
+                last.line = line;
+            }
+        }
+    }
+
+    let scores = candidates.iter().map(score).collect::<Vec<_>>();
+
+    let mut timeline = Timeline::default();
+
+    let accepted =
+        candidates
+            .iter()
+            .zip(&scores)
+            .enumerate()
+            .filter_map(|(i, (candidate, &score))| {
+                // Entries next to other entries are much more likely to be a timeline.
+                let neighbour = |j: Option<usize>| {
+                    j.and_then(|j| candidates.get(j).zip(scores.get(j)))
+                        .is_some_and(|(c, &s)| s > 0 && c.line.abs_diff(candidate.line) <= 2)
+                };
+                let in_block = neighbour(i.checked_sub(1)) || neighbour(Some(i + 1));
+
+                let confidence = if in_block && score > 0 {
+                    (score + 10).min(100)
+                } else {
+                    score
+                };
+
+                (confidence >= MIN_CONFIDENCE).then_some((candidate, confidence))
+            });
+
+    for (candidate, confidence) in accepted {
+        match &candidate.start {
+            Ok(start) => timeline.entries.push(TimelineEntry {
+                start: *start,
+                end: candidate.end.filter(|end| end > start),
+                description: clean_description(&candidate.description),
+                confidence,
+            }),
+            Err(raw) => timeline.invalid_timestamps.push(raw.clone()),
+        }
+    }
+
+    timeline
+}
+
+fn candidate(line: usize, indent: usize, body: &str) -> Option<Candidate> {
+    let from_captures = |anchor, captures: Captures| {
+        let raw = captures.name("start")?.as_str();
+        let start = normalize_timestamp(raw).map_err(|_| raw.to_compact_string());
+        let end = captures
+            .name("end")
+            .and_then(|end| normalize_timestamp(end.as_str()).ok());
+
+        let marked = end.is_some()
+            || captures.name("open").is_some()
+            || captures
+                .name("sep")
+                .is_some_and(|sep| !sep.as_str().trim().is_empty());
+
+        Some(Candidate {
+            line,
+            indent,
+            anchor,
+            marked,
+            start,
+            end,
+            description: trim_separators(captures.name("desc")?.as_str()).to_owned(),
+        })
+    };
+
+    if let Some(captures) = LEADING_REGEX.captures(body) {
+        return from_captures(Anchor::Start, captures);
+    }
+
+    if let Some(link) = LINK_REGEX.captures(body) {
+        let part = |name| link.name(name).and_then(|m| m.as_str().parse::<i32>().ok());
+
+        if let (None, None, None) = (part("h"), part("m"), part("s")) {
+            return None;
+        }
+
+        let start =
+            part("h").unwrap_or(0) * 3600 + part("m").unwrap_or(0) * 60 + part("s").unwrap_or(0);
+        let url = link.get(0)?;
+        let description = format!("{} {}", &body[..url.start()], &body[url.end()..]);
+
+        return Some(Candidate {
+            line,
+            indent,
+            anchor: Anchor::Link,
+            marked: true,
+            start: Ok(start),
+            end: None,
+            description: trim_separators(&description).to_owned(),
+        });
+    }
+
+    TRAILING_REGEX
+        .captures(body)
+        .and_then(|captures| from_captures(Anchor::End, captures))
+}
+
+fn score(candidate: &Candidate) -> i32 {
+    let words = candidate
+        .description
+        .split_whitespace()
+        .map(|w| {
+            w.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .collect::<Vec<_>>();
+
+    if words.is_empty() {
+        return 0;
+    }
+
+    let mut score = match candidate.anchor {
+        Anchor::Start | Anchor::Link => 70,
+        Anchor::End => 50,
+    };
+
+    if candidate.marked {
+        score += 20;
+    }
+
+    if words.len() > 16 {
+        score -= 30;
+    }
+
+    if words.len() > 8 && candidate.description.ends_with(['.', '!', '?']) {
+        score -= 20;
+    }
+
+    match candidate.anchor {
+        Anchor::Start if !candidate.marked && CONTINUATIONS.contains(&words[0].as_str()) => {
+            score -= 40;
+        }
+        Anchor::End if PREPOSITIONS.contains(&words[words.len() - 1].as_str()) => {
+            score -= 40;
+        }
+        _ => {}
+    }
+
+    score.clamp(0, 100)
+}
+
+fn trim_separators(raw: &str) -> &str {
+    raw.trim_matches(|c: char| c.is_whitespace() || matches!(c, '-' | '–' | '—' | ':' | '|' | '•'))
+}
+
+pub fn normalize_timestamp(raw: &str) -> anyhow::Result<i32> {
+    let time_fragment = raw.replace(';', ":").trim_end_matches(':').to_owned();
+
+    let parts: Vec<&str> = time_fragment.split(':').collect();
+
+    let seconds = match parts.len() {
+        1 => {
+            parts[0].parse::<i32>()
+                .with_context(|| format!("Failed to parse seconds from '{time_fragment}' (derived from '{raw}')"))?
+        }
+        2 => {
+            let minutes = parts[0].parse::<i32>()
+                .with_context(|| format!("Failed to parse minutes from '{time_fragment}' (derived from '{raw}')"))?;
+            let seconds = parts[1].parse::<i32>()
+                .with_context(|| format!("Failed to parse seconds from '{time_fragment}' (derived from '{raw}')"))?;
+            if seconds >= 60 {
+                bail!("Seconds out of range in '{time_fragment}' (derived from '{raw}')");
+            }
+            minutes * 60 + seconds
+        }
+        3 => {
+            let hours = parts[0].parse::<i32>()
+                .with_context(|| format!("Failed to parse hours from '{time_fragment}' (derived from '{raw}')"))?;
+            let minutes = parts[1].parse::<i32>()
+                .with_context(|| format!("Failed to parse minutes from '{time_fragment}' (derived from '{raw}')"))?;
+            let seconds = parts[2].parse::<i32>()
+                .with_context(|| format!("Failed to parse seconds from '{time_fragment}' (derived from '{raw}')"))?;
+            if minutes >= 60 || seconds >= 60 {
+                bail!(
+                    "Minutes or seconds out of range in '{time_fragment}' (derived from '{raw}')"
+                );
+            }
+            hours * 3600 + minutes * 60 + seconds
+        }
+        _ => bail!("Unknown timestamp format: '{time_fragment}' (derived from '{raw}')"),
+    };
+
+    Ok(seconds)
+}
+
+/// Formats seconds as `H:MM:SS`, which [`normalize_timestamp`] reads back.
+pub fn format_timestamp(seconds: i32) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+fn clean_description(raw: &str) -> CompactString {
+    raw.trim()
+        .replace('’', "'")
+        .replace("â€™", "'")
+        .replace("â€¦", "…")
+        .replace("â€“", "–")
+        .replace("â€œ", "“")
+        .replace("â€", "”")
+        .to_compact_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_timestamp() {
+        // Standard formats
+        assert_eq!(normalize_timestamp("12:34").unwrap(), 754);
+        assert_eq!(normalize_timestamp("2:34").unwrap(), 154);
+        assert_eq!(normalize_timestamp("1:02:03").unwrap(), 3723);
+
+        // Minutes past the hour are fine without an hours part
+        assert_eq!(normalize_timestamp("75:30").unwrap(), 4530);
+
+        // Out-of-range bounds
+        assert!(normalize_timestamp("2:36:66").is_err()); // Out-of-range seconds!
+        assert!(normalize_timestamp("02:36:66").is_err());
+        assert!(normalize_timestamp("2:80:05").is_err()); // Out-of-range minutes!
+        assert!(normalize_timestamp("12:60").is_err());
+
+        // Alternative separators (semicolons instead of colons)
+        assert_eq!(normalize_timestamp("12;34").unwrap(), 754);
+        assert_eq!(normalize_timestamp("1;02;03").unwrap(), 3723);
+
+        // Trailing separator trimming
+        assert_eq!(normalize_timestamp("12:34:").unwrap(), 754);
+        assert_eq!(normalize_timestamp("1:02:03:").unwrap(), 3723);
+
+        // Single part (seconds only)
+        assert_eq!(normalize_timestamp("45").unwrap(), 45);
+
+        // Error cases
+        assert!(normalize_timestamp("12:34:56:78").is_err()); // Too many parts
+        assert!(normalize_timestamp("abc").is_err()); // Non-digit characters
+        assert!(normalize_timestamp("12:ab").is_err()); // Non-digit parts
+
+        // Round trip
+        assert_eq!(format_timestamp(3723), "1:02:03");
+        assert_eq!(normalize_timestamp(&format_timestamp(754)).unwrap(), 754);
+    }
+
+    fn assert_timeline(text: &str, expected: &[(i32, Option<i32>, &str)]) {
+        let res = parse_timeline(text)
+            .entries
+            .into_iter()
+            .map(|e| (e.start, e.end, e.description))
+            .collect::<Vec<_>>();
+
+        let expected = expected
+            .iter()
+            .map(|&(start, end, description)| (start, end, CompactString::from(description)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(res, expected);
+    }
+
+    // TODO: The fixtures are still written to mimic the description layouts seen on the channel.
+    // Record real descriptions with `record_description_corpus` (see `youtube_api::record`) and
+    // swap these for excerpts of them, badly formatted ones included.
+    #[test]
+    fn corpus_dash_list() {
+        assert_timeline(
+            include_str!("fixtures/dash_list.txt"),
+            &[
+                (0, None, "Intro"),
+                (252, None, "Kyle's new car"),
+                (750, None, "Woody bought a boat"),
+                (3525, None, "Ad read"),
+                (3910, None, "Taylor's trip to Japan"),
+                (9600, None, "Outro"),
+            ],
+        );
+    }
+
+    #[test]
+    fn corpus_brackets_and_multi_line() {
+        assert_timeline(
+            include_str!("fixtures/brackets.txt"),
+            &[
+                (0, None, "Intro"),
+                (195, None, "Woody's dog ate a sock and had to go to the vet"),
+                (1540, None, "Kyle reviews the new Zelda"),
+                (4323, None, "Listener questions"),
+            ],
+        );
+    }
+
+    #[test]
+    fn corpus_ranges() {
+        assert_timeline(
+            include_str!("fixtures/ranges.txt"),
+            &[
+                (0, Some(750), "Intro and news"),
+                (750, Some(3900), "Woody's boat"),
+                (3900, Some(6022), "Guest introduction"),
+                (6022, None, "Outro"),
+            ],
+        );
+    }
+
+    #[test]
+    fn corpus_trailing_timestamps() {
+        assert_timeline(
+            include_str!("fixtures/trailing.txt"),
+            &[
+                (0, None, "Intro"),
+                (750, None, "Woody's boat"),
+                (3910, None, "Kyle's new car"),
+                (9600, None, "Outro"),
+            ],
+        );
+    }
+
+    #[test]
+    fn corpus_links() {
+        assert_timeline(
+            include_str!("fixtures/links.txt"),
+            &[
+                (754, None, "Woody bought a boat"),
+                (3910, None, "Kyle's new car"),
+                (9600, None, "Outro"),
+            ],
+        );
+    }
+
+    #[test]
+    fn corpus_nested() {
+        assert_timeline(
+            include_str!("fixtures/nested.txt"),
+            &[
+                (0, None, "Intro"),
+                (600, None, "News"),
+                (765, None, "Woody's new PC"),
+                (
+                    1210,
+                    None,
+                    "The state of YouTube, ad rates and demonetization",
+                ),
+                (2700, None, "Listener questions"),
+            ],
+        );
+    }
+
+    #[test]
+    fn corpus_prose() {
+        assert_timeline(include_str!("fixtures/prose.txt"), &[]);
+    }
+
+    #[test]
+    fn rejects_prose_lines() {
+        assert_timeline("Catch the clip at 12:30", &[]);
+        assert_timeline("12:30 is when the stream died", &[]);
+        assert_timeline("Streams every Thursday at 7:00 PM EST", &[]);
+        assert_timeline("0:00", &[]);
+
+        // Backwards ranges keep the start only.
+        assert_timeline(
+            "1:05:00 - 12:30 Woody's boat",
+            &[(3900, None, "Woody's boat")],
+        );
+    }
+
+    #[test]
+    fn validate_against_video_length() {
+        let text = "0:00 - Intro\n\
+                    20:00 - Boat\n\
+                    10:00 - Car\n\
+                    10:00 - Car again\n\
+                    2:80:05 - Typo\n\
+                    50:00 - 1:10:00 Outro\n\
+                    1:30:00 - After the stream";
+
+        let (entries, warnings) = parse_timeline(text).validate(3600);
+
+        let res = entries.iter().map(|e| (e.start, e.end)).collect::<Vec<_>>();
+
+        assert_eq!(
+            res,
+            vec![(0, None), (600, None), (1200, None), (3000, Some(3600))]
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                TimelineWarning::InvalidTimestamp {
+                    raw: "2:80:05".into()
+                },
+                TimelineWarning::OutOfOrder {
+                    timestamp: 600,
+                    previous: 1200
+                },
+                TimelineWarning::OutOfOrder {
+                    timestamp: 600,
+                    previous: 1200
+                },
+                TimelineWarning::Duplicate { timestamp: 600 },
+                TimelineWarning::EndPastVideo {
+                    timestamp: 3000,
+                    end: 4200
+                },
+                TimelineWarning::PastEnd {
+                    timestamp: 5400,
+                    video_length_seconds: 3600
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use chrono::{DateTime, Utc};
+use compact_str::CompactString;
+use reqwest::StatusCode;
+
+use super::models::error::ErrorResponse;
+
+#[derive(Debug, thiserror::Error)]
+pub enum YoutubeApiError {
+    #[error("YouTube API quota exceeded until {resets_at}")]
+    QuotaExceeded { resets_at: DateTime<Utc> },
+    #[error("YouTube API key is invalid")]
+    KeyInvalid,
+    #[error("YouTube resource not found: {0}")]
+    NotFound(CompactString),
+    #[error("YouTube API responded with {status}: {message}")]
+    Api {
+        status: StatusCode,
+        reason: Option<CompactString>,
+        message: CompactString,
+    },
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error(transparent)]
+    Decode(#[from] serde_json::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+impl YoutubeApiError {
+    /// Classifies an error response by the reasons in its body, falling back to the status code.
+    pub fn from_response(status: StatusCode, body: &[u8], resets_at: DateTime<Utc>) -> Self {
+        let error = serde_json::from_slice::<ErrorResponse>(body)
+            .unwrap_or_default()
+            .error;
+
+        let reasons = error.reasons().collect::<Vec<_>>();
+        let has_reason = |matches: &dyn Fn(&str) -> bool| reasons.iter().any(|r| matches(r));
+
+        if has_reason(&|r| matches!(r, "quotaExceeded" | "dailyLimitExceeded")) {
+            return Self::QuotaExceeded { resets_at };
+        }
+
+        if has_reason(&|r| matches!(r, "keyInvalid" | "API_KEY_INVALID")) {
+            return Self::KeyInvalid;
+        }
+
+        // e.g. playlistNotFound, videoNotFound
+        if status == StatusCode::NOT_FOUND
+            || has_reason(&|r| r == "notFound" || r.ends_with("NotFound"))
+        {
+            return Self::NotFound(error.message.clone());
+        }
+
+        Self::Api {
+            status,
+            reason: reasons.first().map(|r| CompactString::from(*r)),
+            message: error.message.clone(),
+        }
+    }
+
+    /// Failures worth retrying after a short wait.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Api { status, reason, .. } => {
+                status.is_server_error()
+                    || *status == StatusCode::TOO_MANY_REQUESTS
+                    || matches!(
+                        reason.as_deref(),
+                        Some("rateLimitExceeded" | "userRateLimitExceeded" | "backendError")
+                    )
+            }
+            Self::Http(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            _ => false,
+        }
+    }
+
+    /// When quota ran out somewhere in `error`'s chain, the time it resets.
+    pub fn quota_reset(error: &anyhow::Error) -> Option<DateTime<Utc>> {
+        error.chain().find_map(|e| match e.downcast_ref::<Self>() {
+            Some(Self::QuotaExceeded { resets_at }) => Some(*resets_at),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(status: u16, body: &str) -> YoutubeApiError {
+        YoutubeApiError::from_response(
+            StatusCode::from_u16(status).unwrap(),
+            body.as_bytes(),
+            DateTime::UNIX_EPOCH,
+        )
+    }
+
+    #[test]
+    fn classify_error_payloads() {
+        let quota = r#"{"error":{"code":403,"message":"The request cannot be completed because you have exceeded your quota.","errors":[{"message":"The request cannot be completed because you have exceeded your quota.","domain":"youtube.quota","reason":"quotaExceeded"}]}}"#;
+        assert!(matches!(
+            classify(403, quota),
+            YoutubeApiError::QuotaExceeded { .. }
+        ));
+
+        let key = r#"{"error":{"code":400,"message":"API key not valid. Please pass a valid API key.","errors":[{"message":"API key not valid. Please pass a valid API key.","domain":"global","reason":"badRequest"}],"status":"INVALID_ARGUMENT","details":[{"@type":"type.googleapis.com/google.rpc.ErrorInfo","reason":"API_KEY_INVALID","domain":"googleapis.com"}]}}"#;
+        assert!(matches!(classify(400, key), YoutubeApiError::KeyInvalid));
+
+        let playlist = r#"{"error":{"code":404,"message":"The playlist identified with the request's <code>playlistId</code> parameter cannot be found.","errors":[{"domain":"youtube.playlistItem","reason":"playlistNotFound"}]}}"#;
+        assert!(matches!(
+            classify(404, playlist),
+            YoutubeApiError::NotFound(_)
+        ));
+
+        let backend = r#"{"error":{"code":503,"message":"The service is currently unavailable.","errors":[{"reason":"backendError"}]}}"#;
+        let e = classify(503, backend);
+        assert!(matches!(e, YoutubeApiError::Api { .. }));
+        assert!(e.is_transient());
+
+        let bad_request = classify(400, "not json");
+        assert!(!bad_request.is_transient());
+    }
+
+    #[test]
+    fn quota_reset_through_context() {
+        let resets_at = DateTime::UNIX_EPOCH;
+        let error = anyhow::Error::new(YoutubeApiError::QuotaExceeded { resets_at })
+            .context("Failed to fetch video details");
+
+        assert_eq!(YoutubeApiError::quota_reset(&error), Some(resets_at));
+        assert_eq!(
+            YoutubeApiError::quota_reset(&anyhow::anyhow!("other")),
+            None
+        );
+    }
+}
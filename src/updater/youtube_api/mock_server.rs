@@ -0,0 +1,282 @@
+//! Local stand-in for the YouTube Data API, feed and watch pages that serves the responses in
+//! `fixtures/`.
+//!
+//! TODO: The fixtures are still hand-written in the shape of real responses, with made-up video
+//! ids. Replace them by running the recorder in `record.rs` with a real key, then update the
+//! assertions that depend on their videos.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use super::YoutubeApi;
+use crate::updater::youtube_feed::YoutubeFeed;
+
+pub const API_KEY: &str = "test-api-key";
+
+#[derive(Clone)]
+pub struct Fixtures {
+    pub playlist_pages: Vec<Value>,
+    pub videos: Vec<Value>,
+}
+
+impl Fixtures {
+    pub fn recorded() -> Self {
+        let parse = |raw: &str| serde_json::from_str::<Value>(raw).expect("Invalid fixture");
+
+        let videos = parse(include_str!("fixtures/videos.json"))["items"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+
+        Self {
+            playlist_pages: vec![
+                parse(include_str!("fixtures/playlist_items_page_1.json")),
+                parse(include_str!("fixtures/playlist_items_page_2.json")),
+            ],
+            videos,
+        }
+    }
+
+    pub fn set_description(&mut self, video_id: &str, description: &str) {
+        for video in &mut self.videos {
+            if video["id"] == video_id {
+                video["snippet"]["description"] = description.into();
+            }
+        }
+    }
+}
+
+/// Status and YouTube error reason to answer with.
+type Failure = (StatusCode, &'static str);
+
+#[derive(Clone)]
+struct MockState {
+    fixtures: Fixtures,
+    failures: Arc<Mutex<HashMap<&'static str, VecDeque<Failure>>>>,
+    requests: Arc<Mutex<HashMap<&'static str, usize>>>,
+}
+
+impl MockState {
+    /// Counts the request and answers with the next queued failure for `path`, if any.
+    fn intercept(&self, path: &'static str) -> Result<(), (StatusCode, Json<Value>)> {
+        *self.requests.lock().unwrap().entry(path).or_default() += 1;
+
+        let failure = self
+            .failures
+            .lock()
+            .unwrap()
+            .get_mut(path)
+            .and_then(VecDeque::pop_front);
+
+        match failure {
+            Some((status, reason)) => Err(error_response(status, reason)),
+            None => Ok(()),
+        }
+    }
+}
+
+pub struct MockYoutube {
+    base_url: String,
+    state: MockState,
+    handle: JoinHandle<()>,
+}
+
+impl MockYoutube {
+    pub async fn start(fixtures: Fixtures) -> Self {
+        let state = MockState {
+            fixtures,
+            failures: Arc::default(),
+            requests: Arc::default(),
+        };
+
+        let app = Router::new()
+            .route("/playlistItems", get(playlist_items))
+            .route("/videos", get(videos))
+            .route("/feeds/videos.xml", get(feed))
+            .route("/watch", get(watch_page))
+            .with_state(state.clone());
+
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind mock server");
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        Self {
+            base_url,
+            state,
+            handle,
+        }
+    }
+
+    /// Makes the next request to `path` (e.g. "/videos") fail with a YouTube error payload.
+    pub fn fail_next(&self, path: &'static str, status: StatusCode, reason: &'static str) {
+        self.state
+            .failures
+            .lock()
+            .unwrap()
+            .entry(path)
+            .or_default()
+            .push_back((status, reason));
+    }
+
+    pub fn requests(&self, path: &'static str) -> usize {
+        self.state
+            .requests
+            .lock()
+            .unwrap()
+            .get(path)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn api(&self) -> YoutubeApi {
+        YoutubeApi::new(&self.base_url, API_KEY).unwrap()
+    }
+
+    pub fn feed(&self) -> YoutubeFeed {
+        YoutubeFeed::new(&self.base_url).unwrap()
+    }
+}
+
+impl Drop for MockYoutube {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+type MockResponse = Result<Json<Value>, (StatusCode, Json<Value>)>;
+
+fn error_response(status: StatusCode, reason: &str) -> (StatusCode, Json<Value>) {
+    let body = json!({
+        "error": {
+            "code": status.as_u16(),
+            "message": format!("Mock failure: {reason}"),
+            "errors": [{ "message": format!("Mock failure: {reason}"), "domain": "youtube", "reason": reason }]
+        }
+    });
+
+    (status, Json(body))
+}
+
+fn check_key(params: &HashMap<String, String>) -> Result<(), (StatusCode, Json<Value>)> {
+    if params.get("key").map(String::as_str) == Some(API_KEY) {
+        return Ok(());
+    }
+
+    let body = serde_json::from_str(include_str!("fixtures/error_invalid_key.json"))
+        .expect("Invalid fixture");
+
+    Err((StatusCode::BAD_REQUEST, Json(body)))
+}
+
+async fn playlist_items(
+    State(state): State<MockState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> MockResponse {
+    state.intercept("/playlistItems")?;
+    check_key(&params)?;
+
+    let fixtures = state.fixtures;
+
+    // Each page's nextPageToken leads to the page after it.
+    let index = match params.get("pageToken") {
+        None => 0,
+        Some(token) => {
+            fixtures
+                .playlist_pages
+                .iter()
+                .position(|page| page["nextPageToken"] == token.as_str())
+                .ok_or((StatusCode::BAD_REQUEST, Json(json!({}))))?
+                + 1
+        }
+    };
+
+    let mut page = fixtures.playlist_pages[index].clone();
+
+    if let Some(max_results) = params.get("maxResults").and_then(|m| m.parse().ok()) {
+        if let Some(items) = page["items"].as_array_mut() {
+            items.truncate(max_results);
+        }
+    }
+
+    Ok(Json(page))
+}
+
+async fn videos(
+    State(state): State<MockState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> MockResponse {
+    state.intercept("/videos")?;
+    check_key(&params)?;
+
+    let fixtures = state.fixtures;
+
+    let ids = params.get("id").map(String::as_str).unwrap_or_default();
+
+    let items = ids
+        .split(',')
+        .filter_map(|id| fixtures.videos.iter().find(|v| v["id"] == id))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    Ok(Json(
+        json!({ "kind": "youtube#videoListResponse", "items": items }),
+    ))
+}
+
+async fn feed() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "application/atom+xml; charset=UTF-8")],
+        include_str!("fixtures/feed.xml"),
+    )
+}
+
+async fn watch_page(
+    State(MockState { fixtures, .. }): State<MockState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Html<String>, StatusCode> {
+    let video_id = params.get("v").ok_or(StatusCode::BAD_REQUEST)?;
+
+    let video = fixtures
+        .videos
+        .iter()
+        .find(|v| v["id"] == video_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let length_seconds = video["contentDetails"]["duration"]
+        .as_str()
+        .and_then(|d| iso8601_duration::Duration::parse(d).ok())
+        .and_then(|d| d.to_chrono())
+        .map(|d| d.num_seconds())
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    // Keys are written by hand to keep the field order of a real watch page.
+    let player_response = format!(
+        r#"{{"playabilityStatus":{{"status":"OK"}},"videoDetails":{{"videoId":{},"title":{},"lengthSeconds":"{length_seconds}","channelId":{},"shortDescription":{},"isCrawlable":true}}}}"#,
+        json!(video_id),
+        video["snippet"]["title"],
+        video["snippet"]["channelId"],
+        video["snippet"]["description"],
+    );
+
+    Ok(Html(format!(
+        "<html><body><script>var ytInitialPlayerResponse = {player_response};</script></body></html>"
+    )))
+}
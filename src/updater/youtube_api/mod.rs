@@ -1,20 +1,43 @@
+pub mod error;
+#[cfg(test)]
+pub mod mock_server;
 pub mod models;
+mod pagination;
+pub mod quota;
+#[cfg(test)]
+mod record;
 
 use std::time::Duration;
 
+use anyhow::ensure;
+use chrono::Utc;
 use compact_str::ToCompactString;
 use reqwest::Client;
 use reqwest::ClientBuilder;
+use serde::de::DeserializeOwned;
 use strum_macros::Display;
+use tracing::warn;
 
-use crate::models::errors::ApiError;
-use crate::YT_API_KEY;
+use crate::config::Config;
+use crate::Repo;
 
-use self::models::playlist_items::PlaylistItemsResponse;
+use self::error::YoutubeApiError;
+use self::models::playlist_items::{PlaylistItem, PlaylistItemsResponse};
 use self::models::videos::{VideosItem, VideosResponse};
+use self::pagination::UploadsCollector;
+use self::quota::{next_reset, QuotaTracker};
 
+const MAX_ATTEMPTS: u32 = 4;
+const BASE_RETRY_DELAY: Duration = Duration::from_millis(500);
+/// playlistItems.list and videos.list both cost a single quota unit.
+const LIST_COST: i64 = 1;
+
+#[derive(Clone)]
 pub struct YoutubeApi {
     client: Client,
+    base_url: String,
+    api_key: String,
+    quota: Option<QuotaTracker>,
 }
 
 // https://developers.google.com/youtube/v3/docs explains what these mean
@@ -26,20 +49,65 @@ enum Part {
 }
 
 impl YoutubeApi {
-    pub fn new() -> anyhow::Result<Self> {
+    pub const MAX_IDS_PER_REQUEST: usize = 50;
+
+    pub fn new(base_url: impl Into<String>, api_key: impl Into<String>) -> anyhow::Result<Self> {
         let client = ClientBuilder::new()
             .connect_timeout(Duration::from_secs(10))
             .timeout(Duration::from_secs(10))
             .gzip(true)
             .build()?;
 
-        Ok(YoutubeApi { client })
+        Ok(YoutubeApi {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+            api_key: api_key.into(),
+            quota: None,
+        })
+    }
+
+    pub fn from_config(config: &Config, db: &Repo) -> anyhow::Result<Self> {
+        let quota = QuotaTracker::new(db.clone(), config.youtube_daily_quota);
+
+        Ok(Self::new(&config.youtube_api_url, &config.yt_api_key)?.with_quota(quota))
+    }
+
+    pub fn with_quota(mut self, quota: QuotaTracker) -> Self {
+        self.quota = Some(quota);
+        self
+    }
+
+    /// Pages through the playlist newest first, stopping before the first video
+    /// `is_known` accepts or once `max_results` items are collected.
+    pub async fn get_uploads_until_known(
+        &self,
+        max_results: usize,
+        playlist_id: &str,
+        is_known: impl Fn(&str) -> bool,
+    ) -> anyhow::Result<Vec<PlaylistItem>> {
+        let mut collector = UploadsCollector::new(max_results, is_known);
+        let mut page_token = None;
+
+        loop {
+            let page = self
+                .get_playlist_page(collector.page_size(), playlist_id, page_token.as_deref())
+                .await?;
+
+            page_token = collector.push_page(page);
+
+            if page_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(collector.into_items())
     }
 
-    pub async fn get_latest_uploads(
+    async fn get_playlist_page(
         &self,
         max_results: usize,
         playlist_id: &str,
+        page_token: Option<&str>,
     ) -> anyhow::Result<PlaylistItemsResponse> {
         let parts = [Part::Snippet];
 
@@ -49,23 +117,38 @@ impl YoutubeApi {
             .collect::<Vec<_>>()
             .join(",");
 
-        let api_key = YT_API_KEY.get().await;
-
-        let endpoint = format!(
-            "https://www.googleapis.com/youtube/v3/playlistItems?part={}&maxResults={}&playlistId={}&key={}",
-            part,
-            max_results,
-            playlist_id,
-            api_key,
+        let mut endpoint = format!(
+            "{}/playlistItems?part={}&maxResults={}&playlistId={}&key={}",
+            self.base_url, part, max_results, playlist_id, self.api_key,
         );
 
-        let res = self.client.get(&endpoint).send().await?;
-        let data = serde_json::from_slice::<PlaylistItemsResponse>(&res.bytes().await?)?;
+        if let Some(page_token) = page_token {
+            endpoint.push_str("&pageToken=");
+            endpoint.push_str(page_token);
+        }
 
-        Ok(data)
+        Ok(self.request(&endpoint).await?)
     }
 
     pub async fn get_video_details(&self, video_id: &str) -> anyhow::Result<VideosItem> {
+        let data = self
+            .get_videos(&[video_id])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| YoutubeApiError::NotFound(video_id.to_compact_string()))?;
+
+        Ok(data)
+    }
+
+    /// Videos that are private or removed are left out of the response.
+    pub async fn get_videos(&self, video_ids: &[&str]) -> anyhow::Result<Vec<VideosItem>> {
+        ensure!(
+            video_ids.len() <= Self::MAX_IDS_PER_REQUEST,
+            "Cannot request more than {} videos at once",
+            Self::MAX_IDS_PER_REQUEST
+        );
+
         let parts = [Part::ContentDetails, Part::Snippet];
 
         let part = parts
@@ -74,21 +157,125 @@ impl YoutubeApi {
             .collect::<Vec<_>>()
             .join(",");
 
-        let api_key = YT_API_KEY.get().await;
-
         let endpoint = format!(
-            "https://www.googleapis.com/youtube/v3/videos?part={}&id={}&key={}",
-            part, video_id, api_key,
+            "{}/videos?part={}&id={}&maxResults={}&key={}",
+            self.base_url,
+            part,
+            video_ids.join(","),
+            Self::MAX_IDS_PER_REQUEST,
+            self.api_key,
         );
 
-        let res = self.client.get(&endpoint).send().await?;
+        let data = self.request::<VideosResponse>(&endpoint).await?;
+
+        Ok(data.items)
+    }
+
+    /// Retries transient failures with exponential backoff and jitter.
+    async fn request<T: DeserializeOwned>(&self, endpoint: &str) -> Result<T, YoutubeApiError> {
+        let mut attempt = 1;
+
+        loop {
+            match self.try_request(endpoint).await {
+                Err(e) if e.is_transient() && attempt < MAX_ATTEMPTS => {
+                    let delay = retry_delay(attempt);
+
+                    warn!("YouTube request failed (attempt {attempt}/{MAX_ATTEMPTS}), retrying in {delay:?}: {e}");
+
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    async fn try_request<T: DeserializeOwned>(&self, endpoint: &str) -> Result<T, YoutubeApiError> {
+        if let Some(quota) = &self.quota {
+            quota.spend(LIST_COST).await?;
+        }
+
+        let res = self.client.get(endpoint).send().await?;
+        let status = res.status();
+        let body = res.bytes().await?;
+
+        if !status.is_success() {
+            let error = YoutubeApiError::from_response(status, &body, next_reset(Utc::now()));
+
+            if let (YoutubeApiError::QuotaExceeded { .. }, Some(quota)) = (&error, &self.quota) {
+                quota.exhaust().await?;
+            }
+
+            return Err(error);
+        }
+
+        Ok(serde_json::from_slice(&body)?)
+    }
+}
+
+fn retry_delay(attempt: u32) -> Duration {
+    let delay = BASE_RETRY_DELAY * 2u32.pow(attempt - 1);
+    let jitter = rand::random_range(0..=delay.as_millis() as u64);
+
+    delay + Duration::from_millis(jitter)
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::StatusCode;
+
+    use super::mock_server::{Fixtures, MockYoutube};
+    use super::*;
+
+    #[tokio::test]
+    async fn retries_transient_failures() {
+        let mock = MockYoutube::start(Fixtures::recorded()).await;
+        mock.fail_next("/videos", StatusCode::SERVICE_UNAVAILABLE, "backendError");
+
+        let details = mock.api().get_video_details("vidPKA703").await.unwrap();
+
+        assert_eq!(details.content_details.duration, 9900);
+        assert_eq!(mock.requests("/videos"), 2);
+    }
+
+    #[tokio::test]
+    async fn typed_errors_are_not_retried() {
+        let mock = MockYoutube::start(Fixtures::recorded()).await;
+
+        let e = mock.api().get_video_details("missing").await.unwrap_err();
+        assert!(matches!(
+            e.downcast_ref::<YoutubeApiError>(),
+            Some(YoutubeApiError::NotFound(_))
+        ));
+
+        let api = YoutubeApi::new(mock.base_url(), "wrong-key").unwrap();
+        let e = api.get_video_details("vidPKA703").await.unwrap_err();
+        assert!(matches!(
+            e.downcast_ref::<YoutubeApiError>(),
+            Some(YoutubeApiError::KeyInvalid)
+        ));
+
+        assert_eq!(mock.requests("/videos"), 2);
+    }
+
+    #[tokio::test]
+    async fn quota_exceeded_stops_further_requests() {
+        let db = crate::db::test_pool("quota_exceeded_stops_further_requests").await;
+        let mock = MockYoutube::start(Fixtures::recorded()).await;
+        mock.fail_next("/videos", StatusCode::FORBIDDEN, "quotaExceeded");
+
+        let api = mock.api().with_quota(QuotaTracker::new(db.clone(), 10_000));
+
+        for _ in 0..2 {
+            let e = api.get_video_details("vidPKA703").await.unwrap_err();
+            assert!(YoutubeApiError::quota_reset(&e).is_some());
+        }
 
-        let full_data = serde_json::from_slice::<VideosResponse>(&res.bytes().await?)?;
-        let data = full_data
-            .items
-            .first()
-            .ok_or_else(|| ApiError::new_internal_error("Couldn't find video in item list."))?;
+        // The second attempt never reached YouTube, even from a fresh client.
+        assert_eq!(mock.requests("/videos"), 1);
 
-        Ok(data.to_owned())
+        let api = mock.api().with_quota(QuotaTracker::new(db, 10_000));
+        assert!(api.get_video_details("vidPKA703").await.is_err());
+        assert_eq!(mock.requests("/videos"), 1);
     }
 }
@@ -0,0 +1,40 @@
+use compact_str::CompactString;
+use serde::Deserialize;
+
+/// Body YouTube sends with non-2xx responses.
+#[derive(Default, Debug, Clone, Deserialize)]
+pub struct ErrorResponse {
+    pub error: ErrorBody,
+}
+
+#[derive(Default, Debug, Clone, Deserialize)]
+pub struct ErrorBody {
+    #[serde(default)]
+    pub message: CompactString,
+    #[serde(default)]
+    pub errors: Vec<ErrorItem>,
+    #[serde(default)]
+    pub details: Vec<ErrorDetail>,
+}
+
+#[derive(Default, Debug, Clone, Deserialize)]
+pub struct ErrorItem {
+    #[serde(default)]
+    pub reason: CompactString,
+}
+
+#[derive(Default, Debug, Clone, Deserialize)]
+pub struct ErrorDetail {
+    #[serde(default)]
+    pub reason: Option<CompactString>,
+}
+
+impl ErrorBody {
+    /// Reasons from both the legacy `errors` list and the newer `details` entries.
+    pub fn reasons(&self) -> impl Iterator<Item = &str> {
+        self.errors
+            .iter()
+            .map(|e| e.reason.as_str())
+            .chain(self.details.iter().filter_map(|d| d.reason.as_deref()))
+    }
+}
@@ -1,2 +1,3 @@
+pub mod error;
 pub mod playlist_items;
 pub mod videos;
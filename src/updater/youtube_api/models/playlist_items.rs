@@ -6,6 +6,9 @@ use serde::{de, Deserialize, Deserializer, Serialize};
 #[serde(rename_all = "camelCase")]
 pub struct PlaylistItemsResponse {
     pub items: Vec<PlaylistItem>,
+    /// Present while there are more pages after this one.
+    #[serde(default)]
+    pub next_page_token: Option<CompactString>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
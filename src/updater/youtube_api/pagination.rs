@@ -0,0 +1,120 @@
+use compact_str::CompactString;
+
+use super::models::playlist_items::{PlaylistItem, PlaylistItemsResponse};
+
+/// The playlistItems API returns at most this many items per page.
+pub const MAX_PAGE_SIZE: usize = 50;
+
+/// Accumulates playlist pages until enough items are collected, an already known
+/// video is reached or the playlist runs out of pages.
+pub struct UploadsCollector<F> {
+    items: Vec<PlaylistItem>,
+    max_results: usize,
+    is_known: F,
+}
+
+impl<F> UploadsCollector<F>
+where
+    F: Fn(&str) -> bool,
+{
+    pub fn new(max_results: usize, is_known: F) -> Self {
+        Self {
+            items: Vec::new(),
+            max_results,
+            is_known,
+        }
+    }
+
+    /// Size to request for the next page.
+    pub fn page_size(&self) -> usize {
+        (self.max_results - self.items.len()).clamp(1, MAX_PAGE_SIZE)
+    }
+
+    /// Adds a page and returns the token for the next one, or `None` once collection is finished.
+    pub fn push_page(&mut self, page: PlaylistItemsResponse) -> Option<CompactString> {
+        for item in page.items {
+            if self.items.len() >= self.max_results
+                || (self.is_known)(&item.snippet.resource_id.video_id)
+            {
+                return None;
+            }
+
+            self.items.push(item);
+        }
+
+        if self.items.len() >= self.max_results {
+            return None;
+        }
+
+        page.next_page_token
+    }
+
+    pub fn into_items(self) -> Vec<PlaylistItem> {
+        self.items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(n: usize) -> PlaylistItemsResponse {
+        let raw = match n {
+            1 => include_str!("fixtures/playlist_items_page_1.json"),
+            2 => include_str!("fixtures/playlist_items_page_2.json"),
+            _ => unreachable!(),
+        };
+
+        serde_json::from_str(raw).unwrap()
+    }
+
+    fn video_ids(items: &[PlaylistItem]) -> Vec<&str> {
+        items
+            .iter()
+            .map(|i| i.snippet.resource_id.video_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn follows_next_page_token() {
+        let mut collector = UploadsCollector::new(100, |_| false);
+
+        assert_eq!(
+            collector.push_page(page(1)).as_deref(),
+            Some("EAAaBlBUOkNESQ")
+        );
+        assert_eq!(collector.push_page(page(2)), None);
+        assert_eq!(collector.into_items().len(), 6);
+    }
+
+    #[test]
+    fn stops_at_known_video() {
+        let mut collector = UploadsCollector::new(100, |id| id == "vidPKA700");
+
+        assert!(collector.push_page(page(1)).is_some());
+        assert_eq!(collector.push_page(page(2)), None);
+        assert_eq!(
+            video_ids(&collector.into_items()),
+            vec!["vidPKA703", "vidShort01", "vidPKA702", "vidPKA701"]
+        );
+    }
+
+    #[test]
+    fn stops_at_max_results() {
+        let mut collector = UploadsCollector::new(2, |_| false);
+
+        assert_eq!(collector.page_size(), 2);
+        assert_eq!(collector.push_page(page(1)), None);
+        assert_eq!(
+            video_ids(&collector.into_items()),
+            vec!["vidPKA703", "vidShort01"]
+        );
+    }
+
+    #[test]
+    fn page_size_is_capped() {
+        let collector = UploadsCollector::new(500, |_| false);
+
+        assert_eq!(collector.page_size(), MAX_PAGE_SIZE);
+    }
+}
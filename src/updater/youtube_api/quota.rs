@@ -0,0 +1,101 @@
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, Utc};
+
+use crate::conduit::sqlite::youtube_quota;
+use crate::Repo;
+
+use super::error::YoutubeApiError;
+
+/// YouTube resets quota at midnight Pacific time. A fixed UTC-8 offset means the reset
+/// is seen an hour late during daylight saving time, which only errs on the safe side.
+const PACIFIC_OFFSET_SECONDS: i32 = -8 * 3600;
+
+fn pacific() -> FixedOffset {
+    FixedOffset::east_opt(PACIFIC_OFFSET_SECONDS).expect("Invalid pacific offset")
+}
+
+pub fn quota_day(now: DateTime<Utc>) -> NaiveDate {
+    now.with_timezone(&pacific()).date_naive()
+}
+
+pub fn next_reset(now: DateTime<Utc>) -> DateTime<Utc> {
+    let tomorrow = quota_day(now) + Duration::days(1);
+
+    tomorrow
+        .and_hms_opt(0, 0, 0)
+        .and_then(|t| t.and_local_timezone(pacific()).single())
+        .map(|t| t.with_timezone(&Utc))
+        .expect("Invalid quota reset time")
+}
+
+/// Tracks quota units spent per day in the database so restarts don't forget usage.
+#[derive(Clone)]
+pub struct QuotaTracker {
+    db: Repo,
+    daily_limit: i64,
+}
+
+impl QuotaTracker {
+    pub fn new(db: Repo, daily_limit: i64) -> Self {
+        Self { db, daily_limit }
+    }
+
+    /// Reserves `units` for a request, failing without calling YouTube when the day's budget is spent.
+    pub async fn spend(&self, units: i64) -> Result<(), YoutubeApiError> {
+        let now = Utc::now();
+        let day = quota_day(now).to_string();
+
+        if youtube_quota::try_spend(&self.db, &day, units, self.daily_limit).await? {
+            Ok(())
+        } else {
+            Err(YoutubeApiError::QuotaExceeded {
+                resets_at: next_reset(now),
+            })
+        }
+    }
+
+    pub async fn exhaust(&self) -> Result<(), YoutubeApiError> {
+        let day = quota_day(Utc::now()).to_string();
+
+        youtube_quota::exhaust(&self.db, &day, self.daily_limit).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn quota_day_and_reset() {
+        // 07:59 UTC is still the previous day in the pacific.
+        let now = utc("2024-03-08T07:59:00Z");
+        assert_eq!(quota_day(now).to_string(), "2024-03-07");
+        assert_eq!(next_reset(now), utc("2024-03-08T08:00:00Z"));
+
+        let now = utc("2024-03-08T08:00:00Z");
+        assert_eq!(quota_day(now).to_string(), "2024-03-08");
+        assert_eq!(next_reset(now), utc("2024-03-09T08:00:00Z"));
+    }
+
+    #[tokio::test]
+    async fn spend_within_daily_limit() {
+        let db = crate::db::test_pool("spend_within_daily_limit").await;
+        let tracker = QuotaTracker::new(db, 3);
+
+        tracker.spend(2).await.unwrap();
+        tracker.spend(1).await.unwrap();
+        assert!(matches!(
+            tracker.spend(1).await,
+            Err(YoutubeApiError::QuotaExceeded { .. })
+        ));
+
+        let tracker = QuotaTracker::new(tracker.db, 10);
+        tracker.exhaust().await.unwrap();
+        assert!(tracker.spend(1).await.is_err());
+    }
+}
@@ -0,0 +1,153 @@
+//! Refreshes the responses in `fixtures/` from the live YouTube Data API and channel feed. It
+//! needs a real key and network access, so it only runs when asked for:
+//!
+//! `YT_API_KEY=... cargo test record_youtube_fixtures -- --ignored`
+//!
+//! Responses are written as returned, trimmed only through `maxResults`. The tests built on the
+//! mock server assert on the recorded videos, so review them after recording.
+//!
+//! `record_description_corpus` does the same for the timeline parser's corpus: it saves the
+//! descriptions of a spread of episodes from `pka_episode_list.json` to
+//! `updater/timeline/fixtures/recorded/` to pick excerpts from.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use super::YoutubeApi;
+use crate::models::updater::EpisodesFileRoot;
+
+const API_URL: &str = "https://www.googleapis.com/youtube/v3";
+const FEED_URL: &str = "https://www.youtube.com/feeds/videos.xml";
+
+/// Uploads playlist of the PKA channel, as seeded in the `show` table.
+const PLAYLIST_ID: &str = "UUIPVJoHb_A5S3kcv3TJlyEg";
+
+/// Videos kept per recorded playlist page.
+const PAGE_SIZE: usize = 3;
+
+/// Every this many episodes in the episode list gets its description recorded, so the corpus
+/// covers each era's description layout.
+const CORPUS_STRIDE: usize = 10;
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src/updater/youtube_api/fixtures")
+}
+
+async fn get(client: &reqwest::Client, url: &str) -> (reqwest::StatusCode, String) {
+    let response = client.get(url).send().await.expect("Request failed");
+    let status = response.status();
+
+    (
+        status,
+        response.text().await.expect("Failed to read response"),
+    )
+}
+
+fn write_json(name: &str, body: &str) -> Value {
+    let value = serde_json::from_str::<Value>(body).expect("Response isn't JSON");
+
+    let pretty = serde_json::to_string_pretty(&value).unwrap();
+    std::fs::write(fixtures_dir().join(name), pretty + "\n").expect("Failed to write fixture");
+
+    value
+}
+
+#[tokio::test]
+#[ignore = "records live YouTube responses"]
+async fn record_youtube_fixtures() {
+    let key = std::env::var("YT_API_KEY").expect("YT_API_KEY must be set to record fixtures");
+    let client = reqwest::Client::new();
+
+    let playlist_items = |page_token: Option<&str>| {
+        let mut url = format!(
+            "{API_URL}/playlistItems?part=snippet&playlistId={PLAYLIST_ID}&maxResults={PAGE_SIZE}&key={key}"
+        );
+
+        if let Some(token) = page_token {
+            url.push_str(&format!("&pageToken={token}"));
+        }
+
+        url
+    };
+
+    let (status, body) = get(&client, &playlist_items(None)).await;
+    assert!(status.is_success(), "Playlist: {status} {body}");
+    let page_1 = write_json("playlist_items_page_1.json", &body);
+
+    let token = page_1["nextPageToken"]
+        .as_str()
+        .expect("Missing nextPageToken");
+
+    let (status, body) = get(&client, &playlist_items(Some(token))).await;
+    assert!(status.is_success(), "Second page: {status} {body}");
+    let page_2 = write_json("playlist_items_page_2.json", &body);
+
+    let ids = [&page_1, &page_2]
+        .iter()
+        .flat_map(|page| page["items"].as_array().cloned().unwrap_or_default())
+        .filter_map(|item| {
+            item["snippet"]["resourceId"]["videoId"]
+                .as_str()
+                .map(str::to_owned)
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let (status, body) = get(
+        &client,
+        &format!("{API_URL}/videos?part=contentDetails,snippet&id={ids}&key={key}"),
+    )
+    .await;
+    assert!(status.is_success(), "Videos: {status} {body}");
+    write_json("videos.json", &body);
+
+    let (status, body) = get(
+        &client,
+        &format!("{API_URL}/videos?part=snippet&id={ids}&key=invalid"),
+    )
+    .await;
+    assert_eq!(status, reqwest::StatusCode::BAD_REQUEST, "{body}");
+    write_json("error_invalid_key.json", &body);
+
+    let (status, body) = get(&client, &format!("{FEED_URL}?playlist_id={PLAYLIST_ID}")).await;
+    assert!(status.is_success(), "Feed: {status}");
+    std::fs::write(fixtures_dir().join("feed.xml"), body).expect("Failed to write fixture");
+}
+
+#[tokio::test]
+#[ignore = "records live YouTube descriptions"]
+async fn record_description_corpus() {
+    let key = std::env::var("YT_API_KEY").expect("YT_API_KEY must be set to record fixtures");
+    let api = YoutubeApi::new(API_URL, key).unwrap();
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let list = std::fs::read_to_string(root.join("pka_episode_list.json")).unwrap();
+    let episodes = serde_json::from_str::<EpisodesFileRoot>(&list)
+        .unwrap()
+        .episodes;
+
+    let dir = root.join("src/updater/timeline/fixtures/recorded");
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let sample = episodes.iter().step_by(CORPUS_STRIDE).collect::<Vec<_>>();
+
+    for chunk in sample.chunks(YoutubeApi::MAX_IDS_PER_REQUEST) {
+        let ids = chunk
+            .iter()
+            .map(|e| e.you_tube.as_str())
+            .collect::<Vec<_>>();
+
+        for video in api.get_videos(&ids).await.unwrap() {
+            let Some(episode) = chunk.iter().find(|e| e.you_tube == video.id) else {
+                continue;
+            };
+
+            std::fs::write(
+                dir.join(format!("{}.txt", episode.identifier)),
+                video.snippet.description.as_str(),
+            )
+            .unwrap();
+        }
+    }
+}
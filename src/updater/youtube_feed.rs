@@ -0,0 +1,171 @@
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use anyhow::Context;
+use compact_str::{CompactString, ToCompactString};
+use regex::Regex;
+use reqwest::{Client, ClientBuilder};
+use tracing::warn;
+
+use crate::config::Config;
+use crate::models::rss_feed::YoutubeRssFeed;
+use crate::updater::youtube_api::models::playlist_items::{self, PlaylistItem, ResourceId};
+use crate::updater::youtube_api::models::videos::{self, ContentDetails, VideosItem};
+
+static TITLE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#""videoDetails":\{"videoId":"[^"]*","title":"((?:[^"\\]|\\.)*)""#)
+        .expect("Failed to create TITLE_REGEX")
+});
+
+static LENGTH_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#""lengthSeconds":"(\d+)""#).expect("Failed to create LENGTH_REGEX")
+});
+
+static DESCRIPTION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#""shortDescription":"((?:[^"\\]|\\.)*)""#)
+        .expect("Failed to create DESCRIPTION_REGEX")
+});
+
+/// Keyless access to YouTube through the public Atom feed and watch pages. The feed
+/// only lists the most recent uploads, so this is a fallback for the Data API.
+#[derive(Clone)]
+pub struct YoutubeFeed {
+    client: Client,
+    base_url: String,
+}
+
+impl YoutubeFeed {
+    pub fn new(base_url: impl Into<String>) -> anyhow::Result<Self> {
+        let client = ClientBuilder::new()
+            .connect_timeout(Duration::from_secs(10))
+            .timeout(Duration::from_secs(10))
+            .gzip(true)
+            .build()?;
+
+        Ok(YoutubeFeed {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+        })
+    }
+
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        Self::new(&config.youtube_feed_url)
+    }
+
+    /// Feed entries newest first, stopping before the first video `is_known` accepts
+    /// or once `max_results` items are collected.
+    pub async fn get_uploads_until_known(
+        &self,
+        max_results: usize,
+        playlist_id: &str,
+        is_known: impl Fn(&str) -> bool,
+    ) -> anyhow::Result<Vec<PlaylistItem>> {
+        let endpoint = format!(
+            "{}/feeds/videos.xml?playlist_id={}",
+            self.base_url, playlist_id
+        );
+
+        let res = self
+            .client
+            .get(&endpoint)
+            .send()
+            .await?
+            .error_for_status()?;
+        let feed = quick_xml::de::from_str::<YoutubeRssFeed>(&res.text().await?)?;
+
+        let mut items = Vec::new();
+
+        for entry in feed.entry() {
+            if items.len() >= max_results {
+                return Ok(items);
+            }
+
+            if is_known(entry.video_id()) {
+                return Ok(items);
+            }
+
+            items.push(PlaylistItem {
+                snippet: playlist_items::Snippet {
+                    published_at: entry.published().timestamp(),
+                    title: entry.title().to_compact_string(),
+                    resource_id: ResourceId {
+                        video_id: entry.video_id().to_compact_string(),
+                    },
+                },
+            });
+        }
+
+        warn!(
+            "Reached the end of the feed for playlist '{playlist_id}' without finding a known video, older uploads may be missing."
+        );
+
+        Ok(items)
+    }
+
+    pub async fn get_video_details(&self, video_id: &str) -> anyhow::Result<VideosItem> {
+        let endpoint = format!("{}/watch?v={}", self.base_url, video_id);
+
+        let res = self
+            .client
+            .get(&endpoint)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        scrape_watch_page(video_id, &res.text().await?)
+    }
+}
+
+/// Pulls the title, description and duration out of the player response embedded in a watch page.
+fn scrape_watch_page(video_id: &str, html: &str) -> anyhow::Result<VideosItem> {
+    let capture = |regex: &Regex, field: &str| {
+        regex
+            .captures(html)
+            .and_then(|c| c.get(1))
+            .map(|m| m.as_str())
+            .with_context(|| format!("Failed to find {field} in watch page for '{video_id}'"))
+    };
+
+    // Captured strings are still JSON escaped.
+    let unescape = |raw: &str| serde_json::from_str::<CompactString>(&format!("\"{raw}\""));
+
+    let title = unescape(capture(&TITLE_REGEX, "title")?)?;
+    let description = unescape(capture(&DESCRIPTION_REGEX, "description")?)?;
+    let duration = capture(&LENGTH_REGEX, "length")?.parse::<i32>()?;
+
+    Ok(VideosItem {
+        id: video_id.to_compact_string(),
+        snippet: videos::Snippet {
+            title,
+            description,
+            published_at: CompactString::default(),
+        },
+        content_details: ContentDetails { duration },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrape_details() {
+        let html = r#"<script>var ytInitialPlayerResponse = {"playabilityStatus":{"status":"OK"},"videoDetails":{"videoId":"vidPKA703","title":"PKA 703 - Woody, Kyle & Taylor","lengthSeconds":"9900","keywords":["pka"],"channelId":"UCIPVJoHb_A5S3kcv3TJlyEg","shortDescription":"Timeline:\n0:00 - Intro\n12:30 - Woody bought a \"boat\"","isCrawlable":true}};</script>"#;
+
+        let details = scrape_watch_page("vidPKA703", html).unwrap();
+
+        assert_eq!(details.snippet.title, "PKA 703 - Woody, Kyle & Taylor");
+        assert_eq!(
+            details.snippet.description,
+            "Timeline:\n0:00 - Intro\n12:30 - Woody bought a \"boat\""
+        );
+        assert_eq!(details.content_details.duration, 9900);
+    }
+
+    #[test]
+    fn scrape_missing_player_response() {
+        assert!(
+            scrape_watch_page("vidPKA703", "<html>Sign in to confirm your age</html>").is_err()
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use tokio::time::{self, Duration, Instant};
+use tracing::{error, info};
+
+use crate::updater::drift::detect_drift;
+use crate::updater::source::YoutubeSource;
+use crate::Repo;
+
+const DRIFT_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60 * 24);
+
+pub async fn youtube_drift(state: Arc<Repo>, youtube: Arc<YoutubeSource>) {
+    let Some(yt_api) = youtube.api() else {
+        info!("No YouTube API key configured, drift detection is disabled.");
+        return;
+    };
+
+    // Skip the immediate first tick so restarts don't spend quota on a fresh check.
+    let mut ticker = time::interval_at(Instant::now() + DRIFT_CHECK_INTERVAL, DRIFT_CHECK_INTERVAL);
+
+    loop {
+        ticker.tick().await;
+
+        if let Err(e) = detect_drift(&state, yt_api).await {
+            error!("youtube_drift_worker error: {:#}", e);
+        }
+    }
+}
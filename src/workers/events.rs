@@ -4,6 +4,7 @@ use tokio::time::{self, Duration};
 use tracing::{error, info};
 
 use crate::conduit::sqlite::pka_event;
+use crate::models::show::DEFAULT_SHOW;
 use crate::Repo;
 use crate::PKA_EVENTS_INDEX;
 
@@ -15,11 +16,16 @@ pub async fn update_events(state: Arc<Repo>) {
 
         info!("Updating all events...");
 
-        match pka_event::all(&state).await {
-            Ok(events) => {
-                *PKA_EVENTS_INDEX.write().await = events.into_boxed_slice();
-            }
-            Err(e) => error!("get_latest_worker error: {:#}", e),
+        if let Err(e) = refresh_events_index(&state).await {
+            error!("get_latest_worker error: {:#}", e);
         }
     }
 }
+
+pub async fn refresh_events_index(state: &Repo) -> Result<(), sqlx::Error> {
+    let events = pka_event::for_show(state, DEFAULT_SHOW).await?;
+
+    *PKA_EVENTS_INDEX.write().await = events.into_boxed_slice();
+
+    Ok(())
+}
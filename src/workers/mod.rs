@@ -1,2 +1,3 @@
+pub mod drift;
 pub mod events;
 pub mod new_episode;
@@ -3,19 +3,37 @@ use std::sync::Arc;
 use tokio::time::{self, Duration};
 use tracing::{error, info};
 
+use crate::conduit::redis::stats_cache;
+use crate::redis_db::RedisDb;
 use crate::updater::pka::load_new_episodes;
+use crate::updater::source::YoutubeSource;
+use crate::updater::youtube_api::error::YoutubeApiError;
 use crate::Repo;
 
-pub async fn latest_episode(state: Arc<Repo>) {
+pub async fn latest_episode(state: Arc<Repo>, redis: Arc<RedisDb>, youtube: Arc<YoutubeSource>) {
     let mut ticker = time::interval(Duration::from_secs(300));
 
     loop {
         ticker.tick().await;
 
-        if let Err(e) = load_new_episodes(&state).await {
-            error!("get_latest_worker error: {:#}", e);
-        } else {
-            info!("Successfully finished looking for latest episodes.");
+        match load_new_episodes(&state, youtube.as_ref()).await {
+            Ok(episodes_added) => {
+                info!("Successfully finished looking for latest episodes.");
+
+                if episodes_added > 0 {
+                    if let Err(e) = stats_cache::invalidate(&redis).await {
+                        error!("Failed to invalidate stats cache: {}", e);
+                    }
+                }
+            }
+            Err(e) => {
+                error!("get_latest_worker error: {:#}", e);
+
+                // The API isn't called again until the reset, but the feed keeps being checked.
+                if let Some(resets_at) = YoutubeApiError::quota_reset(&e) {
+                    info!("YouTube quota exhausted until {resets_at}, using the feed until then.");
+                }
+            }
         }
     }
 }